@@ -0,0 +1,339 @@
+use anyhow::{anyhow, Context, Result};
+use crate::security::{AlertCategory, SecurityAlert};
+use crate::state;
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::net::IpAddr;
+
+const ALERTS_FILE: &str = "alerts.json";
+
+/// Alerts not seen for this long are forgotten
+const RETENTION_DAYS: i64 = 30;
+
+/// Shortest id prefix accepted on the command line
+const MIN_ID_PREFIX: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertStatus {
+    Open,
+    Acknowledged,
+    Resolved,
+}
+
+impl std::fmt::Display for AlertStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertStatus::Open => write!(f, "🔔 open"),
+            AlertStatus::Acknowledged => write!(f, "👀 acked"),
+            AlertStatus::Resolved => write!(f, "✅ resolved"),
+        }
+    }
+}
+
+/// Everything known about one alert across scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRecord {
+    pub fingerprint: String,
+    /// The alert as last seen; its timestamp is when it was first (or last re-) opened
+    pub alert: SecurityAlert,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Number of scans that found it
+    pub occurrences: u64,
+    pub status: AlertStatus,
+    pub status_changed: Option<DateTime<Utc>>,
+    /// Whether the latest scan still found it
+    pub present: bool,
+}
+
+impl AlertRecord {
+    pub fn id(&self) -> &str {
+        &self.fingerprint[..8]
+    }
+}
+
+/// Hides matching alerts until it expires; every criterion that is set must match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suppression {
+    pub id: u32,
+    pub category: Option<AlertCategory>,
+    pub source_ip: Option<IpAddr>,
+    /// Regex matched against the message and details
+    pub pattern: Option<String>,
+    pub reason: Option<String>,
+    pub created: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    /// Alerts hidden so far
+    pub hits: u64,
+}
+
+impl Suppression {
+    pub fn matches(&self, alert: &SecurityAlert) -> bool {
+        let category = self.category.as_ref().map(|c| c.label() == alert.category.label()).unwrap_or(true);
+        let source_ip = self.source_ip.map(|ip| alert.source_ips().contains(&ip)).unwrap_or(true);
+        let pattern = match &self.pattern {
+            Some(pattern) => Regex::new(pattern)
+                .map(|re| re.is_match(&alert.message) || alert.details.as_deref().map(|d| re.is_match(d)).unwrap_or(false))
+                .unwrap_or(false),
+            None => true,
+        };
+        category && source_ip && pattern
+    }
+
+    pub fn describe(&self) -> String {
+        let mut criteria = Vec::new();
+        if let Some(category) = &self.category {
+            criteria.push(format!("category {}", category.label()));
+        }
+        if let Some(ip) = self.source_ip {
+            criteria.push(format!("source {}", ip));
+        }
+        if let Some(pattern) = &self.pattern {
+            criteria.push(format!("matching /{}/", pattern));
+        }
+        criteria.join(", ")
+    }
+}
+
+/// Persistent alert records and suppression rules
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertState {
+    pub records: BTreeMap<String, AlertRecord>,
+    pub suppressions: Vec<Suppression>,
+    next_suppression_id: u32,
+}
+
+/// The outcome of recording one scan
+#[derive(Debug, Clone, Default)]
+pub struct Tracked {
+    /// Open alerts the scan found, newest first, timestamped when they were opened
+    pub active: Vec<SecurityAlert>,
+    /// Ids of alerts this scan found for the first time, or again after they had gone
+    pub new: Vec<String>,
+}
+
+impl AlertState {
+    pub fn load() -> Result<Self> {
+        Ok(state::load(ALERTS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        state::save(ALERTS_FILE, self)
+    }
+
+    /// Merge a scan's alerts into the records
+    pub fn observe(&mut self, alerts: Vec<SecurityAlert>, now: DateTime<Utc>) -> Tracked {
+        self.suppressions.retain(|s| s.expires > now);
+        let mut tracked = Tracked::default();
+        let mut seen = HashSet::new();
+
+        for alert in alerts {
+            if let Some(suppression) = self.suppressions.iter_mut().find(|s| s.matches(&alert)) {
+                suppression.hits += 1;
+                continue;
+            }
+            let fingerprint = alert.fingerprint();
+            if !seen.insert(fingerprint.clone()) {
+                continue;
+            }
+
+            match self.records.get_mut(&fingerprint) {
+                Some(record) => {
+                    // Acknowledging or resolving covers one occurrence; coming back after going away reopens it
+                    if record.status != AlertStatus::Open && !record.present {
+                        record.status = AlertStatus::Open;
+                        record.status_changed = Some(now);
+                        record.alert.timestamp = now;
+                        tracked.new.push(record.id().to_string());
+                    }
+                    // Keep the latest wording, such as a count, but not the time it was opened
+                    record.alert = SecurityAlert { timestamp: record.alert.timestamp, ..alert };
+                    record.last_seen = now;
+                    record.occurrences += 1;
+                    record.present = true;
+                }
+                None => {
+                    let record = AlertRecord {
+                        fingerprint: fingerprint.clone(),
+                        alert: SecurityAlert { timestamp: now, ..alert },
+                        first_seen: now,
+                        last_seen: now,
+                        occurrences: 1,
+                        status: AlertStatus::Open,
+                        status_changed: None,
+                        present: true,
+                    };
+                    tracked.new.push(record.id().to_string());
+                    self.records.insert(fingerprint, record);
+                }
+            }
+        }
+
+        for (fingerprint, record) in self.records.iter_mut() {
+            if !seen.contains(fingerprint) {
+                record.present = false;
+            }
+        }
+        self.records.retain(|_, r| now - r.last_seen < Duration::days(RETENTION_DAYS));
+
+        tracked.active = self.active();
+        tracked
+    }
+
+    /// Open alerts present in the latest scan, newest first, timestamped when they were opened
+    pub fn active(&self) -> Vec<SecurityAlert> {
+        let mut active: Vec<&AlertRecord> = self
+            .records
+            .values()
+            .filter(|r| r.present && r.status == AlertStatus::Open)
+            .collect();
+        active.sort_by_key(|r| std::cmp::Reverse(r.alert.timestamp));
+        active.into_iter().map(|r| r.alert.clone()).collect()
+    }
+
+    /// The record whose id starts with `prefix`, which must be unambiguous
+    fn find(&mut self, prefix: &str) -> Result<&mut AlertRecord> {
+        if prefix.len() < MIN_ID_PREFIX {
+            return Err(anyhow!("Alert id '{}' is too short (use at least {} characters)", prefix, MIN_ID_PREFIX));
+        }
+        let prefix = prefix.to_ascii_lowercase();
+        let matches: Vec<&String> = self.records.keys().filter(|f| f.starts_with(&prefix)).collect();
+        match matches.as_slice() {
+            [fingerprint] => {
+                let fingerprint = (*fingerprint).clone();
+                Ok(self.records.get_mut(&fingerprint).expect("fingerprint was just found"))
+            }
+            [] => Err(anyhow!("No alert with id '{}'", prefix)),
+            _ => Err(anyhow!("Alert id '{}' is ambiguous", prefix)),
+        }
+    }
+}
+
+/// Record a scan's alerts in the persistent state
+pub fn track(alerts: Vec<SecurityAlert>) -> Result<Tracked> {
+    let mut state = AlertState::load().context("Failed to load alert state")?;
+    let tracked = state.observe(alerts, Utc::now());
+    state.save()?;
+    Ok(tracked)
+}
+
+/// List tracked alerts and active suppressions
+pub async fn show_alerts(all: bool) -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("                 🛡️  NetNinja Alert Tracker  🛡️            ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let state = AlertState::load()?;
+    let now = Utc::now();
+    let mut records: Vec<&AlertRecord> = state
+        .records
+        .values()
+        .filter(|r| all || (r.present && r.status != AlertStatus::Resolved))
+        .collect();
+    records.sort_by_key(|r| std::cmp::Reverse(r.last_seen));
+
+    if records.is_empty() {
+        println!("✅ No {}alerts\n", if all { "" } else { "current " });
+    } else {
+        println!("{:<10} {:<12} {:<10} {:<18} {:<6} {:<17} Message", "ID", "Status", "Severity", "Category", "Seen", "First Seen");
+        println!("{}", "─".repeat(100));
+        for record in &records {
+            let gone = if record.present { "" } else { " (gone)" };
+            println!(
+                "{:<10} {:<12} {:<10} {:<18} {:<6} {:<17} {}{}",
+                record.id(),
+                record.status.to_string(),
+                record.alert.severity.to_string(),
+                record.alert.category.label(),
+                record.occurrences,
+                record.first_seen.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                record.alert.message,
+                gone
+            );
+        }
+        println!();
+    }
+
+    let active: Vec<&Suppression> = state.suppressions.iter().filter(|s| s.expires > now).collect();
+    if !active.is_empty() {
+        println!("🔇 Suppressions:");
+        for suppression in active {
+            let reason = suppression.reason.as_deref().map(|r| format!(" — {}", r)).unwrap_or_default();
+            println!(
+                "  #{} {} until {} ({} hidden){}",
+                suppression.id,
+                suppression.describe(),
+                suppression.expires.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                suppression.hits,
+                reason
+            );
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Acknowledge or resolve alerts by id
+pub async fn set_status(ids: &[String], status: AlertStatus) -> Result<()> {
+    let mut state = AlertState::load()?;
+    for id in ids {
+        let record = state.find(id)?;
+        record.status = status;
+        record.status_changed = Some(Utc::now());
+        println!("{} {}: {}", record.status, record.id(), record.alert.message);
+    }
+    state.save()
+}
+
+/// Hide alerts matching the given criteria for a while
+pub async fn suppress(
+    category: Option<AlertCategory>,
+    source_ip: Option<IpAddr>,
+    pattern: Option<String>,
+    duration: Duration,
+    reason: Option<String>,
+) -> Result<()> {
+    if category.is_none() && source_ip.is_none() && pattern.is_none() {
+        return Err(anyhow!("Give at least one of --category, --source-ip or --matching"));
+    }
+    if let Some(pattern) = &pattern {
+        Regex::new(pattern).with_context(|| format!("Invalid regex '{}'", pattern))?;
+    }
+
+    let now = Utc::now();
+    let expires = now
+        .checked_add_signed(duration)
+        .ok_or_else(|| anyhow!("Suppression duration is too long"))?;
+    let mut state = AlertState::load()?;
+    state.next_suppression_id += 1;
+    let suppression = Suppression {
+        id: state.next_suppression_id,
+        category,
+        source_ip,
+        pattern,
+        reason,
+        created: now,
+        expires,
+        hits: 0,
+    };
+
+    let covered = state
+        .records
+        .values()
+        .filter(|r| r.present && r.status == AlertStatus::Open && suppression.matches(&r.alert))
+        .count();
+
+    println!(
+        "🔇 Suppressing {} until {} (#{}, covers {} open alert(s) from the next scan)",
+        suppression.describe(),
+        suppression.expires.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+        suppression.id,
+        covered
+    );
+    state.suppressions.push(suppression);
+    state.save()
+}
+
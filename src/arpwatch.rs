@@ -0,0 +1,149 @@
+use anyhow::Result;
+use crate::network::{self, NetworkPeer};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use crate::state;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+/// State file with the IP-to-MAC bindings seen on previous runs
+const ARP_STATE_FILE: &str = "arp-bindings.json";
+
+/// A MAC answering for at least this many IPv4 addresses is suspicious
+const MANY_IPS_THRESHOLD: usize = 4;
+
+/// A binding that changes MAC within this many minutes of last being seen is a conflict
+const FLAP_WINDOW_MINUTES: i64 = 60;
+
+/// Bindings not seen for this many days are forgotten, so a new owner of the address is not compared against them
+const BINDING_RETENTION_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Binding {
+    pub mac: String,
+    pub interface: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ArpState {
+    /// Keyed by "ip%interface" so the same address on two links stays separate
+    bindings: BTreeMap<String, Binding>,
+}
+
+fn binding_key(ip: &str, interface: &str) -> String {
+    format!("{}%{}", ip, interface)
+}
+
+/// Compare the neighbor table with previously seen bindings and flag spoofing signs
+pub fn check_arp_spoofing() -> Result<Vec<SecurityAlert>> {
+    let peers: Vec<NetworkPeer> = network::get_network_peers()?
+        .into_iter()
+        .filter(|p| p.mac.is_some())
+        .collect();
+    let gateways: BTreeSet<String> = network::get_default_gateways()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(ip, dev)| binding_key(&ip, &dev))
+        .collect();
+
+    let mut state: ArpState = state::load(ARP_STATE_FILE)?.unwrap_or_default();
+    let now = Utc::now();
+    state.bindings.retain(|_, b| now - b.last_seen < Duration::days(BINDING_RETENTION_DAYS));
+    let mut alerts = Vec::new();
+
+    // Two MACs answering for one IP: the binding flipped since it was last seen
+    let mut current: BTreeMap<String, &NetworkPeer> = BTreeMap::new();
+    for peer in &peers {
+        current.insert(binding_key(&peer.ip, &peer.interface), peer);
+    }
+    for (key, peer) in &current {
+        let mac = peer.mac.as_deref().unwrap_or_default();
+        let is_gateway = gateways.contains(key);
+
+        if let Some(previous) = state.bindings.get(key) {
+            if previous.mac != mac {
+                let recent = now - previous.last_seen < Duration::minutes(FLAP_WINDOW_MINUTES);
+                if is_gateway || recent {
+                    alerts.push(SecurityAlert {
+                        timestamp: now,
+                        severity: if is_gateway { AlertSeverity::Critical } else { AlertSeverity::High },
+                        category: AlertCategory::ArpSpoofing,
+                        message: if is_gateway {
+                            format!("Gateway MAC changed for {}: {} -> {}", key, previous.mac, mac)
+                        } else {
+                            format!("MAC for {} changed: {} -> {}", key, previous.mac, mac)
+                        },
+                        details: Some(format!(
+                            "old binding {} -> {} (first seen {}, last seen {}); new binding {} -> {}",
+                            key,
+                            previous.mac,
+                            previous.first_seen.format("%Y-%m-%d %H:%M:%S"),
+                            previous.last_seen.format("%Y-%m-%d %H:%M:%S"),
+                            key,
+                            mac
+                        )),
+                        subject: Some(format!("{} -> {}", key, mac)),
+                    });
+                }
+            }
+        }
+    }
+
+    // One MAC claiming many IPv4 addresses, or the gateway's IP plus another host's
+    let mut by_mac: BTreeMap<&str, Vec<&NetworkPeer>> = BTreeMap::new();
+    for peer in &peers {
+        if matches!(peer.ip.parse::<IpAddr>(), Ok(IpAddr::V4(_))) {
+            if let Some(mac) = &peer.mac {
+                by_mac.entry(mac.as_str()).or_default().push(peer);
+            }
+        }
+    }
+    for (mac, claimed) in &by_mac {
+        let ips: Vec<&str> = claimed.iter().map(|p| p.ip.as_str()).collect();
+        let claims_gateway = claimed
+            .iter()
+            .any(|p| gateways.contains(&binding_key(&p.ip, &p.interface)));
+
+        if claims_gateway && ips.len() > 1 {
+            alerts.push(SecurityAlert {
+                timestamp: now,
+                severity: AlertSeverity::Critical,
+                category: AlertCategory::ArpSpoofing,
+                message: format!("MAC {} answers for the gateway and {} other address(es)", mac, ips.len() - 1),
+                details: Some(format!("Bindings: {}", ips.iter().map(|ip| format!("{} -> {}", ip, mac)).collect::<Vec<_>>().join(", "))),
+                subject: Some(format!("{} claims the gateway", mac)),
+            });
+        } else if ips.len() >= MANY_IPS_THRESHOLD {
+            alerts.push(SecurityAlert {
+                timestamp: now,
+                severity: AlertSeverity::High,
+                category: AlertCategory::ArpSpoofing,
+                message: format!("MAC {} claims {} IPv4 addresses", mac, ips.len()),
+                details: Some(format!("Bindings: {}", ips.iter().map(|ip| format!("{} -> {}", ip, mac)).collect::<Vec<_>>().join(", "))),
+                subject: Some(format!("{} claims many addresses", mac)),
+            });
+        }
+    }
+
+    // Record the current bindings for the next run
+    for (key, peer) in &current {
+        let mac = peer.mac.clone().unwrap_or_default();
+        match state.bindings.get_mut(key) {
+            Some(binding) if binding.mac == mac => binding.last_seen = now,
+            _ => {
+                state.bindings.insert(key.clone(), Binding {
+                    mac,
+                    interface: peer.interface.clone(),
+                    first_seen: now,
+                    last_seen: now,
+                });
+            }
+        }
+    }
+    state::save(ARP_STATE_FILE, &state)?;
+
+    Ok(alerts)
+}
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use crate::{firewall, network, state};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Command;
+
+/// State file holding the snapshot taken on the previous run
+const BASELINE_STATE_FILE: &str = "system-baseline.json";
+
+/// Sections collected from commands that may fail without privileges
+const COMMAND_SECTIONS: &[&str] = &["interfaces", "routes", "listening ports", "firewall rules"];
+
+/// Maximum changed lines listed in a single alert's details
+const MAX_DETAIL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// Normalized lines per section, e.g. "routes" or "authorized_keys:/root/.ssh/authorized_keys"
+    pub sections: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionChange {
+    pub section: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Capture the network-relevant state of the host
+pub fn take_snapshot() -> SystemSnapshot {
+    let mut sections = BTreeMap::new();
+
+    if let Ok(interfaces) = network::get_interfaces() {
+        let mut lines = Vec::new();
+        for iface in interfaces {
+            lines.push(format!("{} state {}", iface.name, if iface.is_up { "UP" } else { "DOWN" }));
+            if let Some(mac) = &iface.mac_address {
+                lines.push(format!("{} mac {}", iface.name, mac));
+            }
+            for ip in &iface.ip_addresses {
+                lines.push(format!("{} addr {}", iface.name, ip));
+            }
+        }
+        sections.insert("interfaces".to_string(), lines);
+    }
+
+    let mut routes = Vec::new();
+    for family in ["-4", "-6"] {
+        if let Ok(output) = Command::new("ip").args([family, "route", "show"]).output() {
+            routes.extend(String::from_utf8_lossy(&output.stdout).lines().map(normalize_route));
+        }
+    }
+    sections.insert("routes".to_string(), routes);
+
+    if let Some(lines) = read_config_lines("/etc/resolv.conf") {
+        sections.insert("resolv.conf".to_string(), lines);
+    }
+    if let Some(lines) = read_config_lines("/etc/hosts") {
+        sections.insert("hosts".to_string(), lines);
+    }
+
+    if let Ok(sockets) = network::get_listening_sockets() {
+        let lines = sockets
+            .iter()
+            .map(|s| format!("{} {}:{} ({})", s.protocol, s.address, s.port, s.process.as_deref().unwrap_or("unknown")))
+            .collect();
+        sections.insert("listening ports".to_string(), lines);
+    }
+
+    if let Ok(ruleset) = firewall::read_ruleset() {
+        let mut lines: Vec<String> = ruleset
+            .chains
+            .iter()
+            .filter_map(|c| c.policy.as_ref().map(|p| format!("policy {} {} {} {}", c.family, c.table, c.name, p)))
+            .collect();
+        lines.extend(ruleset.rules.iter().map(|r| r.text.clone()));
+        sections.insert("firewall rules".to_string(), lines);
+    }
+
+    for path in authorized_keys_files() {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            let keys = content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(summarize_key)
+                .collect();
+            sections.insert(format!("authorized_keys:{}", path), keys);
+        }
+    }
+
+    SystemSnapshot {
+        taken_at: Utc::now(),
+        sections,
+    }
+}
+
+/// Drop fields that change on their own, like RA route lifetimes
+fn normalize_route(line: &str) -> String {
+    let mut tokens = Vec::new();
+    let mut skip_next = false;
+    for token in line.split_whitespace() {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if token == "expires" {
+            skip_next = true;
+            continue;
+        }
+        tokens.push(token);
+    }
+    tokens.join(" ")
+}
+
+fn read_config_lines(path: &str) -> Option<Vec<String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(
+        content
+            .lines()
+            .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect(),
+    )
+}
+
+/// authorized_keys files for root and every home directory
+fn authorized_keys_files() -> Vec<String> {
+    let mut homes = vec!["/root".to_string()];
+    if let Ok(entries) = std::fs::read_dir("/home") {
+        homes.extend(entries.flatten().map(|e| e.path().to_string_lossy().to_string()));
+    }
+
+    homes
+        .iter()
+        .flat_map(|home| {
+            ["authorized_keys", "authorized_keys2"]
+                .iter()
+                .map(move |name| format!("{}/.ssh/{}", home, name))
+        })
+        .filter(|path| std::path::Path::new(path).exists())
+        .collect()
+}
+
+/// Whether a file certainly does not exist; an unreadable directory on the way only hides it
+fn is_absent(path: &str) -> bool {
+    matches!(std::fs::metadata(path), Err(e) if e.kind() == std::io::ErrorKind::NotFound)
+}
+
+/// Keep options, key type, the tail of the key blob and the comment
+fn summarize_key(line: &str) -> String {
+    line.split_whitespace()
+        .map(|token| {
+            // Base64 key blobs all start with "AAAA"
+            if token.starts_with("AAAA") && token.len() > 32 {
+                // The last 16 characters, cut at a char boundary in case the token is not ASCII
+                let start = token.char_indices().rev().nth(15).map_or(0, |(i, _)| i);
+                format!("…{}", &token[start..])
+            } else {
+                token.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Compare two snapshots section by section
+pub fn diff_snapshots(previous: &SystemSnapshot, current: &SystemSnapshot) -> Vec<SectionChange> {
+    let names: BTreeSet<&String> = previous.sections.keys().chain(current.sections.keys()).collect();
+    let empty = Vec::new();
+    let mut changes = Vec::new();
+
+    for name in names {
+        // A command that could not run this time is not a change
+        let collected_both = previous.sections.contains_key(name) && current.sections.contains_key(name);
+        if COMMAND_SECTIONS.contains(&name.as_str()) && !collected_both {
+            continue;
+        }
+
+        let old: BTreeSet<&String> = previous.sections.get(name).unwrap_or(&empty).iter().collect();
+        let new: BTreeSet<&String> = current.sections.get(name).unwrap_or(&empty).iter().collect();
+
+        let added: Vec<String> = new.difference(&old).map(|s| s.to_string()).collect();
+        let removed: Vec<String> = old.difference(&new).map(|s| s.to_string()).collect();
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(SectionChange {
+                section: name.clone(),
+                added,
+                removed,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Snapshot the system, compare with the previous run and raise SystemChange alerts
+pub fn check_system_changes() -> Result<Vec<SecurityAlert>> {
+    let previous: Option<SystemSnapshot> = state::load(BASELINE_STATE_FILE)?;
+    let mut current = take_snapshot();
+
+    // Keep the last known state of sections that could not be collected this time
+    if let Some(previous) = &previous {
+        for name in COMMAND_SECTIONS {
+            if let (false, Some(lines)) = (current.sections.contains_key(*name), previous.sections.get(*name)) {
+                current.sections.insert(name.to_string(), lines.clone());
+            }
+        }
+        // A key file missing from this snapshot was only removed if it is really gone, not unreadable
+        for (name, lines) in &previous.sections {
+            let Some(path) = name.strip_prefix("authorized_keys:") else {
+                continue;
+            };
+            if !current.sections.contains_key(name) && !is_absent(path) {
+                current.sections.insert(name.clone(), lines.clone());
+            }
+        }
+    }
+    state::save(BASELINE_STATE_FILE, &current)?;
+
+    // The first run only records the baseline
+    let Some(previous) = previous else {
+        return Ok(Vec::new());
+    };
+
+    let alerts = diff_snapshots(&previous, &current)
+        .into_iter()
+        .map(|change| {
+            let severity = if change.section.starts_with("authorized_keys") {
+                AlertSeverity::High
+            } else {
+                match change.section.as_str() {
+                    "firewall rules" | "resolv.conf" | "hosts" | "routes" => AlertSeverity::Medium,
+                    _ => AlertSeverity::Low,
+                }
+            };
+
+            let mut lines: Vec<String> = change
+                .removed
+                .iter()
+                .map(|l| format!("- {}", l))
+                .chain(change.added.iter().map(|l| format!("+ {}", l)))
+                .collect();
+            if lines.len() > MAX_DETAIL_LINES {
+                let more = lines.len() - MAX_DETAIL_LINES;
+                lines.truncate(MAX_DETAIL_LINES);
+                lines.push(format!("… and {} more", more));
+            }
+
+            SecurityAlert {
+                timestamp: Utc::now(),
+                severity,
+                category: AlertCategory::SystemChange,
+                message: format!(
+                    "{} changed since {}: {} added, {} removed",
+                    change.section,
+                    previous.taken_at.format("%Y-%m-%d %H:%M:%S"),
+                    change.added.len(),
+                    change.removed.len()
+                ),
+                details: Some(lines.join("\n")),
+                // Each change is reported once, so the change itself is the identity
+                subject: None,
+            }
+        })
+        .collect();
+
+    Ok(alerts)
+}
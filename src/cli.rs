@@ -7,6 +7,10 @@ use clap::{Parser, Subcommand};
 pub struct Args {
     #[command(subcommand)]
     pub command: Option<Commands>,
+    
+    /// Run inside another network namespace, by name (/run/netns) or PID
+    #[arg(long, global = true, value_name = "NAME|PID")]
+    pub netns: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -15,5 +19,287 @@ pub enum Commands {
     Monitor,
     
     /// Show quick network status summary
-    Status,
+    Status {
+        /// Print the snapshot as JSON
+        #[arg(long)]
+        json: bool,
+        
+        /// Only show one section of the report
+        #[arg(long, value_enum)]
+        section: Option<crate::monitor::Section>,
+    },
+    
+    /// Run collectors in the background and serve snapshots over a unix socket
+    Daemon,
+    
+    /// Show recorded metrics over time (run the daemon to record them)
+    History {
+        /// Metric to show, e.g. connections, peers, alerts, rx.eth0 or ping (all if omitted)
+        metric: Option<String>,
+        
+        /// How far back to look (e.g. 30m, 6h, 7d)
+        #[arg(long, value_parser = parse_duration, default_value = "6h")]
+        since: chrono::Duration,
+        
+        /// Print a table of values instead of a sparkline
+        #[arg(long)]
+        table: bool,
+    },
+    
+    /// Serve interface, VPN, port, neighbor, alert and firewall metrics to Prometheus
+    Exporter {
+        /// Address to serve /metrics on
+        #[arg(long, default_value = "127.0.0.1:9717")]
+        listen: std::net::SocketAddr,
+    },
+    
+    /// Track, acknowledge and suppress alerts, and test notifications
+    Alerts {
+        #[command(subcommand)]
+        action: AlertCommands,
+    },
+    
+    /// List and try out the user-defined detection rules
+    Rules {
+        #[command(subcommand)]
+        action: RuleCommands,
+    },
+    
+    /// List and try out Sigma rules for auth, syslog and netfilter logs
+    Sigma {
+        #[command(subcommand)]
+        action: SigmaCommands,
+    },
+    
+    /// Show threat-intel blocklists and look addresses up in them
+    Intel {
+        #[command(subcommand)]
+        action: IntelCommands,
+    },
+    
+    /// Show interfaces with their bridge, bond, VLAN and veth relationships
+    Interfaces {
+        /// Nest ports under bridges and bonds, and VLANs under their parent
+        #[arg(long)]
+        tree: bool,
+        
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Inspect the firewall ruleset and listening ports it exposes
+    Firewall,
+    
+    /// Show which listening services are reachable from untrusted networks
+    Exposure,
+    
+    /// Probe for DHCP servers and detect rogue ones
+    Dhcp {
+        /// Interface to send the DHCPDISCOVER on (defaults to the primary interface)
+        #[arg(short, long)]
+        interface: Option<String>,
+        
+        /// Seconds to wait for offers
+        #[arg(short, long, default_value_t = 5)]
+        timeout: u64,
+    },
+    
+    /// Passively fingerprint hosts from DHCP, TCP SYN, mDNS, SSDP and NetBIOS traffic
+    Fingerprint {
+        /// Interface to listen on (defaults to the primary interface)
+        #[arg(short, long)]
+        interface: Option<String>,
+        
+        /// Seconds to capture for
+        #[arg(short, long, default_value_t = 30)]
+        duration: u64,
+    },
+    
+    /// Find live hosts with an ARP sweep and IPv6 neighbor probes
+    Discover {
+        /// Interface to probe (defaults to the primary interface)
+        #[arg(short, long)]
+        interface: Option<String>,
+        
+        /// Seconds to wait for replies after probing
+        #[arg(short, long, default_value_t = 3)]
+        timeout: u64,
+    },
+    
+    /// List services advertised over mDNS/DNS-SD and SSDP/UPnP
+    Services {
+        /// Seconds to browse for
+        #[arg(short, long, default_value_t = 5)]
+        duration: u64,
+    },
+    
+    /// List every device ever seen on the network
+    Peers {
+        /// Only show devices first seen within this window (e.g. 30m, 24h, 7d)
+        #[arg(long, value_parser = parse_duration)]
+        new_since: Option<chrono::Duration>,
+        
+        #[command(subcommand)]
+        action: Option<PeerCommands>,
+    },
+    
+    /// Show Wi-Fi link quality and scan nearby networks
+    Wifi {
+        /// Wireless interface (defaults to the primary interface if it is wireless)
+        #[arg(short, long)]
+        interface: Option<String>,
+        
+        /// Scan for nearby networks and summarise channel congestion
+        #[arg(short, long)]
+        scan: bool,
+    },
+    
+    /// Show Docker/Podman containers with their veths, bridges, IPs and published ports
+    Containers,
+    
+    /// List network namespaces and the interfaces inside them
+    Netns,
+    
+    /// VPN diagnostics
+    Vpn {
+        #[command(subcommand)]
+        action: VpnCommands,
+    },
+    
+    /// Look up MAC vendors and manage the IEEE OUI registry
+    Oui {
+        #[command(subcommand)]
+        action: OuiCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OuiCommands {
+    /// Download the IEEE MA-L/MA-M/MA-S registries into the data directory
+    Update,
+    
+    /// Show the vendor registered for a MAC address
+    Lookup {
+        /// MAC address in any common notation
+        mac: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RuleCommands {
+    /// Show the rules loaded from the rules file
+    List,
+    
+    /// Evaluate every rule now and show what it finds
+    Run,
+}
+
+#[derive(Subcommand)]
+pub enum SigmaCommands {
+    /// Show the Sigma rules that were loaded, and why others were skipped
+    List,
+    
+    /// Evaluate every Sigma rule now and show the events it matches
+    Run,
+}
+
+#[derive(Subcommand)]
+pub enum IntelCommands {
+    /// Show each blocklist with its size and last update
+    List,
+    
+    /// Look addresses up in every blocklist
+    Check {
+        #[arg(required = true)]
+        ips: Vec<std::net::IpAddr>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AlertCommands {
+    /// List current alerts and active suppressions
+    List {
+        /// Include acknowledged, resolved and no longer detected alerts
+        #[arg(long)]
+        all: bool,
+    },
+    
+    /// Acknowledge alerts so they leave the status report until they recur
+    Ack {
+        /// Alert ids (or unique prefixes) from `alerts list`
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    
+    /// Mark alerts as resolved
+    Resolve {
+        /// Alert ids (or unique prefixes) from `alerts list`
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    
+    /// Hide alerts matching a category, source IP or regex for a while
+    Suppress {
+        /// Alert category, e.g. failed_login or new_device
+        #[arg(long)]
+        category: Option<crate::security::AlertCategory>,
+        
+        /// IP address mentioned in the alert
+        #[arg(long)]
+        source_ip: Option<std::net::IpAddr>,
+        
+        /// Regex matched against the alert message and details
+        #[arg(long, value_name = "REGEX")]
+        matching: Option<String>,
+        
+        /// How long the suppression lasts (e.g. 30m, 24h, 7d)
+        #[arg(long = "for", value_parser = parse_duration, default_value = "24h")]
+        duration: chrono::Duration,
+        
+        /// Why the alerts are suppressed
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    
+    /// Send a test alert to every configured notification sink
+    Test,
+}
+
+#[derive(Subcommand)]
+pub enum VpnCommands {
+    /// Check that routes, DNS and UDP traffic actually use the VPN tunnel
+    Check,
+}
+
+#[derive(Subcommand)]
+pub enum PeerCommands {
+    /// Give a device a friendly name (omit the label to clear it)
+    Label {
+        /// MAC address of the device
+        mac: String,
+        
+        /// Name to show instead of the vendor
+        label: Option<String>,
+    },
+}
+
+/// Parse durations like "90s", "30m", "24h", "7d" or "2w"
+pub fn parse_duration(value: &str) -> Result<chrono::Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: i64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", value))?;
+    
+    let duration = match unit {
+        "s" => chrono::Duration::try_seconds(number),
+        "m" => chrono::Duration::try_minutes(number),
+        "h" | "" => chrono::Duration::try_hours(number),
+        "d" => chrono::Duration::try_days(number),
+        "w" => chrono::Duration::try_weeks(number),
+        _ => return Err(format!("invalid duration unit '{}' (use s, m, h, d or w)", unit)),
+    };
+    duration.ok_or_else(|| format!("duration '{}' is too long", value))
 }
@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use crate::cli::parse_duration;
+use crate::security::{AlertCategory, AlertSeverity};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// `$XDG_CONFIG_HOME/netninja` or `~/.config/netninja`
+fn config_dir() -> Result<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var_os("HOME").context("HOME is not set")?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Ok(base.join("netninja"))
+}
+
+/// Config file: `$NETNINJA_CONFIG`, else `config.toml` in the config directory
+pub fn config_path() -> Result<PathBuf> {
+    match std::env::var_os("NETNINJA_CONFIG") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(config_dir()?.join("config.toml")),
+    }
+}
+
+/// Detection rules: `$NETNINJA_RULES`, else `rules.toml` in the config directory
+pub fn rules_path() -> Result<PathBuf> {
+    match std::env::var_os("NETNINJA_RULES") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(config_dir()?.join("rules.toml")),
+    }
+}
+
+/// Sigma rules: `$NETNINJA_SIGMA`, else the `sigma` directory in the config directory
+pub fn sigma_path() -> Result<PathBuf> {
+    match std::env::var_os("NETNINJA_SIGMA") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(config_dir()?.join("sigma")),
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub alerts: AlertsConfig,
+    pub threat_intel: ThreatIntelConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsConfig {
+    pub sinks: Vec<SinkConfig>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThreatIntelConfig {
+    pub lists: Vec<BlocklistConfig>,
+}
+
+/// A local IP/CIDR blocklist file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlocklistConfig {
+    /// Shown in alerts; defaults to the file name without its extension
+    pub name: Option<String>,
+    pub path: PathBuf,
+    /// Severity of alerts for addresses on this list
+    #[serde(default = "default_blocklist_severity", deserialize_with = "severity")]
+    pub severity: AlertSeverity,
+}
+
+/// One notification target with its filters and limits
+#[derive(Debug, Clone, Deserialize)]
+pub struct SinkConfig {
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub kind: SinkKind,
+    /// Least severe alert this sink is notified about
+    #[serde(default = "default_min_severity", deserialize_with = "severity")]
+    pub min_severity: AlertSeverity,
+    /// Only these categories, or every category when empty
+    #[serde(default, deserialize_with = "categories")]
+    pub categories: Vec<AlertCategory>,
+    /// Identical alerts within this window are notified once
+    #[serde(default = "default_dedup_window", deserialize_with = "duration")]
+    pub dedup_window: chrono::Duration,
+    /// At most this many notifications per `rate_window`
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit: usize,
+    #[serde(default = "default_rate_window", deserialize_with = "duration")]
+    pub rate_window: chrono::Duration,
+}
+
+impl SinkConfig {
+    /// Configured name, or the sink type and its target
+    pub fn label(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        match &self.kind {
+            SinkKind::Webhook { url, .. } => format!("webhook {}", url),
+            SinkKind::Syslog { address: Some(address), .. } => format!("syslog {}", address),
+            SinkKind::Syslog { address: None, .. } => "syslog /dev/log".to_string(),
+            SinkKind::Email { to, .. } => format!("email {}", to.join(", ")),
+            SinkKind::Command { command } => format!("command {}", command),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkKind {
+    /// POST each alert as JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// RFC 5424 messages to a remote collector, or to /dev/log without an address
+    Syslog {
+        address: Option<String>,
+        #[serde(default)]
+        protocol: SyslogProtocol,
+        #[serde(default = "default_facility", deserialize_with = "facility")]
+        facility: u8,
+    },
+    /// Plain SMTP through a relay such as a local MTA
+    Email {
+        server: String,
+        from: String,
+        to: Vec<String>,
+    },
+    /// Run a shell command with the alert as JSON on stdin
+    Command { command: String },
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogProtocol {
+    #[default]
+    Udp,
+    Tcp,
+}
+
+fn default_min_severity() -> AlertSeverity {
+    AlertSeverity::Info
+}
+
+fn default_blocklist_severity() -> AlertSeverity {
+    AlertSeverity::High
+}
+
+fn default_dedup_window() -> chrono::Duration {
+    chrono::Duration::minutes(10)
+}
+
+fn default_rate_limit() -> usize {
+    10
+}
+
+fn default_rate_window() -> chrono::Duration {
+    chrono::Duration::minutes(1)
+}
+
+/// The "daemon" facility
+fn default_facility() -> u8 {
+    3
+}
+
+pub fn severity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AlertSeverity, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+}
+
+pub fn category<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AlertCategory, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+}
+
+fn categories<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<AlertCategory>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|c| c.parse().map_err(D::Error::custom))
+        .collect()
+}
+
+pub fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<chrono::Duration, D::Error> {
+    parse_duration(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+fn facility<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    const FACILITIES: [&str; 24] = [
+        "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv", "ftp",
+        "ntp", "audit", "alert", "clock", "local0", "local1", "local2", "local3", "local4", "local5", "local6", "local7",
+    ];
+    let name = String::deserialize(deserializer)?;
+    FACILITIES
+        .iter()
+        .position(|f| *f == name)
+        .map(|code| code as u8)
+        .ok_or_else(|| D::Error::custom(format!("unknown syslog facility '{}'", name)))
+}
+
+/// Load the config file, or the defaults when there is none
+pub fn load() -> Result<Config> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -0,0 +1,456 @@
+use anyhow::{anyhow, Context, Result};
+use crate::network::{self, LinkDetails, NetworkPeer};
+use crate::netns;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait on the container engine API
+const API_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Docker option naming the Linux bridge behind a network
+const BRIDGE_NAME_OPTION: &str = "com.docker.network.bridge.name";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerPort {
+    pub container_port: u16,
+    pub host_ip: Option<String>,
+    pub host_port: Option<u16>,
+    pub protocol: String,
+}
+
+impl std::fmt::Display for ContainerPort {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (self.host_ip.as_deref(), self.host_port) {
+            (Some(ip), Some(port)) if ip.contains(':') => write!(f, "[{}]:{}→{}/{}", ip, port, self.container_port, self.protocol),
+            (Some(ip), Some(port)) => write!(f, "{}:{}→{}/{}", ip, port, self.container_port, self.protocol),
+            (None, Some(port)) => write!(f, "{}→{}/{}", port, self.container_port, self.protocol),
+            _ => write!(f, "{}/{}", self.container_port, self.protocol),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerNetwork {
+    pub network: String,
+    /// Host bridge the network is built on
+    pub bridge: Option<String>,
+    pub ip: Option<String>,
+    pub ipv6: Option<String>,
+    pub mac: Option<String>,
+}
+
+/// A container interface and the host side of its veth pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerLink {
+    pub inside: String,
+    pub host_veth: String,
+    pub bridge: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub state: String,
+    /// "docker", "podman", or the runtime guessed from /proc
+    pub runtime: String,
+    pub pid: Option<u32>,
+    pub networks: Vec<ContainerNetwork>,
+    pub ports: Vec<ContainerPort>,
+    pub links: Vec<ContainerLink>,
+}
+
+impl Container {
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+
+    pub fn addresses(&self) -> Vec<&str> {
+        self.networks
+            .iter()
+            .flat_map(|n| n.ip.iter().chain(n.ipv6.iter()))
+            .map(|s| s.as_str())
+            .collect()
+    }
+}
+
+/// Engine API sockets to try, as (runtime, path)
+fn api_sockets() -> Vec<(&'static str, PathBuf)> {
+    let mut sockets = Vec::new();
+    if let Some(path) = std::env::var("DOCKER_HOST").ok().and_then(|h| h.strip_prefix("unix://").map(PathBuf::from)) {
+        sockets.push(("docker", path));
+    }
+    sockets.push(("docker", PathBuf::from("/var/run/docker.sock")));
+    sockets.push(("podman", PathBuf::from("/run/podman/podman.sock")));
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        sockets.push(("podman", PathBuf::from(runtime_dir).join("podman/podman.sock")));
+    }
+
+    sockets.retain(|(_, path)| path.exists());
+    // DOCKER_HOST usually names the default socket again
+    sockets.dedup_by(|a, b| a.1 == b.1);
+    sockets
+}
+
+/// GET a JSON document from the Docker-compatible API on a unix socket
+fn api_get(socket: &PathBuf, path: &str) -> Result<serde_json::Value> {
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("Failed to connect to {}", socket.display()))?;
+    stream.set_read_timeout(Some(API_TIMEOUT))?;
+    stream.set_write_timeout(Some(API_TIMEOUT))?;
+    // HTTP/1.0 so the engine closes the connection instead of using chunked encoding
+    stream.write_all(format!("GET {} HTTP/1.0\r\nHost: localhost\r\n\r\n", path).as_bytes())?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow!("Malformed response from {}", socket.display()))?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(anyhow!("{} returned HTTP {} for {}", socket.display(), status, path));
+    }
+    serde_json::from_str(body).context("Failed to parse container API response")
+}
+
+/// Bridge device for every network the engine knows, keyed by network name
+fn network_bridges(socket: &PathBuf) -> HashMap<String, String> {
+    let Ok(networks) = api_get(socket, "/networks") else {
+        return HashMap::new();
+    };
+    networks
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|net| {
+            let name = net["Name"].as_str()?.to_string();
+            let bridge = match net["Options"][BRIDGE_NAME_OPTION].as_str() {
+                Some(bridge) => bridge.to_string(),
+                // User-defined Docker bridges are named after the network id
+                None if net["Driver"].as_str() == Some("bridge") => format!("br-{}", &net["Id"].as_str()?.get(..12)?),
+                None => return None,
+            };
+            Some((name, bridge))
+        })
+        .collect()
+}
+
+fn non_empty(value: &serde_json::Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+fn containers_from_api(runtime: &str, socket: &PathBuf) -> Result<Vec<Container>> {
+    let list = api_get(socket, "/containers/json")?;
+    let bridges = network_bridges(socket);
+
+    let mut containers = Vec::new();
+    for summary in list.as_array().into_iter().flatten() {
+        let Some(id) = summary["Id"].as_str() else { continue };
+        let name = summary["Names"][0].as_str().unwrap_or(id).trim_start_matches('/').to_string();
+
+        // The summary has no PID, which is needed to look inside the namespace
+        let pid = api_get(socket, &format!("/containers/{}/json", id))
+            .ok()
+            .and_then(|detail| detail["State"]["Pid"].as_u64())
+            .filter(|&pid| pid > 0)
+            .map(|pid| pid as u32);
+
+        let networks = summary["NetworkSettings"]["Networks"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(network, settings)| ContainerNetwork {
+                network: network.clone(),
+                bridge: bridges.get(network).cloned(),
+                ip: non_empty(&settings["IPAddress"]),
+                ipv6: non_empty(&settings["GlobalIPv6Address"]),
+                mac: non_empty(&settings["MacAddress"]).map(|m| m.to_lowercase()),
+            })
+            .collect();
+
+        let ports = summary["Ports"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|port| {
+                Some(ContainerPort {
+                    container_port: port["PrivatePort"].as_u64()? as u16,
+                    host_ip: non_empty(&port["IP"]),
+                    host_port: port["PublicPort"].as_u64().map(|p| p as u16),
+                    protocol: port["Type"].as_str().unwrap_or("tcp").to_string(),
+                })
+            })
+            .collect();
+
+        containers.push(Container {
+            id: id.to_string(),
+            name,
+            image: summary["Image"].as_str().unwrap_or_default().to_string(),
+            state: summary["State"].as_str().unwrap_or("running").to_string(),
+            runtime: runtime.to_string(),
+            pid,
+            networks,
+            ports,
+            links: Vec::new(),
+        });
+    }
+    Ok(containers)
+}
+
+/// Find containers from process cgroups when no engine API is reachable
+fn containers_from_proc() -> Result<Vec<Container>> {
+    let pattern = Regex::new(r"(docker|libpod|cri-containerd|crio)[-/]([0-9a-f]{64})")?;
+    let host_netns = fs::metadata("/proc/self/ns/net").map(|m| m.ino()).ok();
+
+    // Lowest PID per container id is its init process
+    let mut found: BTreeMap<String, (u32, String)> = BTreeMap::new();
+    for entry in fs::read_dir("/proc").context("Failed to read /proc")?.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let Ok(cgroup) = fs::read_to_string(entry.path().join("cgroup")) else {
+            continue;
+        };
+        let Some(caps) = pattern.captures(&cgroup) else { continue };
+        let runtime = match &caps[1] {
+            "libpod" => "podman",
+            "cri-containerd" => "containerd",
+            other => other,
+        };
+        let slot = found.entry(caps[2].to_string()).or_insert((pid, runtime.to_string()));
+        if pid < slot.0 {
+            slot.0 = pid;
+        }
+    }
+
+    let mut containers = Vec::new();
+    for (id, (pid, runtime)) in found {
+        let own_netns = fs::metadata(format!("/proc/{}/ns/net", pid)).map(|m| m.ino()).ok() != host_netns;
+        let name = fs::read_to_string(format!("/proc/{}/comm", pid)).map(|c| c.trim().to_string()).unwrap_or_default();
+
+        // Addresses are only visible from inside the container's namespace
+        let addresses = if own_netns {
+            netns::run_in(&pid.to_string(), network::get_interfaces).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let networks = addresses
+            .iter()
+            .filter(|iface| iface.name != "lo")
+            .map(|iface| ContainerNetwork {
+                network: iface.name.clone(),
+                bridge: None,
+                ip: iface.ip_addresses.iter().find(|ip| !ip.contains(':')).cloned(),
+                ipv6: iface.ip_addresses.iter().find(|ip| ip.contains(':') && !ip.starts_with("fe80")).cloned(),
+                mac: iface.mac_address.clone(),
+            })
+            .collect();
+
+        containers.push(Container {
+            name: format!("{} ({})", &id[..12], name),
+            id,
+            image: String::new(),
+            state: "running".to_string(),
+            runtime,
+            pid: Some(pid),
+            networks,
+            ports: Vec::new(),
+            links: Vec::new(),
+        });
+    }
+    Ok(containers)
+}
+
+/// Running containers from every reachable engine, falling back to /proc
+pub fn list_containers() -> Result<Vec<Container>> {
+    let sockets = api_sockets();
+    let mut containers = Vec::new();
+    let mut reached = false;
+    for (runtime, socket) in &sockets {
+        if let Ok(found) = containers_from_api(runtime, socket) {
+            reached = true;
+            containers.extend(found);
+        }
+    }
+    if !reached {
+        containers = containers_from_proc()?;
+    }
+    containers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(containers)
+}
+
+/// Pair each container interface with its host veth by following the peer ifindex
+pub fn attach_host_links(containers: &mut [Container]) -> Result<()> {
+    let host_links = network::get_link_details()?;
+    let by_index: HashMap<u32, &LinkDetails> = host_links.iter().map(|l| (l.index, l)).collect();
+
+    for container in containers.iter_mut() {
+        let Some(pid) = container.pid else { continue };
+        // Entering another namespace needs root; without it the container keeps no links
+        let Ok(inside) = netns::run_in(&pid.to_string(), network::get_link_details) else {
+            continue;
+        };
+        container.links = inside
+            .iter()
+            .filter(|link| link.kind.as_deref() == Some("veth"))
+            .filter_map(|link| {
+                let host = by_index.get(&link.link_index?)?;
+                Some(ContainerLink {
+                    inside: link.name.clone(),
+                    host_veth: host.name.clone(),
+                    bridge: host.master.clone(),
+                })
+            })
+            .collect();
+    }
+    Ok(())
+}
+
+/// Which container or container network a host interface belongs to
+pub fn describe_interface(containers: &[Container], interface: &str) -> Option<String> {
+    for container in containers {
+        if let Some(link) = container.links.iter().find(|l| l.host_veth == interface) {
+            return Some(format!("veth of {} ({})", container.name, link.inside));
+        }
+    }
+    let network = containers
+        .iter()
+        .flat_map(|c| &c.networks)
+        .find(|n| n.bridge.as_deref() == Some(interface));
+    if let Some(network) = network {
+        return Some(format!("bridge of network {}", network.network));
+    }
+    let bridged = containers
+        .iter()
+        .any(|c| c.links.iter().any(|l| l.bridge.as_deref() == Some(interface)));
+    bridged.then(|| "container bridge".to_string())
+}
+
+/// Mark peers whose address or MAC belongs to a local container
+pub fn annotate_peers(peers: &mut [NetworkPeer]) {
+    // Only worth asking the runtimes when some peer sits behind a local bridge or veth
+    let Ok(links) = network::get_link_details() else { return };
+    let container_links: HashSet<&str> = links
+        .iter()
+        .filter(|l| matches!(l.kind.as_deref(), Some("bridge" | "veth")))
+        .map(|l| l.name.as_str())
+        .collect();
+    if !peers.iter().any(|p| container_links.contains(p.interface.as_str())) {
+        return;
+    }
+    let Ok(containers) = list_containers() else { return };
+
+    for peer in peers.iter_mut() {
+        let peer_mac = peer.mac.as_deref().map(str::to_lowercase);
+        let owner = containers.iter().find(|c| {
+            c.networks.iter().any(|n| {
+                n.ip.as_deref() == Some(peer.ip.as_str())
+                    || n.ipv6.as_deref() == Some(peer.ip.as_str())
+                    || (n.mac.is_some() && n.mac == peer_mac)
+            })
+        });
+        if let Some(container) = owner {
+            peer.container = Some(container.name.clone());
+            peer.device_type = "Container".to_string();
+            if peer.hostname.is_none() {
+                peer.hostname = Some(container.name.clone());
+            }
+        }
+    }
+}
+
+/// Print one line per container, shared with the status report
+pub fn print_summary(containers: &[Container]) {
+    for container in containers {
+        let addresses = container.addresses();
+        println!(
+            "• {} ({}) — {}{}",
+            container.name,
+            container.runtime,
+            container.state,
+            if addresses.is_empty() { String::new() } else { format!(", {}", addresses.join(", ")) }
+        );
+        for link in &container.links {
+            println!(
+                "  {} ↔ {}{}",
+                link.inside,
+                link.host_veth,
+                link.bridge.as_deref().map(|b| format!(" on {}", b)).unwrap_or_default()
+            );
+        }
+        if !container.ports.is_empty() {
+            let ports: Vec<String> = container.ports.iter().map(|p| p.to_string()).collect();
+            println!("  Ports: {}", ports.join(", "));
+        }
+    }
+}
+
+/// Show containers, their networking and which host interfaces belong to them
+pub async fn show_containers() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              🐳  NetNinja Container Networks  🐳          ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let containers = tokio::task::spawn_blocking(|| -> Result<Vec<Container>> {
+        let mut containers = list_containers()?;
+        attach_host_links(&mut containers)?;
+        Ok(containers)
+    })
+    .await??;
+
+    if api_sockets().is_empty() {
+        println!("⚠️  No Docker or Podman API socket found, containers were found through /proc\n");
+    }
+    if containers.is_empty() {
+        println!("No running containers");
+        println!();
+        return Ok(());
+    }
+
+    for container in &containers {
+        println!("🐳 {}  {}", container.name, container.short_id());
+        if !container.image.is_empty() {
+            println!("   Image: {}", container.image);
+        }
+        println!("   Runtime: {}  State: {}{}", container.runtime, container.state,
+            container.pid.map(|p| format!("  PID: {}", p)).unwrap_or_default());
+        for net in &container.networks {
+            let addresses: Vec<&str> = net.ip.iter().chain(net.ipv6.iter()).map(|s| s.as_str()).collect();
+            println!(
+                "   Network {}: {}{}",
+                net.network,
+                if addresses.is_empty() { "no address".to_string() } else { addresses.join(", ") },
+                net.bridge.as_deref().map(|b| format!(" via {}", b)).unwrap_or_default()
+            );
+        }
+        for link in &container.links {
+            println!("   Link: {} ↔ host {}{}", link.inside, link.host_veth,
+                link.bridge.as_deref().map(|b| format!(" (bridge {})", b)).unwrap_or_default());
+        }
+        for port in &container.ports {
+            println!("   Port: {}", port);
+        }
+        println!();
+    }
+
+    // Name the host-side devices that otherwise show up as anonymous veths and bridges
+    println!("🔌 HOST INTERFACES");
+    println!("───────────────────────────────────────────────────────────");
+    for iface in network::get_interfaces()? {
+        if let Some(owner) = describe_interface(&containers, &iface.name) {
+            println!("{:<18} {}", iface.name, owner);
+        }
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use anyhow::{anyhow, Context, Result};
+use crate::history::{self, HistoryStore, Sampler};
+use crate::monitor::{Section, StatusSnapshot};
+use crate::notify::Dispatcher;
+use crate::security::SecurityAlert;
+use crate::{netns, rules, sigma, state};
+use nix::sys::stat::{umask, Mode};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::MissedTickBehavior;
+
+const SOCKET_NAME: &str = "netninja.sock";
+
+/// How long clients and the daemon wait on each other
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often each section of the snapshot is refreshed
+fn refresh_interval(section: Section) -> Duration {
+    Duration::from_secs(match section {
+        Section::Interfaces | Section::Vpn | Section::Ports => 5,
+        Section::Wireless | Section::Peers => 10,
+        Section::Containers => 30,
+        // journalctl and dmesg are the expensive collectors
+        Section::Security => 60,
+    })
+}
+
+/// Control socket: `$XDG_RUNTIME_DIR/netninja.sock`, or the state directory; one per namespace
+pub fn socket_path() -> Result<PathBuf> {
+    let name = state::namespaced(SOCKET_NAME);
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir).join(name)),
+        _ => Ok(state::data_dir()?.join(name)),
+    }
+}
+
+/// Send a one-line request to the daemon and return its response
+async fn request(command: &str) -> Result<String> {
+    let path = socket_path()?;
+    let exchange = async {
+        let mut stream = UnixStream::connect(&path).await?;
+        stream.write_all(format!("{}\n", command).as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok::<_, anyhow::Error>(response)
+    };
+    tokio::time::timeout(CLIENT_TIMEOUT, exchange)
+        .await
+        .map_err(|_| anyhow!("The daemon did not answer"))?
+}
+
+/// The daemon's latest snapshot, or None when no daemon is running or it is still warming up
+pub async fn fetch_snapshot() -> Option<StatusSnapshot> {
+    let response = request("snapshot").await.ok()?;
+    let snapshot: StatusSnapshot = serde_json::from_str(&response).ok()?;
+    (snapshot.updated.len() == Section::ALL.len()).then_some(snapshot)
+}
+
+/// Answer a single client request
+async fn serve(stream: UnixStream, snapshot: Arc<RwLock<StatusSnapshot>>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    tokio::time::timeout(CLIENT_TIMEOUT, BufReader::new(reader).read_line(&mut line)).await??;
+
+    let response = match line.trim() {
+        "snapshot" => serde_json::to_string(&*snapshot.read().await)?,
+        "ping" => "pong".to_string(),
+        other => format!("unknown request: {}", other),
+    };
+    writer.write_all(response.as_bytes()).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+/// Refresh one section of the shared snapshot forever, passing security alerts on for notification
+async fn run_collector(section: Section, snapshot: Arc<RwLock<StatusSnapshot>>, alerts: mpsc::UnboundedSender<Vec<SecurityAlert>>) {
+    let mut ticker = tokio::time::interval(refresh_interval(section));
+    // A slow collector skips missed ticks instead of running back to back
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        // Collect into a copy so readers are never blocked behind journalctl or ss
+        let mut scratch = snapshot.read().await.clone();
+        let Ok(fresh) = tokio::task::spawn_blocking(move || {
+            section.collect_into(&mut scratch);
+            scratch
+        })
+        .await
+        else {
+            continue;
+        };
+
+        // Only alerts seen for the first time are notified
+        if let (Section::Security, Some(found)) = (section, &fresh.alerts) {
+            let new: Vec<SecurityAlert> = found
+                .iter()
+                .filter(|a| fresh.new_alert_ids.contains(&a.id()))
+                .cloned()
+                .collect();
+            if !new.is_empty() {
+                let _ = alerts.send(new);
+            }
+        }
+
+        let mut shared = snapshot.write().await;
+        let error = fresh.errors.get(section.name()).cloned();
+        if error.is_some() && error.as_ref() != shared.errors.get(section.name()) {
+            eprintln!("⚠️  {} collector failed: {}", section.name(), error.unwrap_or_default());
+        }
+        section.merge(fresh, &mut shared);
+    }
+}
+
+/// Send alerts to the configured sinks as the security collector finds them
+async fn run_notifier(mut dispatcher: Dispatcher, mut alerts: mpsc::UnboundedReceiver<Vec<SecurityAlert>>) {
+    while let Some(batch) = alerts.recv().await {
+        // Sinks talk to the network and run commands
+        let Ok((returned, failures)) = tokio::task::spawn_blocking(move || {
+            let failures = dispatcher.dispatch(&batch);
+            (dispatcher, failures)
+        })
+        .await
+        else {
+            return;
+        };
+        dispatcher = returned;
+        for (sink, e) in failures {
+            eprintln!("⚠️  Notification to {} failed: {:#}", sink, e);
+        }
+    }
+}
+
+/// Record a history sample every minute, reading peers and alerts from the snapshot
+async fn run_recorder(snapshot: Arc<RwLock<StatusSnapshot>>) {
+    let mut store = match HistoryStore::load() {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("⚠️  History recording disabled: {}", e);
+            return;
+        }
+    };
+    let mut sampler = Sampler::default();
+    let mut ticker = tokio::time::interval(history::SAMPLE_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut failing = false;
+
+    loop {
+        ticker.tick().await;
+        let current = snapshot.read().await.clone();
+        // Pinging the gateways blocks for up to a second each
+        let Ok((returned_sampler, returned_store, saved)) = tokio::task::spawn_blocking(move || {
+            let samples = sampler.sample(&current);
+            store.record(chrono::Utc::now(), &samples);
+            let saved = store.save();
+            (sampler, store, saved)
+        })
+        .await
+        else {
+            return;
+        };
+        sampler = returned_sampler;
+        store = returned_store;
+
+        if let Err(e) = &saved {
+            if !failing {
+                eprintln!("⚠️  Failed to save history: {}", e);
+            }
+        }
+        failing = saved.is_err();
+    }
+}
+
+/// Run the collectors on their schedules and serve the snapshot until interrupted
+pub async fn run_daemon() -> Result<()> {
+    let path = socket_path()?;
+    if path.exists() {
+        if request("ping").await.is_ok() {
+            return Err(anyhow!("A daemon is already listening on {}", path.display()));
+        }
+        // Left behind by a daemon that did not shut down cleanly
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+
+    // A broken config should stop the daemon rather than silently drop notifications or detections
+    let dispatcher = Dispatcher::from_config()?;
+    rules::load_rules()?;
+    sigma::load_rules()?;
+
+    // The snapshot includes security alerts, so only the owner may connect; the socket is
+    // created owner-only rather than narrowed after bind, which would leave a window open
+    let previous_umask = umask(Mode::from_bits_truncate(0o177));
+    let listener = UnixListener::bind(&path);
+    umask(previous_umask);
+    let listener = listener.with_context(|| format!("Failed to listen on {}", path.display()))?;
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              ⚡  NetNinja Monitoring Daemon  ⚡           ");
+    println!("═══════════════════════════════════════════════════════════\n");
+    println!("Listening on {}", path.display());
+    for section in Section::ALL {
+        println!("  • {:<12} every {}s", section.name(), refresh_interval(section).as_secs());
+    }
+    println!("  • {:<12} every {}s", "history", history::SAMPLE_INTERVAL.as_secs());
+    for sink in dispatcher.sink_labels() {
+        println!("  🔔 Notifying {}", sink);
+    }
+    println!("\nPress Ctrl+C to stop");
+
+    let snapshot = Arc::new(RwLock::new(StatusSnapshot {
+        netns: netns::entered().map(str::to_string),
+        ..Default::default()
+    }));
+    let (alerts_tx, alerts_rx) = mpsc::unbounded_channel();
+    if !dispatcher.is_empty() {
+        tokio::spawn(run_notifier(dispatcher, alerts_rx));
+    }
+    for section in Section::ALL {
+        tokio::spawn(run_collector(section, snapshot.clone(), alerts_tx.clone()));
+    }
+    tokio::spawn(run_recorder(snapshot.clone()));
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                if let Ok((stream, _)) = accepted {
+                    tokio::spawn(serve(stream, snapshot.clone()));
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+        }
+    }
+
+    let _ = std::fs::remove_file(&path);
+    println!("\n👋 Daemon stopped");
+    Ok(())
+}
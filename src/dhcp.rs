@@ -0,0 +1,330 @@
+use anyhow::{Context, Result};
+use crate::network;
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use chrono::Utc;
+use pnet::datalink::{self, Channel, NetworkInterface as DatalinkInterface};
+use pnet::packet::dhcp::{DhcpHardwareTypes, DhcpOperations, DhcpPacket, MutableDhcpPacket};
+use pnet::packet::ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ipv4::{self, Ipv4Packet, MutableIpv4Packet};
+use pnet::packet::udp::{self, MutableUdpPacket, UdpPacket};
+use pnet::packet::Packet;
+use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+const DHCP_SERVER_PORT: u16 = 67;
+const DHCP_CLIENT_PORT: u16 = 68;
+const DHCP_MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+/// BOOTP messages are padded to at least this many bytes
+const MIN_DHCP_LEN: usize = 300;
+const DHCP_FIXED_LEN: usize = 236;
+
+const OPT_SUBNET_MASK: u8 = 1;
+const OPT_ROUTER: u8 = 3;
+const OPT_DNS: u8 = 6;
+const OPT_DOMAIN_NAME: u8 = 15;
+const OPT_LEASE_TIME: u8 = 51;
+const OPT_MESSAGE_TYPE: u8 = 53;
+const OPT_SERVER_ID: u8 = 54;
+const OPT_PARAM_LIST: u8 = 55;
+const OPT_END: u8 = 255;
+
+const DHCPDISCOVER: u8 = 1;
+const DHCPOFFER: u8 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DhcpOffer {
+    pub server_id: Option<Ipv4Addr>,
+    pub source_ip: Ipv4Addr,
+    pub source_mac: String,
+    pub offered_ip: Ipv4Addr,
+    pub subnet_mask: Option<Ipv4Addr>,
+    pub lease_seconds: Option<u32>,
+    pub routers: Vec<Ipv4Addr>,
+    pub dns_servers: Vec<Ipv4Addr>,
+    pub domain_name: Option<String>,
+    pub response_time_ms: f64,
+}
+
+impl DhcpOffer {
+    /// Identity of the answering server, preferring the server identifier option
+    pub fn server_key(&self) -> String {
+        format!("{}/{}", self.server_id.unwrap_or(self.source_ip), self.source_mac)
+    }
+}
+
+/// Broadcast a DHCPDISCOVER on the interface and collect every OFFER until the timeout
+pub fn probe_dhcp_servers(interface_name: &str, timeout: Duration) -> Result<Vec<DhcpOffer>> {
+    let interface = datalink::interfaces()
+        .into_iter()
+        .find(|i| i.name == interface_name)
+        .with_context(|| format!("Interface {} not found", interface_name))?;
+    let mac = interface.mac.context("Interface has no MAC address")?;
+
+    let config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(200)),
+        ..Default::default()
+    };
+    let (mut tx, mut rx) = match datalink::channel(&interface, config)
+        .context("Failed to open datalink channel (are you root?)")?
+    {
+        Channel::Ethernet(tx, rx) => (tx, rx),
+        _ => return Err(anyhow::anyhow!("Unsupported datalink channel type")),
+    };
+
+    let xid = transaction_id();
+    let frame = build_discover(&interface, mac, xid);
+    let sent_at = Instant::now();
+    tx.send_to(&frame, None)
+        .context("Failed to send DHCPDISCOVER")?
+        .context("Failed to send DHCPDISCOVER")?;
+
+    let mut offers = Vec::new();
+    while sent_at.elapsed() < timeout {
+        let frame = match rx.next() {
+            Ok(frame) => frame,
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(e) => return Err(e).context("Failed to read from datalink channel"),
+        };
+        if let Some(offer) = parse_offer(frame, xid, sent_at.elapsed()) {
+            offers.push(offer);
+        }
+    }
+
+    Ok(offers)
+}
+
+fn transaction_id() -> u32 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    nanos ^ std::process::id().rotate_left(16)
+}
+
+fn build_discover(interface: &DatalinkInterface, mac: MacAddr, xid: u32) -> Vec<u8> {
+    let mut options = DHCP_MAGIC_COOKIE.to_vec();
+    options.extend_from_slice(&[OPT_MESSAGE_TYPE, 1, DHCPDISCOVER]);
+    options.extend_from_slice(&[OPT_PARAM_LIST, 6, OPT_SUBNET_MASK, OPT_ROUTER, OPT_DNS, OPT_DOMAIN_NAME, OPT_LEASE_TIME, OPT_SERVER_ID]);
+    options.push(OPT_END);
+
+    let dhcp_len = (DHCP_FIXED_LEN + options.len()).max(MIN_DHCP_LEN);
+    let udp_len = 8 + dhcp_len;
+    let ip_len = 20 + udp_len;
+    let mut frame = vec![0u8; 14 + ip_len];
+
+    {
+        let mut dhcp = MutableDhcpPacket::new(&mut frame[14 + 20 + 8..]).expect("buffer sized for DHCP");
+        dhcp.set_op(DhcpOperations::Request);
+        dhcp.set_htype(DhcpHardwareTypes::Ethernet);
+        dhcp.set_hlen(6);
+        dhcp.set_xid(xid);
+        // Ask for broadcast replies since we have no address yet
+        dhcp.set_flags(0x8000);
+        dhcp.set_chaddr(mac);
+        dhcp.set_options(&options);
+    }
+    {
+        let mut udp = MutableUdpPacket::new(&mut frame[14 + 20..]).expect("buffer sized for UDP");
+        udp.set_source(DHCP_CLIENT_PORT);
+        udp.set_destination(DHCP_SERVER_PORT);
+        udp.set_length(udp_len as u16);
+        let checksum = udp::ipv4_checksum(&udp.to_immutable(), &Ipv4Addr::UNSPECIFIED, &Ipv4Addr::BROADCAST);
+        udp.set_checksum(checksum);
+    }
+    {
+        let mut ip = MutableIpv4Packet::new(&mut frame[14..]).expect("buffer sized for IPv4");
+        ip.set_version(4);
+        ip.set_header_length(5);
+        ip.set_total_length(ip_len as u16);
+        ip.set_ttl(64);
+        ip.set_next_level_protocol(IpNextHeaderProtocols::Udp);
+        ip.set_source(Ipv4Addr::UNSPECIFIED);
+        ip.set_destination(Ipv4Addr::BROADCAST);
+        let checksum = ipv4::checksum(&ip.to_immutable());
+        ip.set_checksum(checksum);
+    }
+    {
+        let mut ethernet = MutableEthernetPacket::new(&mut frame).expect("buffer sized for Ethernet");
+        ethernet.set_destination(MacAddr::broadcast());
+        ethernet.set_source(interface.mac.unwrap_or(mac));
+        ethernet.set_ethertype(EtherTypes::Ipv4);
+    }
+
+    frame
+}
+
+fn parse_offer(frame: &[u8], xid: u32, elapsed: Duration) -> Option<DhcpOffer> {
+    let ethernet = EthernetPacket::new(frame)?;
+    if ethernet.get_ethertype() != EtherTypes::Ipv4 {
+        return None;
+    }
+    let ip = Ipv4Packet::new(ethernet.payload())?;
+    if ip.get_next_level_protocol() != IpNextHeaderProtocols::Udp {
+        return None;
+    }
+    let udp = UdpPacket::new(ip.payload())?;
+    if udp.get_source() != DHCP_SERVER_PORT || udp.get_destination() != DHCP_CLIENT_PORT {
+        return None;
+    }
+    let dhcp = DhcpPacket::new(udp.payload())?;
+    if dhcp.get_op() != DhcpOperations::Reply || dhcp.get_xid() != xid {
+        return None;
+    }
+
+    let options = parse_options(dhcp.payload())?;
+    let option = |code: u8| options.iter().find(|(c, _)| *c == code).map(|(_, v)| v.as_slice());
+    if option(OPT_MESSAGE_TYPE)?.first() != Some(&DHCPOFFER) {
+        return None;
+    }
+
+    let addresses = |code: u8| -> Vec<Ipv4Addr> {
+        option(code)
+            .map(|v| v.chunks_exact(4).map(|c| Ipv4Addr::new(c[0], c[1], c[2], c[3])).collect())
+            .unwrap_or_default()
+    };
+
+    Some(DhcpOffer {
+        server_id: addresses(OPT_SERVER_ID).first().copied(),
+        source_ip: ip.get_source(),
+        source_mac: ethernet.get_source().to_string(),
+        offered_ip: dhcp.get_yiaddr(),
+        subnet_mask: addresses(OPT_SUBNET_MASK).first().copied(),
+        lease_seconds: option(OPT_LEASE_TIME)
+            .filter(|v| v.len() == 4)
+            .map(|v| u32::from_be_bytes([v[0], v[1], v[2], v[3]])),
+        routers: addresses(OPT_ROUTER),
+        dns_servers: addresses(OPT_DNS),
+        domain_name: option(OPT_DOMAIN_NAME).map(|v| String::from_utf8_lossy(v).trim_end_matches('\0').to_string()),
+        response_time_ms: elapsed.as_secs_f64() * 1000.0,
+    })
+}
+
+/// Split the options area (after the magic cookie) into (code, value) pairs
+pub fn parse_options(data: &[u8]) -> Option<Vec<(u8, Vec<u8>)>> {
+    if data.len() < 4 || data[..4] != DHCP_MAGIC_COOKIE {
+        return None;
+    }
+
+    let mut options = Vec::new();
+    let mut i = 4;
+    while i < data.len() {
+        match data[i] {
+            0 => i += 1,
+            OPT_END => break,
+            code => {
+                let len = *data.get(i + 1)? as usize;
+                let value = data.get(i + 2..i + 2 + len)?.to_vec();
+                options.push((code, value));
+                i += 2 + len;
+            }
+        }
+    }
+
+    Some(options)
+}
+
+/// Alert when more than one DHCP server answered the same DISCOVER
+pub fn check_rogue_servers(interface: &str, offers: &[DhcpOffer]) -> Vec<SecurityAlert> {
+    let mut servers: Vec<&DhcpOffer> = Vec::new();
+    for offer in offers {
+        if !servers.iter().any(|s| s.server_key() == offer.server_key()) {
+            servers.push(offer);
+        }
+    }
+
+    if servers.len() <= 1 {
+        return Vec::new();
+    }
+
+    vec![SecurityAlert {
+        timestamp: Utc::now(),
+        severity: AlertSeverity::Critical,
+        category: AlertCategory::RogueDhcp,
+        message: format!("{} DHCP servers answered on {}: possible rogue DHCP server", servers.len(), interface),
+        details: Some(
+            servers
+                .iter()
+                .map(|s| {
+                    format!(
+                        "server {} (mac {}) offered {} router {} dns {}",
+                        s.server_id.unwrap_or(s.source_ip),
+                        s.source_mac,
+                        s.offered_ip,
+                        join_addrs(&s.routers),
+                        join_addrs(&s.dns_servers)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+        subject: Some(interface.to_string()),
+    }]
+}
+
+fn join_addrs(addrs: &[Ipv4Addr]) -> String {
+    if addrs.is_empty() {
+        return "-".to_string();
+    }
+    addrs.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Probe for DHCP servers and print every offer received
+pub async fn show_dhcp(interface: Option<String>, timeout_secs: u64) -> Result<()> {
+    let interface = match interface {
+        Some(name) => name,
+        None => network::get_primary_interface()?.name,
+    };
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("               📨  NetNinja DHCP Probe  📨                 ");
+    println!("═══════════════════════════════════════════════════════════\n");
+    println!("Sending DHCPDISCOVER on {} and listening for {}s...\n", interface, timeout_secs);
+
+    let probe_interface = interface.clone();
+    let offers = tokio::task::spawn_blocking(move || {
+        probe_dhcp_servers(&probe_interface, Duration::from_secs(timeout_secs))
+    })
+    .await??;
+
+    if offers.is_empty() {
+        println!("⚠️  No DHCP server answered on {}", interface);
+        println!();
+        return Ok(());
+    }
+
+    for offer in &offers {
+        println!("🖥️  Server {} (MAC {})", offer.server_id.unwrap_or(offer.source_ip), offer.source_mac);
+        println!("───────────────────────────────────────────────────────────");
+        println!("Offered IP: {}", offer.offered_ip);
+        if let Some(mask) = offer.subnet_mask {
+            println!("Subnet Mask: {}", mask);
+        }
+        match offer.lease_seconds {
+            Some(lease) => println!("Lease: {}s ({:.1}h)", lease, lease as f64 / 3600.0),
+            None => println!("Lease: not specified"),
+        }
+        println!("Routers: {}", join_addrs(&offer.routers));
+        println!("DNS Servers: {}", join_addrs(&offer.dns_servers));
+        if let Some(domain) = &offer.domain_name {
+            println!("Domain: {}", domain);
+        }
+        println!("Response Time: {:.1} ms", offer.response_time_ms);
+        println!();
+    }
+
+    let alerts = check_rogue_servers(&interface, &offers);
+    if alerts.is_empty() {
+        println!("✅ Exactly one DHCP server answered");
+    } else {
+        for alert in &alerts {
+            println!("🚨 [{}] {}", alert.severity, alert.message);
+        }
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,415 @@
+use anyhow::{Context, Result};
+use crate::{fingerprint, inventory};
+use crate::network::{self, NetworkPeer};
+use pnet::datalink::{self, Channel, DataLinkReceiver, DataLinkSender};
+use pnet::ipnetwork::{IpNetwork, Ipv4Network};
+use pnet::packet::arp::{ArpHardwareTypes, ArpOperations, ArpPacket, MutableArpPacket};
+use pnet::packet::ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket};
+use pnet::packet::icmpv6::{self, Icmpv6Packet};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ipv6::{Ipv6Packet, MutableIpv6Packet};
+use pnet::packet::Packet;
+use pnet::util::MacAddr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Subnets larger than this are narrowed to the block around our own address
+const MAX_SWEEP_PREFIX: u8 = 20;
+
+/// ARP requests sent back to back before a short pause
+const SEND_BURST: usize = 32;
+const BURST_PAUSE: Duration = Duration::from_millis(2);
+
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+const ICMPV6_NEIGHBOR_SOLICIT: u8 = 135;
+const ICMPV6_NEIGHBOR_ADVERT: u8 = 136;
+const NDP_OPT_SOURCE_LLADDR: u8 = 1;
+
+const ALL_NODES: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredPeer {
+    pub peer: NetworkPeer,
+    /// How the host answered: "arp", "icmpv6-echo" or "ndp"
+    pub method: String,
+    pub response_time_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryResult {
+    pub interface: String,
+    pub swept: Vec<String>,
+    pub peers: Vec<DiscoveredPeer>,
+}
+
+/// Outstanding probes and the answers collected so far, shared with the reader thread
+#[derive(Default)]
+struct Probes {
+    sent: HashMap<IpAddr, Instant>,
+    /// Identifier-matched echo requests sent to a multicast group
+    multicast_echo: Option<Instant>,
+    answers: HashMap<IpAddr, (MacAddr, &'static str, Duration)>,
+    deadline: Option<Instant>,
+}
+
+/// ARP-sweep the interface's IPv4 subnets and probe IPv6 neighbors, returning every host that answered
+pub fn discover_peers(interface_name: &str, timeout: Duration) -> Result<DiscoveryResult> {
+    let interface = datalink::interfaces()
+        .into_iter()
+        .find(|i| i.name == interface_name)
+        .with_context(|| format!("Interface {} not found", interface_name))?;
+    let mac = interface.mac.context("Interface has no MAC address")?;
+
+    let config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let (mut tx, rx) = match datalink::channel(&interface, config)
+        .context("Failed to open datalink channel (are you root?)")?
+    {
+        Channel::Ethernet(tx, rx) => (tx, rx),
+        _ => return Err(anyhow::anyhow!("Unsupported datalink channel type")),
+    };
+
+    let networks: Vec<Ipv4Network> = interface
+        .ips
+        .iter()
+        .filter_map(|ip| match ip {
+            IpNetwork::V4(net) => Some(sweep_network(*net)),
+            IpNetwork::V6(_) => None,
+        })
+        .collect();
+    let link_local = interface.ips.iter().find_map(|ip| match ip {
+        IpNetwork::V6(net) if is_link_local(&net.ip()) => Some(net.ip()),
+        _ => None,
+    });
+    let echo_id = std::process::id() as u16;
+
+    let probes = Mutex::new(Probes::default());
+    std::thread::scope(|scope| -> Result<()> {
+        scope.spawn(|| read_replies(rx, echo_id, &probes));
+
+        let sent = send_probes(&mut *tx, mac, &networks, link_local, echo_id, timeout, &probes);
+        // Stop the reader even when sending failed part way
+        probes.lock().unwrap().deadline = Some(Instant::now() + timeout);
+        sent
+    })?;
+
+    let answers = probes.into_inner().unwrap().answers;
+    let mut peers: Vec<DiscoveredPeer> = answers
+        .into_iter()
+        .map(|(ip, (peer_mac, method, rtt))| DiscoveredPeer {
+            peer: NetworkPeer::new(ip.to_string(), Some(peer_mac.to_string()), interface.name.clone(), "REACHABLE".to_string()),
+            method: method.to_string(),
+            response_time_ms: rtt.as_secs_f64() * 1000.0,
+        })
+        .collect();
+    peers.sort_by_key(|d| sort_key(&d.peer.ip));
+
+    let mut network_peers: Vec<NetworkPeer> = peers.iter().map(|d| d.peer.clone()).collect();
+    fingerprint::apply_fingerprints(&mut network_peers);
+    // Quiet devices found by the sweep belong in the inventory too
+    let _ = inventory::record_peers(&network_peers);
+    for (discovered, peer) in peers.iter_mut().zip(network_peers) {
+        discovered.peer = peer;
+    }
+
+    Ok(DiscoveryResult {
+        interface: interface.name.clone(),
+        swept: networks.iter().map(|n| format!("{}/{}", n.network(), n.prefix())).collect(),
+        peers,
+    })
+}
+
+/// Send the ARP sweep, the multicast echo and the follow-up neighbor solicitations
+fn send_probes(
+    tx: &mut dyn DataLinkSender,
+    mac: MacAddr,
+    networks: &[Ipv4Network],
+    link_local: Option<Ipv6Addr>,
+    echo_id: u16,
+    timeout: Duration,
+    probes: &Mutex<Probes>,
+) -> Result<()> {
+    for net in networks {
+        let own = net.ip();
+        let targets = net
+            .iter()
+            .filter(|ip| *ip != own && *ip != net.network() && *ip != net.broadcast());
+        for (i, target) in targets.enumerate() {
+            probes.lock().unwrap().sent.insert(IpAddr::V4(target), Instant::now());
+            let frame = build_arp_request(mac, own, target);
+            tx.send_to(&frame, None)
+                .context("Failed to send ARP request")?
+                .context("Failed to send ARP request")?;
+            if i % SEND_BURST == SEND_BURST - 1 {
+                std::thread::sleep(BURST_PAUSE);
+            }
+        }
+    }
+
+    if let Some(source) = link_local {
+        // Most stacks answer a multicast echo; the rest are asked directly below
+        probes.lock().unwrap().multicast_echo = Some(Instant::now());
+        let frame = build_icmpv6(mac, source, ALL_NODES, &echo_request(echo_id));
+        tx.send_to(&frame, None)
+            .context("Failed to send ICMPv6 echo")?
+            .context("Failed to send ICMPv6 echo")?;
+        std::thread::sleep(timeout.min(Duration::from_secs(1)));
+
+        // Solicit the EUI-64 link-local address of every IPv4 host not yet heard on IPv6
+        let candidates: Vec<Ipv6Addr> = {
+            let probes = probes.lock().unwrap();
+            let answered_macs: Vec<MacAddr> = probes
+                .answers
+                .iter()
+                .filter(|(ip, _)| ip.is_ipv6())
+                .map(|(_, (mac, _, _))| *mac)
+                .collect();
+            probes
+                .answers
+                .iter()
+                .filter(|(ip, (mac, _, _))| ip.is_ipv4() && !answered_macs.contains(mac))
+                .map(|(_, (mac, _, _))| eui64_link_local(*mac))
+                .collect()
+        };
+        for target in candidates {
+            probes.lock().unwrap().sent.insert(IpAddr::V6(target), Instant::now());
+            let frame = build_icmpv6(mac, source, solicited_node(target), &neighbor_solicit(target, mac));
+            tx.send_to(&frame, None)
+                .context("Failed to send neighbor solicitation")?
+                .context("Failed to send neighbor solicitation")?;
+        }
+    }
+    Ok(())
+}
+
+/// Narrow very large subnets to the block that holds our own address
+fn sweep_network(net: Ipv4Network) -> Ipv4Network {
+    if net.prefix() >= MAX_SWEEP_PREFIX {
+        return net;
+    }
+    Ipv4Network::new(net.ip(), MAX_SWEEP_PREFIX).unwrap_or(net)
+}
+
+fn is_link_local(ip: &Ipv6Addr) -> bool {
+    ip.segments()[0] & 0xffc0 == 0xfe80
+}
+
+fn sort_key(ip: &str) -> (u8, u128) {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => (0, u32::from(v4) as u128),
+        Ok(IpAddr::V6(v6)) => (1, u128::from(v6)),
+        Err(_) => (2, 0),
+    }
+}
+
+fn read_replies(mut rx: Box<dyn DataLinkReceiver>, echo_id: u16, probes: &Mutex<Probes>) {
+    loop {
+        if let Some(deadline) = probes.lock().unwrap().deadline {
+            if Instant::now() >= deadline {
+                return;
+            }
+        }
+        let frame = match rx.next() {
+            Ok(frame) => frame,
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(_) => return,
+        };
+        let Some(ethernet) = EthernetPacket::new(frame) else {
+            continue;
+        };
+        let Some((ip, method)) = parse_reply(&ethernet, echo_id) else {
+            continue;
+        };
+
+        let mut probes = probes.lock().unwrap();
+        let sent_at = match method {
+            "icmpv6-echo" => probes.multicast_echo,
+            _ => probes.sent.get(&ip).copied(),
+        };
+        if let Some(sent_at) = sent_at {
+            probes
+                .answers
+                .entry(ip)
+                .or_insert((ethernet.get_source(), method, sent_at.elapsed()));
+        }
+    }
+}
+
+/// The address and probe type of an ARP reply, echo reply or neighbor advertisement
+fn parse_reply(ethernet: &EthernetPacket, echo_id: u16) -> Option<(IpAddr, &'static str)> {
+    match ethernet.get_ethertype() {
+        EtherTypes::Arp => {
+            let arp = ArpPacket::new(ethernet.payload())?;
+            (arp.get_operation() == ArpOperations::Reply)
+                .then(|| (IpAddr::V4(arp.get_sender_proto_addr()), "arp"))
+        }
+        EtherTypes::Ipv6 => {
+            let ip = Ipv6Packet::new(ethernet.payload())?;
+            if ip.get_next_header() != IpNextHeaderProtocols::Icmpv6 {
+                return None;
+            }
+            let icmp = ip.payload();
+            match *icmp.first()? {
+                ICMPV6_ECHO_REPLY if icmp.get(4..6)? == echo_id.to_be_bytes() => {
+                    Some((IpAddr::V6(ip.get_source()), "icmpv6-echo"))
+                }
+                ICMPV6_NEIGHBOR_ADVERT => {
+                    let target: [u8; 16] = icmp.get(8..24)?.try_into().ok()?;
+                    Some((IpAddr::V6(Ipv6Addr::from(target)), "ndp"))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn build_arp_request(mac: MacAddr, source: Ipv4Addr, target: Ipv4Addr) -> Vec<u8> {
+    let mut frame = vec![0u8; 14 + 28];
+    {
+        let mut ethernet = MutableEthernetPacket::new(&mut frame).expect("buffer sized for Ethernet");
+        ethernet.set_destination(MacAddr::broadcast());
+        ethernet.set_source(mac);
+        ethernet.set_ethertype(EtherTypes::Arp);
+    }
+    {
+        let mut arp = MutableArpPacket::new(&mut frame[14..]).expect("buffer sized for ARP");
+        arp.set_hardware_type(ArpHardwareTypes::Ethernet);
+        arp.set_protocol_type(EtherTypes::Ipv4);
+        arp.set_hw_addr_len(6);
+        arp.set_proto_addr_len(4);
+        arp.set_operation(ArpOperations::Request);
+        arp.set_sender_hw_addr(mac);
+        arp.set_sender_proto_addr(source);
+        arp.set_target_hw_addr(MacAddr::zero());
+        arp.set_target_proto_addr(target);
+    }
+    frame
+}
+
+fn echo_request(id: u16) -> Vec<u8> {
+    let mut body = vec![ICMPV6_ECHO_REQUEST, 0, 0, 0];
+    body.extend_from_slice(&id.to_be_bytes());
+    body.extend_from_slice(&1u16.to_be_bytes());
+    body
+}
+
+fn neighbor_solicit(target: Ipv6Addr, mac: MacAddr) -> Vec<u8> {
+    let mut body = vec![ICMPV6_NEIGHBOR_SOLICIT, 0, 0, 0, 0, 0, 0, 0];
+    body.extend_from_slice(&target.octets());
+    body.extend_from_slice(&[NDP_OPT_SOURCE_LLADDR, 1]);
+    body.extend_from_slice(&mac.octets());
+    body
+}
+
+/// Wrap an ICMPv6 message in IPv6 and Ethernet headers, filling in the checksum
+fn build_icmpv6(mac: MacAddr, source: Ipv6Addr, destination: Ipv6Addr, body: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0u8; 14 + 40 + body.len()];
+    frame[54..].copy_from_slice(body);
+
+    let checksum = icmpv6::checksum(
+        &Icmpv6Packet::new(&frame[54..]).expect("buffer sized for ICMPv6"),
+        &source,
+        &destination,
+    );
+    frame[56..58].copy_from_slice(&checksum.to_be_bytes());
+
+    {
+        let mut ip = MutableIpv6Packet::new(&mut frame[14..]).expect("buffer sized for IPv6");
+        ip.set_version(6);
+        ip.set_payload_length(body.len() as u16);
+        ip.set_next_header(IpNextHeaderProtocols::Icmpv6);
+        // Neighbor discovery requires a hop limit of 255
+        ip.set_hop_limit(255);
+        ip.set_source(source);
+        ip.set_destination(destination);
+    }
+    {
+        let mut ethernet = MutableEthernetPacket::new(&mut frame).expect("buffer sized for Ethernet");
+        ethernet.set_destination(multicast_mac(destination));
+        ethernet.set_source(mac);
+        ethernet.set_ethertype(EtherTypes::Ipv6);
+    }
+    frame
+}
+
+/// The 33:33:xx:xx:xx:xx MAC an IPv6 multicast group maps to
+fn multicast_mac(group: Ipv6Addr) -> MacAddr {
+    let o = group.octets();
+    MacAddr::new(0x33, 0x33, o[12], o[13], o[14], o[15])
+}
+
+/// ff02::1:ffXX:XXXX for the low 24 bits of the target
+fn solicited_node(target: Ipv6Addr) -> Ipv6Addr {
+    let o = target.octets();
+    Ipv6Addr::new(0xff02, 0, 0, 0, 0, 1, 0xff00 | o[13] as u16, u16::from_be_bytes([o[14], o[15]]))
+}
+
+/// fe80:: address a host derives from its MAC when not using privacy addresses
+fn eui64_link_local(mac: MacAddr) -> Ipv6Addr {
+    let m = mac.octets();
+    Ipv6Addr::new(
+        0xfe80,
+        0,
+        0,
+        0,
+        u16::from_be_bytes([m[0] ^ 0x02, m[1]]),
+        u16::from_be_bytes([m[2], 0xff]),
+        u16::from_be_bytes([0xfe, m[3]]),
+        u16::from_be_bytes([m[4], m[5]]),
+    )
+}
+
+/// Run a discovery sweep and print every host that answered
+pub async fn show_discovery(interface: Option<String>, timeout_secs: u64) -> Result<()> {
+    let interface = match interface {
+        Some(name) => name,
+        None => network::get_primary_interface()?.name,
+    };
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              📡  NetNinja LAN Discovery  📡               ");
+    println!("═══════════════════════════════════════════════════════════\n");
+    println!("Probing {} with ARP and ICMPv6, waiting {}s for replies...\n", interface, timeout_secs);
+
+    let sweep_interface = interface.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        discover_peers(&sweep_interface, Duration::from_secs(timeout_secs))
+    })
+    .await??;
+
+    if !result.swept.is_empty() {
+        println!("Swept: {}", result.swept.join(", "));
+    }
+    if result.peers.is_empty() {
+        println!("⚠️  No hosts answered on {}", interface);
+        println!();
+        return Ok(());
+    }
+
+    println!("\n{:<40} {:<18} {:<24} {:<15} {:<12} {:>8}", "IP Address", "MAC Address", "Vendor", "Device Type", "Method", "RTT");
+    println!("{}", "─".repeat(122));
+    for discovered in &result.peers {
+        let peer = &discovered.peer;
+        let vendor: String = peer.vendor.chars().take(23).collect();
+        println!(
+            "{:<40} {:<18} {:<24} {:<15} {:<12} {:>6.1}ms",
+            peer.ip,
+            peer.mac.as_deref().unwrap_or("N/A"),
+            vendor,
+            peer.device_type,
+            discovered.method,
+            discovered.response_time_ms
+        );
+    }
+    println!("\n✅ {} live host(s)", result.peers.len());
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+
+const CLASS_IN: u16 = 1;
+
+/// Upper bound on compression pointer hops, to stop loops in malformed packets
+const MAX_POINTER_HOPS: usize = 32;
+
+#[derive(Debug, Clone)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ptr(String),
+    Srv { port: u16, target: String },
+    Txt(Vec<String>),
+    Other(Vec<u8>),
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsRecord {
+    pub name: String,
+    pub data: RecordData,
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsMessage {
+    pub flags: u16,
+    /// Answer, authority and additional records in wire order
+    pub records: Vec<DnsRecord>,
+}
+
+impl DnsMessage {
+    pub fn is_response(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*buf.get(offset)?, *buf.get(offset + 1)?]))
+}
+
+/// Read a possibly compressed name, returning it and the offset just past it
+fn read_name(buf: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    let mut hops = 0;
+
+    loop {
+        let len = *buf.get(offset)? as usize;
+        if len == 0 {
+            offset += 1;
+            break;
+        }
+        if len & 0xc0 == 0xc0 {
+            hops += 1;
+            if hops > MAX_POINTER_HOPS {
+                return None;
+            }
+            let pointer = (read_u16(buf, offset)? & 0x3fff) as usize;
+            end.get_or_insert(offset + 2);
+            offset = pointer;
+            continue;
+        }
+        let label = buf.get(offset + 1..offset + 1 + len)?;
+        // Escape dots inside a label so names can be split back into labels
+        labels.push(String::from_utf8_lossy(label).replace('.', "\\."));
+        offset += 1 + len;
+    }
+
+    Some((labels.join("."), end.unwrap_or(offset)))
+}
+
+/// Parse a DNS, mDNS or NetBIOS name service message
+pub fn parse_message(buf: &[u8]) -> Option<DnsMessage> {
+    let flags = read_u16(buf, 2)?;
+    let qdcount = read_u16(buf, 4)?;
+    let record_count = read_u16(buf, 6)? as usize + read_u16(buf, 8)? as usize + read_u16(buf, 10)? as usize;
+
+    // Questions are skipped; only what the sender asserts is of interest
+    let mut offset = 12;
+    for _ in 0..qdcount {
+        offset = read_name(buf, offset)?.1 + 4;
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..record_count {
+        let (name, next) = read_name(buf, offset)?;
+        let rtype = read_u16(buf, next)?;
+        let rdlen = read_u16(buf, next + 8)? as usize;
+        let rdata_start = next + 10;
+        let rdata = buf.get(rdata_start..rdata_start + rdlen)?;
+
+        let data = match rtype {
+            TYPE_A if rdlen == 4 => RecordData::A(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3])),
+            TYPE_AAAA if rdlen == 16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(rdata);
+                RecordData::Aaaa(Ipv6Addr::from(octets))
+            }
+            TYPE_PTR => RecordData::Ptr(read_name(buf, rdata_start)?.0),
+            TYPE_SRV => RecordData::Srv {
+                port: read_u16(buf, rdata_start + 4)?,
+                target: read_name(buf, rdata_start + 6)?.0,
+            },
+            TYPE_TXT => {
+                let mut strings = Vec::new();
+                let mut i = 0;
+                while i < rdata.len() {
+                    let len = rdata[i] as usize;
+                    if let Some(s) = rdata.get(i + 1..i + 1 + len) {
+                        if !s.is_empty() {
+                            strings.push(String::from_utf8_lossy(s).to_string());
+                        }
+                    }
+                    i += 1 + len;
+                }
+                RecordData::Txt(strings)
+            }
+            _ => RecordData::Other(rdata.to_vec()),
+        };
+
+        records.push(DnsRecord { name, data });
+        offset = rdata_start + rdlen;
+    }
+
+    Some(DnsMessage { flags, records })
+}
+
+/// Split a name on unescaped dots, unescaping `\.` within labels
+pub fn split_labels(name: &str) -> Vec<String> {
+    let mut labels = Vec::new();
+    let mut current = String::new();
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => current.extend(chars.next()),
+            '.' => labels.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        labels.push(current);
+    }
+    labels
+}
+
+/// Build a standard query for the given (name, type) questions
+pub fn build_query(id: u16, questions: &[(&str, u16)]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(512);
+    buf.extend_from_slice(&id.to_be_bytes());
+    buf.extend_from_slice(&[0, 0]);
+    buf.extend_from_slice(&(questions.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+    for (name, qtype) in questions {
+        for label in split_labels(name) {
+            let label = &label.as_bytes()[..label.len().min(63)];
+            buf.push(label.len() as u8);
+            buf.extend_from_slice(label);
+        }
+        buf.push(0);
+        buf.extend_from_slice(&qtype.to_be_bytes());
+        buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    }
+
+    buf
+}
+
+/// Decode a NetBIOS first-level encoded name into the name and its service suffix
+pub fn decode_netbios_name(encoded: &str) -> Option<(String, u8)> {
+    let label = encoded.split('.').next()?.as_bytes();
+    if label.len() != 32 {
+        return None;
+    }
+
+    let bytes: Vec<u8> = label
+        .chunks_exact(2)
+        .map(|pair| ((pair[0].wrapping_sub(b'A')) << 4) | (pair[1].wrapping_sub(b'A') & 0x0f))
+        .collect();
+
+    let name = String::from_utf8_lossy(&bytes[..15]).trim_end().to_string();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+        return None;
+    }
+    Some((name, bytes[15]))
+}
@@ -0,0 +1,275 @@
+use anyhow::{Context, Result};
+use crate::monitor::{self, Section, StatusSnapshot};
+use crate::{alerts, daemon, network, security};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::process::Command;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Sections collected per scrape when no daemon is running.
+/// The security scan is left out: it reads the logs and updates alert state, which a scrape should not do.
+const SCRAPE_SECTIONS: [Section; 2] = [Section::Vpn, Section::Ports];
+
+/// Without a daemon, fill in the security values that need no scan: the firewall and the recorded open alerts
+fn collect_scrape_snapshot() -> StatusSnapshot {
+    let mut snapshot = monitor::collect_snapshot(&SCRAPE_SECTIONS);
+    snapshot.firewall_active = security::check_firewall_status().ok();
+    match alerts::AlertState::load() {
+        Ok(state) => snapshot.alerts = Some(state.active()),
+        Err(e) => {
+            snapshot.errors.insert(Section::Security.name().to_string(), e.to_string());
+        }
+    }
+    snapshot
+}
+
+/// Counter family name, help text and the field it reads
+type CounterFamily = (&'static str, &'static str, fn(&network::InterfaceCounters) -> u64);
+
+/// Link state family name, help text and the flag it reads
+type LinkFamily = (&'static str, &'static str, fn(&network::LinkDetails) -> bool);
+
+/// Text exposition format writer
+#[derive(Default)]
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    /// Start a metric family with its HELP and TYPE lines
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+            .collect();
+        if labels.is_empty() {
+            let _ = writeln!(self.out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(self.out, "{}{{{}}} {}", name, labels.join(","), value);
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn gauge_bool(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+/// Neighbor cache entries per NUD state, e.g. REACHABLE, STALE, FAILED
+fn neighbor_states() -> Result<BTreeMap<String, usize>> {
+    let output = Command::new("ip")
+        .args(["-j", "neigh", "show"])
+        .output()
+        .context("Failed to execute ip neigh command")?;
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+
+    let mut states = BTreeMap::new();
+    for entry in entries {
+        // [{"dst":"192.0.2.1","dev":"eth0","lladdr":"...","state":["STALE"]}]
+        let state = entry["state"][0].as_str().unwrap_or("NONE").to_string();
+        *states.entry(state).or_insert(0) += 1;
+    }
+    Ok(states)
+}
+
+/// Render every metric; collectors that fail are reported through netninja_collector_up
+fn render_metrics(snapshot: &StatusSnapshot) -> String {
+    let mut m = Exposition::default();
+    let mut collectors: Vec<(&str, bool)> = Vec::new();
+
+    let counters = network::get_interface_counters();
+    collectors.push(("interfaces", counters.is_ok()));
+    let counters = counters.unwrap_or_default();
+    let counter_families: [CounterFamily; 8] = [
+        ("netninja_interface_receive_bytes_total", "Bytes received", |c| c.rx_bytes),
+        ("netninja_interface_transmit_bytes_total", "Bytes transmitted", |c| c.tx_bytes),
+        ("netninja_interface_receive_packets_total", "Packets received", |c| c.rx_packets),
+        ("netninja_interface_transmit_packets_total", "Packets transmitted", |c| c.tx_packets),
+        ("netninja_interface_receive_errors_total", "Receive errors", |c| c.rx_errors),
+        ("netninja_interface_transmit_errors_total", "Transmit errors", |c| c.tx_errors),
+        ("netninja_interface_receive_drop_total", "Received packets dropped", |c| c.rx_dropped),
+        ("netninja_interface_transmit_drop_total", "Transmitted packets dropped", |c| c.tx_dropped),
+    ];
+    for (name, help, field) in counter_families {
+        m.family(name, "counter", help);
+        for c in &counters {
+            m.sample(name, &[("interface", &c.name)], field(c) as f64);
+        }
+    }
+
+    let links = network::get_link_details();
+    collectors.push(("links", links.is_ok()));
+    let links = links.unwrap_or_default();
+    let link_families: [LinkFamily; 2] = [
+        ("netninja_interface_up", "Whether the interface is administratively up", |l| l.is_up()),
+        ("netninja_interface_oper_up", "Whether the interface has carrier (operstate UP)", |l| l.operstate == "UP"),
+    ];
+    for (name, help, state) in link_families {
+        m.family(name, "gauge", help);
+        for link in &links {
+            let kind = link.kind.clone().unwrap_or_else(|| link.link_type.clone());
+            m.sample(name, &[("interface", &link.name), ("kind", &kind)], gauge_bool(state(link)));
+        }
+    }
+
+    collectors.push(("vpn", !snapshot.errors.contains_key(Section::Vpn.name())));
+    if let Some(vpn) = &snapshot.vpn {
+        m.family("netninja_vpn_connected", "gauge", "Whether any VPN tunnel is up");
+        m.sample("netninja_vpn_connected", &[], gauge_bool(vpn.is_connected));
+        m.family("netninja_vpn_default_route", "gauge", "Whether the default route goes through a VPN tunnel");
+        m.sample("netninja_vpn_default_route", &[], gauge_bool(vpn.default_route_via_vpn));
+        m.family("netninja_vpn_tunnel_up", "gauge", "Whether each detected VPN tunnel is up");
+        for tunnel in &vpn.tunnels {
+            m.sample("netninja_vpn_tunnel_up", &[("tunnel", &tunnel.name), ("type", &tunnel.vpn_type)], gauge_bool(tunnel.is_up));
+        }
+    }
+
+    collectors.push(("ports", !snapshot.errors.contains_key(Section::Ports.name())));
+    if let Some(ports) = &snapshot.open_ports {
+        let mut by_protocol: BTreeMap<&str, usize> = BTreeMap::from([("tcp", 0), ("udp", 0)]);
+        for (protocol, _, _) in ports {
+            *by_protocol.entry(protocol.as_str()).or_insert(0) += 1;
+        }
+        m.family("netninja_listening_ports", "gauge", "Listening sockets per protocol");
+        for (protocol, count) in by_protocol {
+            m.sample("netninja_listening_ports", &[("protocol", protocol)], count as f64);
+        }
+    }
+
+    let neighbors = neighbor_states();
+    collectors.push(("neighbors", neighbors.is_ok()));
+    m.family("netninja_neighbors", "gauge", "Neighbor cache entries per state");
+    for (state, count) in neighbors.unwrap_or_default() {
+        m.sample("netninja_neighbors", &[("state", &state)], count as f64);
+    }
+
+    collectors.push(("security", !snapshot.errors.contains_key(Section::Security.name())));
+    if let Some(alerts) = &snapshot.alerts {
+        let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for alert in alerts {
+            *counts.entry((alert.severity.label(), alert.category.label())).or_insert(0) += 1;
+        }
+        m.family("netninja_security_alerts", "gauge", "Current security alerts by severity and category");
+        for ((severity, category), count) in counts {
+            m.sample("netninja_security_alerts", &[("severity", severity), ("category", category)], count as f64);
+        }
+    }
+    if let Some(active) = snapshot.firewall_active {
+        m.family("netninja_firewall_active", "gauge", "Whether a host firewall is active");
+        m.sample("netninja_firewall_active", &[], gauge_bool(active));
+    }
+
+    m.family("netninja_collector_up", "gauge", "Whether the collector succeeded during this scrape");
+    for (collector, ok) in collectors {
+        m.sample("netninja_collector_up", &[("collector", collector)], gauge_bool(ok));
+    }
+
+    m.out
+}
+
+/// Collect and render a scrape, reusing the daemon's snapshot when it is running
+async fn scrape() -> Result<String> {
+    let snapshot = daemon::fetch_snapshot().await;
+    let body = tokio::task::spawn_blocking(move || {
+        let snapshot = snapshot.unwrap_or_else(collect_scrape_snapshot);
+        render_metrics(&snapshot)
+    })
+    .await?;
+    Ok(body)
+}
+
+/// Answer one HTTP request
+async fn handle(stream: TcpStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut request_line = String::new();
+    tokio::time::timeout(REQUEST_TIMEOUT, async {
+        reader.read_line(&mut request_line).await?;
+        // Skip the headers; nothing in them changes the response
+        let mut header = String::new();
+        loop {
+            header.clear();
+            if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+        Ok::<_, std::io::Error>(())
+    })
+    .await??;
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = path.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => match scrape().await {
+            Ok(body) => ("200 OK", CONTENT_TYPE, body),
+            Err(e) => ("500 Internal Server Error", "text/plain", format!("{}\n", e)),
+        },
+        ("GET", "/") => (
+            "200 OK",
+            "text/html",
+            "<html><body><h1>NetNinja Exporter</h1><a href=\"/metrics\">Metrics</a></body></html>\n".to_string(),
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain", "Method not allowed\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    writer.write_all(response.as_bytes()).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+/// Serve Prometheus metrics on `listen` until interrupted
+pub async fn run_exporter(listen: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("             📡  NetNinja Prometheus Exporter  📡          ");
+    println!("═══════════════════════════════════════════════════════════\n");
+    println!("Serving metrics on http://{}/metrics", listen);
+    if !listen.ip().is_loopback() {
+        println!("⚠️  Listening beyond localhost exposes interface, port and alert details");
+    }
+    println!("\nPress Ctrl+C to stop");
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                if let Ok((stream, _)) = accepted {
+                    tokio::spawn(handle(stream));
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    println!("\n👋 Exporter stopped");
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use anyhow::Result;
+use crate::firewall::{self, FirewallRuleset, PortExposure};
+use crate::network::{self, InterfaceClass, ListeningSocket, NetworkInterface};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use crate::state;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+/// State file remembering which services were exposed on the previous run
+const EXPOSURE_STATE_FILE: &str = "exposure.json";
+
+/// Ports whose services should never be reachable from untrusted networks
+const SENSITIVE_PORTS: &[u16] = &[
+    23, 69, 111, 135, 139, 161, 445, 512, 513, 514, 873, 2049, 2375, 2376, 2379, 3306, 3389,
+    5432, 5900, 5984, 6379, 8086, 9200, 9300, 10250, 11211, 27017,
+];
+
+/// Ports commonly offered to the public on purpose
+const PUBLIC_SERVICE_PORTS: &[u16] = &[25, 53, 80, 443, 465, 587, 993, 995, 8080, 8443];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Reachability {
+    Internet,
+    LocalNetwork,
+    Vpn,
+    Containers,
+    LocalOnly,
+    Filtered,
+}
+
+impl std::fmt::Display for Reachability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reachability::Internet => f.pad("INTERNET"),
+            Reachability::LocalNetwork => f.pad("LAN"),
+            Reachability::Vpn => f.pad("VPN"),
+            Reachability::Containers => f.pad("CONTAINERS"),
+            Reachability::LocalOnly => f.pad("LOCAL"),
+            Reachability::Filtered => f.pad("FILTERED"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceExposure {
+    pub socket: ListeningSocket,
+    pub interfaces: Vec<(String, InterfaceClass)>,
+    /// Firewall verdict for new connections, None if the ruleset could not be read
+    pub firewall: Option<PortExposure>,
+    pub reachability: Reachability,
+    pub risk: AlertSeverity,
+}
+
+impl ServiceExposure {
+    /// Stable identifier used to recognise the same service across runs
+    pub fn key(&self) -> String {
+        format!("{}/{}:{}", self.socket.protocol, self.socket.address, self.socket.port)
+    }
+
+    pub fn is_exposed(&self) -> bool {
+        matches!(self.reachability, Reachability::Internet | Reachability::LocalNetwork)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExposureState {
+    exposed: BTreeSet<String>,
+}
+
+/// Cross listening sockets with bind addresses, interface classes and the firewall
+pub fn analyze() -> Result<Vec<ServiceExposure>> {
+    let sockets = network::get_listening_sockets()?;
+    let interfaces = network::get_interfaces()?;
+    let ruleset = firewall::read_ruleset().ok();
+
+    Ok(sockets
+        .into_iter()
+        .map(|socket| assess_socket(socket, &interfaces, ruleset.as_ref()))
+        .collect())
+}
+
+fn assess_socket(
+    socket: ListeningSocket,
+    interfaces: &[NetworkInterface],
+    ruleset: Option<&FirewallRuleset>,
+) -> ServiceExposure {
+    let bind_ip = socket.address.parse::<IpAddr>().ok();
+
+    // Interfaces the listener accepts connections on
+    let reachable: Vec<(String, InterfaceClass)> = if socket.is_loopback() {
+        Vec::new()
+    } else {
+        interfaces
+            .iter()
+            .filter(|iface| iface.is_up)
+            .filter(|iface| match &socket.scope_interface {
+                Some(scope) => &iface.name == scope,
+                None => true,
+            })
+            .filter(|iface| socket.is_wildcard() || iface.ip_addresses.contains(&socket.address))
+            .map(|iface| (iface.name.clone(), network::classify_interface(iface)))
+            .filter(|(_, class)| *class != InterfaceClass::Loopback)
+            .collect()
+    };
+
+    // "::" and "*" listeners normally accept IPv4 as well
+    let families: &[&str] = match bind_ip {
+        Some(IpAddr::V4(_)) => &["ip"],
+        Some(IpAddr::V6(v6)) if !v6.is_unspecified() => &["ip6"],
+        _ => &["ip", "ip6"],
+    };
+    let firewall = ruleset.map(|rs| {
+        families
+            .iter()
+            .map(|family| rs.evaluate_port(family, &socket.protocol, socket.port).exposure)
+            .min_by_key(|exposure| match exposure {
+                PortExposure::Open => 0,
+                PortExposure::Restricted => 1,
+                PortExposure::Blocked => 2,
+            })
+            .unwrap_or(PortExposure::Open)
+    });
+
+    let has_class = |class: InterfaceClass| reachable.iter().any(|(_, c)| *c == class);
+    let reachability = if reachable.is_empty() {
+        Reachability::LocalOnly
+    } else if firewall == Some(PortExposure::Blocked) {
+        Reachability::Filtered
+    } else if has_class(InterfaceClass::Public) {
+        Reachability::Internet
+    } else if has_class(InterfaceClass::Private) {
+        Reachability::LocalNetwork
+    } else if has_class(InterfaceClass::Vpn) {
+        Reachability::Vpn
+    } else {
+        Reachability::Containers
+    };
+
+    let risk = rate_risk(socket.port, reachability, firewall);
+
+    ServiceExposure {
+        socket,
+        interfaces: reachable,
+        firewall,
+        reachability,
+        risk,
+    }
+}
+
+/// Rate how risky it is for a port to be reachable the given way
+fn rate_risk(port: u16, reachability: Reachability, firewall: Option<PortExposure>) -> AlertSeverity {
+    let sensitive = SENSITIVE_PORTS.contains(&port);
+    let public_service = PUBLIC_SERVICE_PORTS.contains(&port);
+    let restricted = firewall == Some(PortExposure::Restricted);
+
+    match reachability {
+        Reachability::Internet => {
+            if sensitive {
+                if restricted { AlertSeverity::High } else { AlertSeverity::Critical }
+            } else if public_service || restricted {
+                AlertSeverity::Low
+            } else if port == 22 {
+                AlertSeverity::Medium
+            } else {
+                AlertSeverity::High
+            }
+        }
+        Reachability::LocalNetwork => {
+            if sensitive && !restricted {
+                AlertSeverity::Medium
+            } else {
+                AlertSeverity::Low
+            }
+        }
+        Reachability::Vpn | Reachability::Containers => AlertSeverity::Low,
+        Reachability::LocalOnly | Reachability::Filtered => AlertSeverity::Info,
+    }
+}
+
+/// Raise alerts for services that became exposed since the previous security scan, and record the new baseline
+pub fn check_new_exposures(exposures: &[ServiceExposure]) -> Result<Vec<SecurityAlert>> {
+    let previous: Option<ExposureState> = state::load(EXPOSURE_STATE_FILE)?;
+    let current = ExposureState {
+        exposed: exposures
+            .iter()
+            .filter(|e| e.is_exposed())
+            .map(|e| e.key())
+            .collect(),
+    };
+    state::save(EXPOSURE_STATE_FILE, &current)?;
+
+    // The first run only records the baseline
+    Ok(previous.map(|previous| new_exposures(exposures, &previous)).unwrap_or_default())
+}
+
+/// Alerts for exposed services missing from a baseline
+fn new_exposures(exposures: &[ServiceExposure], previous: &ExposureState) -> Vec<SecurityAlert> {
+    exposures
+        .iter()
+        .filter(|e| e.is_exposed() && !previous.exposed.contains(&e.key()))
+        .map(|e| SecurityAlert {
+            timestamp: Utc::now(),
+            severity: e.risk.clone(),
+            category: AlertCategory::ExposedService,
+            message: format!(
+                "Newly exposed service: {} port {} reachable from {}",
+                e.socket.protocol, e.socket.port, e.reachability
+            ),
+            details: Some(format!(
+                "bind {} process {} interfaces {}",
+                e.socket.address,
+                e.socket.process.as_deref().unwrap_or("unknown"),
+                e.interfaces.iter().map(|(name, class)| format!("{}({})", name, class)).collect::<Vec<_>>().join(", ")
+            )),
+            subject: Some(format!("{}/{}", e.socket.protocol, e.socket.port)),
+        })
+        .collect()
+}
+
+/// Print the service exposure report
+pub async fn show_exposure() -> Result<()> {
+    let mut exposures = analyze()?;
+    // Read-only: the baseline belongs to the security scan, which raises the alerts
+    let alerts = match state::load::<ExposureState>(EXPOSURE_STATE_FILE)? {
+        Some(previous) => new_exposures(&exposures, &previous),
+        None => Vec::new(),
+    };
+
+    exposures.sort_by_key(|e| (e.reachability as u8, e.socket.port));
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("             🌐  NetNinja Exposure Report  🌐              ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    println!("{:<6} {:<24} {:<7} {:<16} {:<11} {:<11} {:<8}",
+        "Proto", "Bind Address", "Port", "Process", "Firewall", "Reach", "Risk");
+    println!("{}", "─".repeat(87));
+    for e in &exposures {
+        let firewall = e.firewall.map(|f| f.to_string()).unwrap_or_else(|| "UNKNOWN".to_string());
+        println!("{:<6} {:<24} {:<7} {:<16} {:<11} {:<11} {:<8}",
+            e.socket.protocol,
+            e.socket.address,
+            e.socket.port,
+            e.socket.process.as_deref().unwrap_or("-"),
+            firewall,
+            e.reachability,
+            e.risk.to_string());
+    }
+
+    let internet = exposures.iter().filter(|e| e.reachability == Reachability::Internet).count();
+    println!();
+    println!("Services reachable from the internet: {}", internet);
+
+    if !alerts.is_empty() {
+        println!("\n🚨 Newly exposed since the last security scan:");
+        for alert in &alerts {
+            println!("  [{}] {}", alert.severity, alert.message);
+        }
+    }
+
+    if exposures.iter().any(|e| e.firewall.is_none()) {
+        println!("\n⚠️  Firewall ruleset unreadable; run with sudo for accurate filtering data");
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,651 @@
+use anyhow::{Context, Result};
+use crate::network::{self, NetworkPeer};
+use crate::{dhcp, dnswire, state};
+use chrono::{DateTime, Utc};
+use pnet::datalink::{self, Channel};
+use pnet::ipnetwork::IpNetwork;
+use pnet::packet::dhcp::{DhcpOperations, DhcpPacket};
+use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::ipv6::Ipv6Packet;
+use pnet::packet::tcp::{TcpFlags, TcpPacket};
+use pnet::packet::udp::UdpPacket;
+use pnet::packet::Packet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// State file with the fingerprints from previous captures, keyed by MAC
+const FINGERPRINT_STATE_FILE: &str = "fingerprints.json";
+
+/// Guesses below this confidence do not override the MAC-based guess
+const MIN_APPLY_CONFIDENCE: u8 = 25;
+
+/// Total evidence weight at which a unanimous guess reaches full confidence
+const FULL_CONFIDENCE_WEIGHT: u32 = 10;
+
+const DHCP_SERVER_PORT: u16 = 67;
+const DHCP_CLIENT_PORT: u16 = 68;
+const NETBIOS_NS_PORT: u16 = 137;
+const SSDP_PORT: u16 = 1900;
+const MDNS_PORT: u16 = 5353;
+
+const OPT_HOSTNAME: u8 = 12;
+const OPT_PARAM_LIST: u8 = 55;
+const OPT_VENDOR_CLASS: u8 = 60;
+
+/// Where a hostname came from, in increasing order of trust
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum HostnameSource {
+    NetBios,
+    Mdns,
+    Dhcp,
+}
+
+/// One piece of evidence and what it votes for
+#[derive(Debug, Clone)]
+struct Observation {
+    source: &'static str,
+    detail: String,
+    os: Option<&'static str>,
+    device_type: Option<&'static str>,
+    weight: u32,
+}
+
+#[derive(Debug, Default)]
+struct PeerEvidence {
+    ips: BTreeSet<String>,
+    hostname: Option<(HostnameSource, String)>,
+    observations: Vec<Observation>,
+    /// Deduplicates repeated announcements
+    seen: BTreeSet<String>,
+}
+
+impl PeerEvidence {
+    fn observe(&mut self, observation: Observation) {
+        let key = format!("{}:{}", observation.source, observation.detail);
+        if self.seen.insert(key) {
+            self.observations.push(observation);
+        }
+    }
+
+    fn set_hostname(&mut self, source: HostnameSource, name: &str) {
+        let name = name.trim().trim_end_matches('.').trim_end_matches(".local");
+        if name.is_empty() {
+            return;
+        }
+        if self.hostname.as_ref().map(|(s, _)| source > *s).unwrap_or(true) {
+            self.hostname = Some((source, name.to_string()));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub mac: String,
+    pub ips: Vec<String>,
+    pub hostname: Option<String>,
+    pub os_guess: Option<String>,
+    pub device_type: Option<String>,
+    /// 0-100, combining agreement between signals and the amount of evidence
+    pub confidence: u8,
+    pub evidence: Vec<String>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Passively listen on an interface and fingerprint every host heard from
+pub fn capture(interface_name: &str, duration: Duration) -> Result<Vec<Fingerprint>> {
+    let interface = datalink::interfaces()
+        .into_iter()
+        .find(|i| i.name == interface_name)
+        .with_context(|| format!("Interface {} not found", interface_name))?;
+    let own_mac = interface.mac;
+
+    let config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(200)),
+        promiscuous: true,
+        ..Default::default()
+    };
+    let mut rx = match datalink::channel(&interface, config)
+        .context("Failed to open datalink channel (are you root?)")?
+    {
+        Channel::Ethernet(_, rx) => rx,
+        _ => return Err(anyhow::anyhow!("Unsupported datalink channel type")),
+    };
+
+    let mut peers: BTreeMap<String, PeerEvidence> = BTreeMap::new();
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        let frame = match rx.next() {
+            Ok(frame) => frame,
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(e) => return Err(e).context("Failed to read from datalink channel"),
+        };
+        let Some(ethernet) = EthernetPacket::new(frame) else {
+            continue;
+        };
+        if Some(ethernet.get_source()) == own_mac {
+            continue;
+        }
+        let mac = ethernet.get_source().to_string();
+        inspect_frame(&ethernet, &interface.ips, peers.entry(mac).or_default());
+    }
+
+    let now = Utc::now();
+    Ok(peers
+        .into_iter()
+        .filter(|(_, evidence)| !evidence.observations.is_empty() || evidence.hostname.is_some())
+        .map(|(mac, evidence)| combine(mac, evidence, now))
+        .collect())
+}
+
+/// Routed traffic arrives with the gateway's MAC, so only sources inside a local prefix describe the sender
+fn is_on_link(source: IpAddr, prefixes: &[IpNetwork]) -> bool {
+    prefixes.iter().any(|prefix| prefix.contains(source))
+}
+
+fn inspect_frame(ethernet: &EthernetPacket, prefixes: &[IpNetwork], evidence: &mut PeerEvidence) {
+    match ethernet.get_ethertype() {
+        EtherTypes::Ipv4 => {
+            let Some(ip) = Ipv4Packet::new(ethernet.payload()) else {
+                return;
+            };
+            // DHCP clients send from 0.0.0.0 before they have an address
+            if !ip.get_source().is_unspecified() {
+                if !is_on_link(ip.get_source().into(), prefixes) {
+                    return;
+                }
+                evidence.ips.insert(ip.get_source().to_string());
+            }
+            inspect_transport(ip.get_next_level_protocol(), ip.get_ttl(), ip.payload(), evidence);
+        }
+        EtherTypes::Ipv6 => {
+            let Some(ip) = Ipv6Packet::new(ethernet.payload()) else {
+                return;
+            };
+            if !ip.get_source().is_unspecified() {
+                if !is_on_link(ip.get_source().into(), prefixes) {
+                    return;
+                }
+                evidence.ips.insert(ip.get_source().to_string());
+            }
+            inspect_transport(ip.get_next_header(), ip.get_hop_limit(), ip.payload(), evidence);
+        }
+        _ => {}
+    }
+}
+
+fn inspect_transport(protocol: IpNextHeaderProtocol, ttl: u8, payload: &[u8], evidence: &mut PeerEvidence) {
+    match protocol {
+        IpNextHeaderProtocols::Tcp => {
+            if let Some(tcp) = TcpPacket::new(payload) {
+                let flags = tcp.get_flags();
+                if flags & TcpFlags::SYN != 0 && flags & TcpFlags::ACK == 0 {
+                    inspect_syn(&tcp, ttl, evidence);
+                }
+            }
+        }
+        IpNextHeaderProtocols::Udp => {
+            let Some(udp) = UdpPacket::new(payload) else {
+                return;
+            };
+            match (udp.get_source(), udp.get_destination()) {
+                (DHCP_CLIENT_PORT, DHCP_SERVER_PORT) => inspect_dhcp(udp.payload(), evidence),
+                (MDNS_PORT, _) => inspect_mdns(udp.payload(), evidence),
+                (SSDP_PORT, _) | (_, SSDP_PORT) => inspect_ssdp(udp.payload(), evidence),
+                (NETBIOS_NS_PORT, _) => inspect_netbios(udp.payload(), evidence),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Initial TTL, assuming the packet crossed fewer than 32 hops
+fn initial_ttl(ttl: u8) -> u8 {
+    match ttl {
+        0..=32 => 32,
+        33..=64 => 64,
+        65..=128 => 128,
+        _ => 255,
+    }
+}
+
+fn inspect_syn(tcp: &TcpPacket, ttl: u8, evidence: &mut PeerEvidence) {
+    // Option order as a compact layout, e.g. "M,S,T,N,W" for Linux
+    let layout = tcp
+        .get_options_iter()
+        .map(|option| match option.get_number().0 {
+            0 => "E",
+            1 => "N",
+            2 => "M",
+            3 => "W",
+            4 => "S",
+            8 => "T",
+            _ => "?",
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let window = tcp.get_window();
+    let ttl = initial_ttl(ttl);
+
+    let (os, device_type, weight) = match (ttl, layout.as_str()) {
+        (128, _) => (Some("Windows"), None, 3),
+        (64, "M,S,T,N,W") => (Some("Linux"), None, 3),
+        (64, l) if l.starts_with("M,N,W,N,N,T,S") => (Some("macOS"), None, 2),
+        (64, _) => (Some("Linux"), None, 1),
+        (255, _) => (Some("Network OS"), Some("Network Device"), 2),
+        _ => (None, None, 0),
+    };
+
+    evidence.observe(Observation {
+        source: "tcp",
+        detail: format!("SYN ttl={} win={} opts={}", ttl, window, layout),
+        os,
+        device_type,
+        weight,
+    });
+}
+
+fn inspect_dhcp(payload: &[u8], evidence: &mut PeerEvidence) {
+    let Some(packet) = DhcpPacket::new(payload) else {
+        return;
+    };
+    if packet.get_op() != DhcpOperations::Request {
+        return;
+    }
+    let Some(options) = dhcp::parse_options(packet.payload()) else {
+        return;
+    };
+    let option = |code: u8| options.iter().find(|(c, _)| *c == code).map(|(_, v)| v.as_slice());
+
+    if let Some(name) = option(OPT_HOSTNAME) {
+        evidence.set_hostname(HostnameSource::Dhcp, &String::from_utf8_lossy(name));
+    }
+
+    if let Some(vendor) = option(OPT_VENDOR_CLASS) {
+        let vendor = String::from_utf8_lossy(vendor).to_string();
+        let lower = vendor.to_lowercase();
+        let (os, device_type, weight) = if lower.starts_with("msft") {
+            (Some("Windows"), Some("Computer"), 5)
+        } else if lower.starts_with("android-dhcp") {
+            (Some("Android"), Some("Mobile Device"), 5)
+        } else if lower.starts_with("udhcp") {
+            (Some("Embedded Linux"), Some("IoT Device"), 4)
+        } else if lower.starts_with("dhcpcd") && lower.contains("linux") {
+            (Some("Linux"), None, 3)
+        } else if lower.contains("cisco") || lower.contains("aruba") || lower.contains("ubnt") {
+            (Some("Network OS"), Some("Network Device"), 4)
+        } else {
+            (None, None, 0)
+        };
+        evidence.observe(Observation {
+            source: "dhcp",
+            detail: format!("vendor class \"{}\"", vendor),
+            os,
+            device_type,
+            weight,
+        });
+    }
+
+    if let Some(params) = option(OPT_PARAM_LIST) {
+        let (os, device_type, weight) = classify_param_list(params);
+        evidence.observe(Observation {
+            source: "dhcp",
+            detail: format!(
+                "option 55 [{}]",
+                params.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",")
+            ),
+            os,
+            device_type,
+            weight,
+        });
+    }
+}
+
+/// Match the DHCP parameter request list against well-known client stacks
+fn classify_param_list(params: &[u8]) -> (Option<&'static str>, Option<&'static str>, u32) {
+    let has = |code: u8| params.contains(&code);
+
+    if has(249) && has(252) && has(43) {
+        (Some("Windows"), Some("Computer"), 4)
+    } else if params.starts_with(&[1, 121, 3, 6, 15, 119, 252]) {
+        if has(95) || has(44) {
+            (Some("macOS"), Some("Computer"), 4)
+        } else {
+            (Some("iOS"), Some("Mobile Device"), 4)
+        }
+    } else if params.starts_with(&[1, 3, 6, 15, 26, 28, 51, 58, 59]) {
+        (Some("Android"), Some("Mobile Device"), 3)
+    } else if params == [1, 3, 6, 12, 15, 28, 42] {
+        (Some("Embedded Linux"), Some("IoT Device"), 3)
+    } else if has(2) && has(42) && has(119) && has(28) {
+        (Some("Linux"), None, 3)
+    } else {
+        (None, None, 0)
+    }
+}
+
+/// Service types announced over mDNS and what they imply
+const MDNS_SERVICES: &[(&str, Option<&str>, Option<&str>, u32)] = &[
+    ("_apple-mobdev2._tcp", Some("iOS"), Some("Mobile Device"), 2),
+    ("_googlecast._tcp", Some("Google Cast"), Some("Media Device"), 3),
+    ("_amzn-wplay._tcp", Some("Fire OS"), Some("Media Device"), 3),
+    ("_spotify-connect._tcp", None, Some("Media Device"), 1),
+    ("_ipp._tcp", None, Some("Printer"), 3),
+    ("_ipps._tcp", None, Some("Printer"), 3),
+    ("_printer._tcp", None, Some("Printer"), 3),
+    ("_pdl-datastream._tcp", None, Some("Printer"), 3),
+    ("_hap._tcp", None, Some("IoT Device"), 2),
+    ("_workstation._tcp", Some("Linux"), Some("Computer"), 2),
+];
+
+fn inspect_mdns(payload: &[u8], evidence: &mut PeerEvidence) {
+    let Some(message) = dnswire::parse_message(payload) else {
+        return;
+    };
+    // Only what a host announces about itself, not what it asks for
+    if !message.is_response() {
+        return;
+    }
+
+    for record in &message.records {
+        match &record.data {
+            // Sleep proxies answer for other hosts, so only trust names for the sender's own addresses
+            dnswire::RecordData::A(addr) if evidence.ips.contains(&addr.to_string()) => {
+                evidence.set_hostname(HostnameSource::Mdns, &record.name);
+            }
+            dnswire::RecordData::Aaaa(addr) if evidence.ips.contains(&addr.to_string()) => {
+                evidence.set_hostname(HostnameSource::Mdns, &record.name);
+            }
+            dnswire::RecordData::Txt(entries) if record.name.contains("._device-info._tcp") => {
+                if let Some(model) = entries.iter().find_map(|e| e.strip_prefix("model=")) {
+                    let (os, device_type, weight) = classify_apple_model(model);
+                    evidence.observe(Observation {
+                        source: "mdns",
+                        detail: format!("model {}", model),
+                        os,
+                        device_type,
+                        weight,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        let service_name = match &record.data {
+            dnswire::RecordData::Ptr(target) => target.as_str(),
+            _ => record.name.as_str(),
+        };
+        for (service, os, device_type, weight) in MDNS_SERVICES {
+            if service_name.contains(service) {
+                evidence.observe(Observation {
+                    source: "mdns",
+                    detail: format!("announces {}", service),
+                    os: *os,
+                    device_type: *device_type,
+                    weight: *weight,
+                });
+            }
+        }
+    }
+}
+
+fn classify_apple_model(model: &str) -> (Option<&'static str>, Option<&'static str>, u32) {
+    if model.starts_with("iPhone") {
+        (Some("iOS"), Some("Mobile Device"), 5)
+    } else if model.starts_with("iPad") {
+        (Some("iPadOS"), Some("Tablet"), 5)
+    } else if model.starts_with("AppleTV") {
+        (Some("tvOS"), Some("Media Device"), 5)
+    } else if model.starts_with("AudioAccessory") {
+        (Some("audioOS"), Some("Media Device"), 5)
+    } else if model.starts_with("Mac") || model.starts_with("iMac") {
+        (Some("macOS"), Some("Computer"), 5)
+    } else {
+        (None, None, 0)
+    }
+}
+
+fn inspect_ssdp(payload: &[u8], evidence: &mut PeerEvidence) {
+    let text = String::from_utf8_lossy(payload);
+    let header = |name: &str| {
+        text.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+        })
+    };
+
+    if let Some(server) = header("SERVER").or_else(|| header("USER-AGENT")) {
+        let lower = server.to_lowercase();
+        let (os, device_type, weight) = if lower.contains("roku") {
+            (Some("Roku OS"), Some("Media Device"), 3)
+        } else if lower.contains("sonos") {
+            (Some("Sonos"), Some("Media Device"), 3)
+        } else if lower.contains("windows") {
+            (Some("Windows"), None, 2)
+        } else if lower.contains("darwin") || lower.contains("mac os") {
+            (Some("macOS"), None, 2)
+        } else if lower.contains("android") {
+            (Some("Android"), None, 2)
+        } else if lower.contains("linux") {
+            (Some("Linux"), None, 1)
+        } else {
+            (None, None, 0)
+        };
+        evidence.observe(Observation {
+            source: "ssdp",
+            detail: format!("server \"{}\"", server),
+            os,
+            device_type,
+            weight,
+        });
+    }
+
+    if let Some(target) = header("NT").or_else(|| header("ST")) {
+        let device_type = if target.contains("InternetGatewayDevice") {
+            Some("Router")
+        } else if target.contains("MediaRenderer") || target.contains("MediaServer") {
+            Some("Media Device")
+        } else if target.contains("Printer") {
+            Some("Printer")
+        } else {
+            None
+        };
+        if device_type.is_some() {
+            evidence.observe(Observation {
+                source: "ssdp",
+                detail: format!("advertises {}", target),
+                os: None,
+                device_type,
+                weight: 3,
+            });
+        }
+    }
+}
+
+fn inspect_netbios(payload: &[u8], evidence: &mut PeerEvidence) {
+    let Some(message) = dnswire::parse_message(payload) else {
+        return;
+    };
+
+    // Registrations carry the name in an additional record, responses in answers
+    for record in &message.records {
+        let dnswire::RecordData::Other(data) = &record.data else {
+            continue;
+        };
+        let unique = data.first().map(|flags| flags & 0x80 == 0).unwrap_or(false);
+        if let Some((name, suffix)) = dnswire::decode_netbios_name(&record.name) {
+            if unique && (suffix == 0x00 || suffix == 0x20) {
+                evidence.set_hostname(HostnameSource::NetBios, &name);
+                evidence.observe(Observation {
+                    source: "netbios",
+                    detail: format!("name {}", name),
+                    os: Some("Windows"),
+                    device_type: Some("Computer"),
+                    weight: 2,
+                });
+            }
+        }
+    }
+}
+
+/// Weigh the votes for OS and device type into a single guess
+fn combine(mac: String, evidence: PeerEvidence, now: DateTime<Utc>) -> Fingerprint {
+    fn vote<'a>(votes: impl Iterator<Item = (&'a str, u32)>) -> Option<(String, u32, u32)> {
+        let mut totals: BTreeMap<&str, u32> = BTreeMap::new();
+        for (label, weight) in votes {
+            *totals.entry(label).or_default() += weight;
+        }
+        let total: u32 = totals.values().sum();
+        totals
+            .into_iter()
+            .max_by_key(|(_, weight)| *weight)
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(label, weight)| (label.to_string(), weight, total))
+    }
+
+    let os = vote(evidence.observations.iter().filter_map(|o| o.os.map(|os| (os, o.weight))));
+    let device_type = vote(evidence.observations.iter().filter_map(|o| o.device_type.map(|d| (d, o.weight))));
+
+    // Agreement among signals, scaled down while there is little evidence
+    let confidence = os
+        .as_ref()
+        .or(device_type.as_ref())
+        .map(|(_, best, total)| {
+            let agreement = *best as f64 / *total as f64;
+            let strength = (*total).min(FULL_CONFIDENCE_WEIGHT) as f64 / FULL_CONFIDENCE_WEIGHT as f64;
+            (agreement * strength * 100.0).round() as u8
+        })
+        .unwrap_or(0);
+
+    Fingerprint {
+        mac,
+        ips: evidence.ips.into_iter().collect(),
+        hostname: evidence.hostname.map(|(_, name)| name),
+        os_guess: os.map(|(label, _, _)| label),
+        device_type: device_type.map(|(label, _, _)| label),
+        confidence,
+        evidence: evidence
+            .observations
+            .iter()
+            .map(|o| format!("{}: {}", o.source, o.detail))
+            .collect(),
+        last_seen: now,
+    }
+}
+
+/// Merge new fingerprints into the stored ones and persist them
+pub fn save_fingerprints(fingerprints: &[Fingerprint]) -> Result<()> {
+    let mut stored: BTreeMap<String, Fingerprint> = state::load(FINGERPRINT_STATE_FILE)?.unwrap_or_default();
+    for fingerprint in fingerprints {
+        let entry = stored
+            .entry(fingerprint.mac.clone())
+            .or_insert_with(|| fingerprint.clone());
+        // Keep an earlier stronger guess over a weaker one from a quiet capture
+        let hostname = fingerprint.hostname.clone().or_else(|| entry.hostname.clone());
+        if fingerprint.confidence >= entry.confidence || fingerprint.os_guess.is_some() && entry.os_guess.is_none() {
+            *entry = fingerprint.clone();
+        }
+        entry.hostname = hostname;
+        entry.last_seen = fingerprint.last_seen;
+    }
+    state::save(FINGERPRINT_STATE_FILE, &stored)
+}
+
+/// Fill in device type, OS and hostname on peers from stored fingerprints
+pub fn apply_fingerprints(peers: &mut [NetworkPeer]) {
+    let Ok(Some(stored)) = state::load::<BTreeMap<String, Fingerprint>>(FINGERPRINT_STATE_FILE) else {
+        return;
+    };
+
+    for peer in peers.iter_mut() {
+        // Fall back to the address only when the MAC is unknown; a known MAC without a fingerprint has none
+        let found = match &peer.mac {
+            Some(mac) => stored.get(&mac.to_lowercase()),
+            None => stored.values().find(|f| f.ips.contains(&peer.ip)),
+        };
+        let Some(fingerprint) = found else {
+            continue;
+        };
+
+        if peer.hostname.is_none() {
+            peer.hostname = fingerprint.hostname.clone();
+        }
+        if fingerprint.confidence < MIN_APPLY_CONFIDENCE {
+            continue;
+        }
+        if let Some(os) = &fingerprint.os_guess {
+            peer.os_guess = os.clone();
+        }
+        if let Some(device_type) = &fingerprint.device_type {
+            peer.device_type = device_type.clone();
+        }
+    }
+}
+
+/// Capture for a while, print what each host gave away and remember the results
+pub async fn show_fingerprints(interface: Option<String>, duration_secs: u64) -> Result<()> {
+    let interface = match interface {
+        Some(name) => name,
+        None => network::get_primary_interface()?.name,
+    };
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("            🔬  NetNinja Passive Fingerprinting  🔬         ");
+    println!("═══════════════════════════════════════════════════════════\n");
+    println!("Listening on {} for {}s (DHCP, TCP SYN, mDNS, SSDP, NetBIOS)...\n", interface, duration_secs);
+
+    let capture_interface = interface.clone();
+    let fingerprints = tokio::task::spawn_blocking(move || {
+        capture(&capture_interface, Duration::from_secs(duration_secs))
+    })
+    .await??;
+
+    if fingerprints.is_empty() {
+        println!("⚠️  No fingerprintable traffic seen on {}", interface);
+        println!();
+        return Ok(());
+    }
+
+    println!("{:<18} {:<16} {:<20} {:<16} {:<15} {:>5}", "MAC", "IP", "Hostname", "OS", "Type", "Conf");
+    println!("───────────────────────────────────────────────────────────────────────────────────────────────");
+    for fingerprint in &fingerprints {
+        // Prefer an IPv4 address for display
+        let ip = fingerprint
+            .ips
+            .iter()
+            .find(|ip| matches!(ip.parse::<IpAddr>(), Ok(IpAddr::V4(_))))
+            .or(fingerprint.ips.first())
+            .map(String::as_str)
+            .unwrap_or("-");
+        let hostname: String = fingerprint.hostname.as_deref().unwrap_or("-").chars().take(19).collect();
+        println!(
+            "{:<18} {:<16} {:<20} {:<16} {:<15} {:>4}%",
+            fingerprint.mac,
+            ip,
+            hostname,
+            fingerprint.os_guess.as_deref().unwrap_or("Unknown"),
+            fingerprint.device_type.as_deref().unwrap_or("Unknown"),
+            fingerprint.confidence
+        );
+    }
+
+    println!("\n🧾 Evidence");
+    println!("───────────────────────────────────────────────────────────");
+    for fingerprint in &fingerprints {
+        println!("{}", fingerprint.mac);
+        for line in &fingerprint.evidence {
+            println!("   • {}", line);
+        }
+    }
+    println!();
+
+    save_fingerprints(&fingerprints)?;
+    println!("💾 Saved {} fingerprint(s); peer listings now use them", fingerprints.len());
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,832 @@
+use anyhow::{Context, Result};
+use crate::network;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Command;
+
+/// Maximum depth followed through jump/goto targets when evaluating a chain
+const MAX_JUMP_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallRuleset {
+    pub backend: String,
+    pub chains: Vec<FirewallChain>,
+    pub rules: Vec<FirewallRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallChain {
+    pub family: String,
+    pub table: String,
+    pub name: String,
+    pub chain_type: Option<String>,
+    pub hook: Option<String>,
+    pub priority: i64,
+    pub policy: Option<String>,
+    pub rule_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallRule {
+    pub family: String,
+    pub table: String,
+    pub chain: String,
+    pub protocol: Option<String>,
+    pub dports: Vec<(u16, u16)>,
+    pub source: Option<String>,
+    pub in_interface: Option<String>,
+    pub ct_states: Vec<String>,
+    /// "ip" or "ip6" when a rule in an inet table only matches one family
+    #[serde(default)]
+    pub ip_family: Option<String>,
+    pub has_other_matches: bool,
+    pub verdict: RuleVerdict,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RuleVerdict {
+    Accept,
+    Drop,
+    Reject,
+    Jump(String),
+    Goto(String),
+    Return,
+    Continue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PortExposure {
+    /// Reachable from any source address
+    Open,
+    /// Only accepted for specific source addresses
+    Restricted,
+    /// Dropped or rejected for new connections
+    Blocked,
+}
+
+/// Outcome of evaluating a listening port against the input chains of one address family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortVerdict {
+    pub exposure: PortExposure,
+    /// The rule that decided the verdict, or None when the chain policy applied
+    pub rule: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposedPort {
+    pub protocol: String,
+    pub port: u16,
+    pub ipv4: PortVerdict,
+    pub ipv6: PortVerdict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallReport {
+    pub ruleset: FirewallRuleset,
+    pub ipv6_filtered: bool,
+    pub exposed_ports: Vec<ExposedPort>,
+}
+
+impl std::fmt::Display for PortExposure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortExposure::Open => f.pad("OPEN"),
+            PortExposure::Restricted => f.pad("RESTRICTED"),
+            PortExposure::Blocked => f.pad("BLOCKED"),
+        }
+    }
+}
+
+/// A new inbound connection being evaluated against the rules
+struct Probe<'a> {
+    /// "ip" or "ip6"
+    family: &'a str,
+    proto: &'a str,
+    port: u16,
+}
+
+impl FirewallRule {
+    fn new(family: &str, table: &str, chain: &str) -> Self {
+        FirewallRule {
+            family: family.to_string(),
+            table: table.to_string(),
+            chain: chain.to_string(),
+            protocol: None,
+            dports: Vec::new(),
+            source: None,
+            in_interface: None,
+            ct_states: Vec::new(),
+            ip_family: None,
+            has_other_matches: false,
+            verdict: RuleVerdict::Continue,
+            text: String::new(),
+        }
+    }
+
+    /// Whether a new inbound connection to proto/port from a remote host can hit this rule
+    fn matches_new_connection(&self, proto: &str, port: u16) -> bool {
+        if !self.ct_states.is_empty()
+            && !self.ct_states.iter().any(|s| s == "new" || s == "untracked")
+        {
+            return false;
+        }
+        if let Some(p) = &self.protocol {
+            if p != proto {
+                return false;
+            }
+        }
+        if !self.dports.is_empty() && !self.dports.iter().any(|(lo, hi)| port >= *lo && port <= *hi) {
+            return false;
+        }
+        if self.in_interface.as_deref() == Some("lo") {
+            return false;
+        }
+        true
+    }
+
+    /// Whether the rule can see traffic of the given family ("ip" or "ip6")
+    fn applies_to_family(&self, family: &str) -> bool {
+        self.ip_family.as_deref().is_none_or(|f| f == family)
+    }
+
+    /// Rules that only apply to part of the traffic (interface or unparsed matches)
+    fn is_conditional(&self) -> bool {
+        self.has_other_matches || self.in_interface.is_some()
+    }
+}
+
+impl FirewallRuleset {
+    /// Base chains hooked on input that apply to the given family ("ip" or "ip6")
+    fn input_chains(&self, family: &str) -> Vec<&FirewallChain> {
+        let mut chains: Vec<&FirewallChain> = self
+            .chains
+            .iter()
+            .filter(|c| c.family == family || c.family == "inet")
+            .filter(|c| c.hook.as_deref() == Some("input"))
+            .filter(|c| c.chain_type.as_deref().unwrap_or("filter") == "filter")
+            .collect();
+        chains.sort_by_key(|c| c.priority);
+        chains
+    }
+
+    /// Whether anything filters inbound traffic at all
+    pub fn is_filtering(&self) -> bool {
+        self.is_filtering_family("ip") || self.is_filtering_family("ip6")
+    }
+
+    /// Whether inbound traffic of the given family is dropped by policy or by a rule reachable from input
+    pub fn is_filtering_family(&self, family: &str) -> bool {
+        self.input_chains(family).iter().any(|chain| {
+            matches!(chain.policy.as_deref(), Some("drop") | Some("reject"))
+                || self.chain_drops(chain, &chain.name, family, 0)
+        })
+    }
+
+    /// Whether a chain, or any chain it jumps to, drops or rejects traffic
+    fn chain_drops(&self, base: &FirewallChain, chain: &str, family: &str, depth: usize) -> bool {
+        if depth > MAX_JUMP_DEPTH {
+            return false;
+        }
+
+        self.rules
+            .iter()
+            .filter(|r| r.family == base.family && r.table == base.table && r.chain == chain)
+            .filter(|r| r.applies_to_family(family))
+            .any(|rule| match &rule.verdict {
+                // Dropping invalid packets is hygiene, not filtering
+                RuleVerdict::Drop | RuleVerdict::Reject => !rule.ct_states.iter().any(|s| s == "invalid"),
+                RuleVerdict::Jump(target) | RuleVerdict::Goto(target) => self.chain_drops(base, target, family, depth + 1),
+                _ => false,
+            })
+    }
+
+    /// Evaluate a new inbound connection to proto/port for one family
+    pub fn evaluate_port(&self, family: &str, proto: &str, port: u16) -> PortVerdict {
+        let mut restricted = false;
+        let probe = Probe { family, proto, port };
+
+        // A packet has to survive every input base chain, in priority order
+        let mut accepted_by = None;
+        for chain in self.input_chains(family) {
+            match self.walk_chain(chain, &chain.name, &probe, 0, &mut restricted) {
+                Some((true, rule)) => {
+                    if accepted_by.is_none() {
+                        accepted_by = rule;
+                    }
+                }
+                Some((false, rule)) => {
+                    return PortVerdict {
+                        exposure: if restricted { PortExposure::Restricted } else { PortExposure::Blocked },
+                        rule,
+                    };
+                }
+                None => {
+                    if matches!(chain.policy.as_deref(), Some("drop") | Some("reject")) {
+                        return PortVerdict {
+                            exposure: if restricted { PortExposure::Restricted } else { PortExposure::Blocked },
+                            rule: None,
+                        };
+                    }
+                }
+            }
+        }
+
+        PortVerdict {
+            exposure: PortExposure::Open,
+            rule: accepted_by,
+        }
+    }
+
+    /// Walk the rules of a chain, following jumps; None means the chain fell through
+    fn walk_chain(
+        &self,
+        base: &FirewallChain,
+        chain: &str,
+        probe: &Probe,
+        depth: usize,
+        restricted: &mut bool,
+    ) -> Option<(bool, Option<String>)> {
+        if depth > MAX_JUMP_DEPTH {
+            return None;
+        }
+
+        let rules = self
+            .rules
+            .iter()
+            .filter(|r| r.family == base.family && r.table == base.table && r.chain == chain)
+            .filter(|r| r.applies_to_family(probe.family));
+
+        for rule in rules {
+            if !rule.matches_new_connection(probe.proto, probe.port) {
+                continue;
+            }
+
+            // Source-restricted accepts do not expose the port to the world
+            if rule.source.is_some() {
+                if rule.verdict == RuleVerdict::Accept {
+                    *restricted = true;
+                }
+                continue;
+            }
+
+            match &rule.verdict {
+                RuleVerdict::Accept => return Some((true, Some(rule.text.clone()))),
+                RuleVerdict::Drop | RuleVerdict::Reject => {
+                    // Only count drops that certainly apply to this traffic
+                    if !rule.is_conditional() {
+                        return Some((false, Some(rule.text.clone())));
+                    }
+                }
+                RuleVerdict::Jump(target) => {
+                    if let Some(result) = self.walk_chain(base, target, probe, depth + 1, restricted) {
+                        return Some(result);
+                    }
+                }
+                RuleVerdict::Goto(target) => {
+                    return self.walk_chain(base, target, probe, depth + 1, restricted);
+                }
+                RuleVerdict::Return => {
+                    if !rule.is_conditional() {
+                        return None;
+                    }
+                }
+                RuleVerdict::Continue => {}
+            }
+        }
+
+        None
+    }
+}
+
+/// Read the active firewall ruleset, preferring nftables and falling back to iptables-save
+pub fn read_ruleset() -> Result<FirewallRuleset> {
+    if let Ok(output) = Command::new("nft").args(["-j", "list", "ruleset"]).output() {
+        if output.status.success() {
+            let ruleset = parse_nft_json(&String::from_utf8_lossy(&output.stdout))?;
+            if !ruleset.chains.is_empty() {
+                return Ok(ruleset);
+            }
+        }
+    }
+
+    let output = Command::new("iptables-save")
+        .output()
+        .context("Failed to execute nft or iptables-save")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "iptables-save failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let mut ruleset = FirewallRuleset {
+        backend: "iptables".to_string(),
+        chains: Vec::new(),
+        rules: Vec::new(),
+    };
+    parse_iptables_save(&String::from_utf8_lossy(&output.stdout), "ip", &mut ruleset);
+
+    if let Ok(output) = Command::new("ip6tables-save").output() {
+        if output.status.success() {
+            parse_iptables_save(&String::from_utf8_lossy(&output.stdout), "ip6", &mut ruleset);
+        }
+    }
+
+    count_rules(&mut ruleset);
+    Ok(ruleset)
+}
+
+/// Build a report of chain policies and listening ports reachable from the world
+pub fn get_firewall_report(open_ports: &[(String, u16, String)]) -> Result<FirewallReport> {
+    let ruleset = read_ruleset()?;
+
+    let mut exposed_ports = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for (proto, port, _) in open_ports {
+        if !seen.insert((proto.clone(), *port)) {
+            continue;
+        }
+        let ipv4 = ruleset.evaluate_port("ip", proto, *port);
+        let ipv6 = ruleset.evaluate_port("ip6", proto, *port);
+        if ipv4.exposure != PortExposure::Blocked || ipv6.exposure != PortExposure::Blocked {
+            exposed_ports.push(ExposedPort {
+                protocol: proto.clone(),
+                port: *port,
+                ipv4,
+                ipv6,
+            });
+        }
+    }
+
+    Ok(FirewallReport {
+        ipv6_filtered: ruleset.is_filtering_family("ip6"),
+        ruleset,
+        exposed_ports,
+    })
+}
+
+fn count_rules(ruleset: &mut FirewallRuleset) {
+    for chain in &mut ruleset.chains {
+        chain.rule_count = ruleset
+            .rules
+            .iter()
+            .filter(|r| r.family == chain.family && r.table == chain.table && r.chain == chain.name)
+            .count();
+    }
+}
+
+fn parse_nft_json(json: &str) -> Result<FirewallRuleset> {
+    let root: Value = serde_json::from_str(json).context("Failed to parse nft JSON output")?;
+    let items = root
+        .get("nftables")
+        .and_then(|v| v.as_array())
+        .context("Unexpected nft JSON layout")?;
+
+    let mut ruleset = FirewallRuleset {
+        backend: "nftables".to_string(),
+        chains: Vec::new(),
+        rules: Vec::new(),
+    };
+
+    for item in items {
+        if let Some(chain) = item.get("chain") {
+            let field = |k: &str| chain.get(k).and_then(|v| v.as_str()).map(|s| s.to_string());
+            ruleset.chains.push(FirewallChain {
+                family: field("family").unwrap_or_default(),
+                table: field("table").unwrap_or_default(),
+                name: field("name").unwrap_or_default(),
+                chain_type: field("type"),
+                hook: field("hook"),
+                priority: chain.get("prio").and_then(|v| v.as_i64()).unwrap_or(0),
+                policy: field("policy"),
+                rule_count: 0,
+            });
+        } else if let Some(rule) = item.get("rule") {
+            if let Some(parsed) = parse_nft_rule(rule) {
+                ruleset.rules.push(parsed);
+            }
+        }
+    }
+
+    count_rules(&mut ruleset);
+    Ok(ruleset)
+}
+
+fn parse_nft_rule(rule: &Value) -> Option<FirewallRule> {
+    let family = rule.get("family")?.as_str()?;
+    let table = rule.get("table")?.as_str()?;
+    let chain = rule.get("chain")?.as_str()?;
+    let mut parsed = FirewallRule::new(family, table, chain);
+    let mut text = Vec::new();
+
+    for expr in rule.get("expr")?.as_array()? {
+        let Some((key, body)) = expr.as_object().and_then(|o| o.iter().next()) else {
+            continue;
+        };
+        match key.as_str() {
+            "match" => {
+                let op = body.get("op").and_then(|v| v.as_str()).unwrap_or("==");
+                let left = body.get("left").unwrap_or(&Value::Null);
+                let right = body.get("right").unwrap_or(&Value::Null);
+                text.push(format!("{} {} {}", nft_operand(left), op, nft_operand(right)));
+
+                if op != "==" && op != "in" {
+                    parsed.has_other_matches = true;
+                    continue;
+                }
+                apply_nft_match(&mut parsed, left, right);
+            }
+            "accept" => parsed.verdict = RuleVerdict::Accept,
+            "drop" => parsed.verdict = RuleVerdict::Drop,
+            "reject" => parsed.verdict = RuleVerdict::Reject,
+            "return" => parsed.verdict = RuleVerdict::Return,
+            "jump" | "goto" => {
+                let target = body.get("target").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                parsed.verdict = if key == "jump" { RuleVerdict::Jump(target) } else { RuleVerdict::Goto(target) };
+            }
+            // Statements that do not restrict which packets match
+            "counter" | "log" | "comment" | "notrack" | "mangle" | "meta" => continue,
+            _ => parsed.has_other_matches = true,
+        }
+    }
+
+    parsed.text = format!(
+        "{} {} {}: {} {}",
+        family,
+        table,
+        chain,
+        text.join(" "),
+        verdict_label(&parsed.verdict)
+    );
+    Some(parsed)
+}
+
+fn apply_nft_match(rule: &mut FirewallRule, left: &Value, right: &Value) {
+    if let Some(payload) = left.get("payload") {
+        let protocol = payload.get("protocol").and_then(|v| v.as_str()).unwrap_or_default();
+        let field = payload.get("field").and_then(|v| v.as_str()).unwrap_or_default();
+        // "ip saddr ..." or "ip6 nexthdr ..." in an inet table only sees that family
+        if protocol == "ip" || protocol == "ip6" {
+            rule.ip_family = Some(protocol.to_string());
+        }
+        match field {
+            "dport" => {
+                if protocol == "tcp" || protocol == "udp" {
+                    rule.protocol = Some(protocol.to_string());
+                }
+                match nft_ports(right) {
+                    Some(ports) => rule.dports = ports,
+                    None => rule.has_other_matches = true,
+                }
+            }
+            "saddr" => rule.source = Some(nft_operand(right)),
+            "protocol" | "nexthdr" => match right.as_str() {
+                Some(p) => rule.protocol = Some(p.to_string()),
+                None => rule.has_other_matches = true,
+            },
+            _ => rule.has_other_matches = true,
+        }
+    } else if let Some(meta) = left.get("meta") {
+        match meta.get("key").and_then(|v| v.as_str()).unwrap_or_default() {
+            "iifname" | "iif" => rule.in_interface = Some(nft_operand(right)),
+            "l4proto" => match right.as_str() {
+                Some(p) => rule.protocol = Some(p.to_string()),
+                None => rule.has_other_matches = true,
+            },
+            "nfproto" => match right.as_str() {
+                Some("ipv4") => rule.ip_family = Some("ip".to_string()),
+                Some("ipv6") => rule.ip_family = Some("ip6".to_string()),
+                _ => rule.has_other_matches = true,
+            },
+            _ => rule.has_other_matches = true,
+        }
+    } else if let Some(ct) = left.get("ct") {
+        if ct.get("key").and_then(|v| v.as_str()) == Some("state") {
+            rule.ct_states = nft_strings(right);
+        } else {
+            rule.has_other_matches = true;
+        }
+    } else {
+        rule.has_other_matches = true;
+    }
+}
+
+fn nft_ports(value: &Value) -> Option<Vec<(u16, u16)>> {
+    if let Some(port) = value.as_u64() {
+        return Some(vec![(port as u16, port as u16)]);
+    }
+    if let Some(range) = value.get("range").and_then(|v| v.as_array()) {
+        let lo = range.first()?.as_u64()? as u16;
+        let hi = range.get(1)?.as_u64()? as u16;
+        return Some(vec![(lo, hi)]);
+    }
+    if let Some(set) = value.get("set").and_then(|v| v.as_array()) {
+        let mut ports = Vec::new();
+        for element in set {
+            ports.extend(nft_ports(element)?);
+        }
+        return Some(ports);
+    }
+    // Named sets ("@name") and other expressions cannot be resolved here
+    None
+}
+
+fn nft_strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(nft_strings).collect(),
+        Value::Object(o) => o.get("set").map(nft_strings).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Render an nft JSON operand roughly the way `nft list ruleset` would
+fn nft_operand(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Array(items) => items.iter().map(nft_operand).collect::<Vec<_>>().join(", "),
+        Value::Object(o) => {
+            if let Some(payload) = o.get("payload") {
+                format!(
+                    "{} {}",
+                    payload.get("protocol").and_then(|v| v.as_str()).unwrap_or("?"),
+                    payload.get("field").and_then(|v| v.as_str()).unwrap_or("?")
+                )
+            } else if let Some(meta) = o.get("meta") {
+                format!("meta {}", meta.get("key").and_then(|v| v.as_str()).unwrap_or("?"))
+            } else if let Some(ct) = o.get("ct") {
+                format!("ct {}", ct.get("key").and_then(|v| v.as_str()).unwrap_or("?"))
+            } else if let Some(prefix) = o.get("prefix") {
+                format!(
+                    "{}/{}",
+                    prefix.get("addr").and_then(|v| v.as_str()).unwrap_or("?"),
+                    prefix.get("len").and_then(|v| v.as_u64()).unwrap_or(0)
+                )
+            } else if let Some(range) = o.get("range") {
+                let bounds: Vec<String> = range.as_array().map(|r| r.iter().map(nft_operand).collect()).unwrap_or_default();
+                bounds.join("-")
+            } else if let Some(set) = o.get("set") {
+                format!("{{ {} }}", nft_operand(set))
+            } else {
+                value.to_string()
+            }
+        }
+        _ => value.to_string(),
+    }
+}
+
+fn verdict_label(verdict: &RuleVerdict) -> String {
+    match verdict {
+        RuleVerdict::Accept => "accept".to_string(),
+        RuleVerdict::Drop => "drop".to_string(),
+        RuleVerdict::Reject => "reject".to_string(),
+        RuleVerdict::Jump(t) => format!("jump {}", t),
+        RuleVerdict::Goto(t) => format!("goto {}", t),
+        RuleVerdict::Return => "return".to_string(),
+        RuleVerdict::Continue => String::new(),
+    }
+}
+
+fn parse_iptables_save(output: &str, family: &str, ruleset: &mut FirewallRuleset) {
+    let mut table = String::new();
+    let mut table_chains: Vec<String> = Vec::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line == "COMMIT" {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('*') {
+            table = name.to_string();
+            table_chains.clear();
+        } else if let Some(decl) = line.strip_prefix(':') {
+            // ":INPUT DROP [0:0]" declares a chain; user chains have "-" as policy
+            let parts: Vec<&str> = decl.split_whitespace().collect();
+            let Some(name) = parts.first() else { continue };
+            let policy = parts.get(1).filter(|p| **p != "-").map(|p| p.to_lowercase());
+            let hook = match *name {
+                "INPUT" => Some("input"),
+                "FORWARD" => Some("forward"),
+                "OUTPUT" => Some("output"),
+                "PREROUTING" => Some("prerouting"),
+                "POSTROUTING" => Some("postrouting"),
+                _ => None,
+            };
+            table_chains.push(name.to_string());
+            ruleset.chains.push(FirewallChain {
+                family: family.to_string(),
+                table: table.clone(),
+                name: name.to_string(),
+                chain_type: Some(if table == "filter" { "filter" } else { "other" }.to_string()),
+                hook: hook.map(|h| h.to_string()),
+                priority: 0,
+                policy,
+                rule_count: 0,
+            });
+        } else if line.starts_with("-A ") {
+            if let Some(rule) = parse_iptables_rule(line, family, &table, &table_chains) {
+                ruleset.rules.push(rule);
+            }
+        }
+    }
+}
+
+fn parse_iptables_rule(line: &str, family: &str, table: &str, chains: &[String]) -> Option<FirewallRule> {
+    let tokens = tokenize(line);
+    let chain = tokens.get(1)?;
+    let mut rule = FirewallRule::new(family, table, chain);
+    rule.text = line.to_string();
+
+    let mut negate = false;
+    let mut i = 2;
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+        let value = tokens.get(i + 1).map(|s| s.as_str());
+
+        if token == "!" {
+            negate = true;
+            i += 1;
+            continue;
+        }
+        if negate {
+            rule.has_other_matches = true;
+        }
+
+        match token {
+            "-p" | "--protocol" => {
+                if let Some(p) = value.filter(|p| *p != "all") {
+                    if !negate {
+                        rule.protocol = Some(p.to_lowercase());
+                    }
+                }
+                i += 2;
+            }
+            "-s" | "--source" => {
+                if let Some(s) = value.filter(|s| *s != "0.0.0.0/0" && *s != "::/0") {
+                    rule.source = Some(s.to_string());
+                }
+                i += 2;
+            }
+            "-i" | "--in-interface" => {
+                rule.in_interface = value.map(|s| s.to_string());
+                i += 2;
+            }
+            "--dport" | "--destination-port" | "--dports" | "--destination-ports" => {
+                if !negate {
+                    rule.dports = value.map(parse_port_list).unwrap_or_default();
+                }
+                i += 2;
+            }
+            "--ctstate" | "--state" => {
+                if !negate {
+                    rule.ct_states = value
+                        .map(|v| v.split(',').map(|s| s.to_lowercase()).collect())
+                        .unwrap_or_default();
+                }
+                i += 2;
+            }
+            "-m" | "--match" => {
+                if !matches!(value, Some("tcp" | "udp" | "multiport" | "conntrack" | "state" | "comment")) {
+                    rule.has_other_matches = true;
+                }
+                i += 2;
+            }
+            "--comment" => i += 2,
+            "--syn" => i += 1,
+            "--tcp-flags" => {
+                rule.has_other_matches = true;
+                i += 3;
+            }
+            "-j" | "--jump" | "-g" | "--goto" => {
+                let target = value.unwrap_or_default();
+                rule.verdict = match target {
+                    "ACCEPT" => RuleVerdict::Accept,
+                    "DROP" => RuleVerdict::Drop,
+                    "REJECT" => RuleVerdict::Reject,
+                    "RETURN" => RuleVerdict::Return,
+                    t if chains.iter().any(|c| c == t) => {
+                        if token == "-g" || token == "--goto" {
+                            RuleVerdict::Goto(t.to_string())
+                        } else {
+                            RuleVerdict::Jump(t.to_string())
+                        }
+                    }
+                    // Non-terminating targets such as LOG or MARK
+                    _ => RuleVerdict::Continue,
+                };
+                // Target options follow the target name and never restrict matching
+                break;
+            }
+            _ => {
+                rule.has_other_matches = true;
+                // Skip the option's argument if it has one
+                if value.map(|v| !v.starts_with('-')).unwrap_or(false) {
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        negate = false;
+    }
+
+    Some(rule)
+}
+
+/// Parse "22", "1000:2000" or "80,443,8000:8100" into inclusive ranges
+fn parse_port_list(value: &str) -> Vec<(u16, u16)> {
+    value
+        .split(',')
+        .filter_map(|part| match part.split_once(':') {
+            Some((lo, hi)) => Some((lo.parse().unwrap_or(0), hi.parse().unwrap_or(u16::MAX))),
+            None => part.parse().ok().map(|p| (p, p)),
+        })
+        .collect()
+}
+
+/// Split an iptables-save line into tokens, honoring double-quoted arguments
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Print the firewall ruleset report
+pub async fn show_report() -> Result<()> {
+    let open_ports = network::get_open_ports().unwrap_or_default();
+    let report = get_firewall_report(&open_ports)?;
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              🧱  NetNinja Firewall Report  🧱             ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    println!("Backend: {}", report.ruleset.backend);
+    println!("Filtering: {}", if report.ruleset.is_filtering() { "🟢 Active" } else { "🔴 Not filtering (accept-all)" });
+    println!("IPv6 filtered: {}", if report.ipv6_filtered { "🟢 Yes" } else { "🔴 No" });
+    println!();
+
+    println!("📋 CHAIN POLICIES");
+    println!("───────────────────────────────────────────────────────────");
+    println!("{:<8} {:<12} {:<24} {:<10} {:<6}", "Family", "Table", "Chain", "Policy", "Rules");
+    println!("{}", "─".repeat(64));
+    for chain in &report.ruleset.chains {
+        println!("{:<8} {:<12} {:<24} {:<10} {:<6}",
+            chain.family,
+            chain.table,
+            chain.name,
+            chain.policy.as_deref().unwrap_or("-"),
+            chain.rule_count);
+    }
+    println!();
+
+    println!("🌍 LISTENING PORTS REACHABLE FROM THE WORLD");
+    println!("───────────────────────────────────────────────────────────");
+    if report.exposed_ports.is_empty() {
+        println!("✅ No listening ports are exposed by the ruleset");
+    } else {
+        println!("{:<8} {:<8} {:<12} {:<12}", "Proto", "Port", "IPv4", "IPv6");
+        println!("{}", "─".repeat(40));
+        for exposed in &report.exposed_ports {
+            println!("{:<8} {:<8} {:<12} {:<12}",
+                exposed.protocol, exposed.port, exposed.ipv4.exposure, exposed.ipv6.exposure);
+            for (family, verdict) in [("IPv4", &exposed.ipv4), ("IPv6", &exposed.ipv6)] {
+                if verdict.exposure == PortExposure::Blocked {
+                    continue;
+                }
+                match &verdict.rule {
+                    Some(rule) => println!("    {} via rule: {}", family, rule),
+                    None => println!("    {} via chain policy / no input filtering", family),
+                }
+            }
+        }
+    }
+    println!();
+
+    Ok(())
+}
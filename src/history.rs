@@ -0,0 +1,331 @@
+use anyhow::{Context, Result};
+use crate::monitor::StatusSnapshot;
+use crate::{network, state, vpn};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::process::Command;
+
+/// Samples of the current namespace, stored per namespace by `state`
+const HISTORY_FILE: &str = "history.json";
+
+/// How often the daemon records a sample
+pub const SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Raw samples are kept for a day, then averaged into 5-minute points
+const RAW_RETENTION: i64 = 24 * 3600;
+const MEDIUM_STEP: i64 = 5 * 60;
+/// 5-minute points are kept for a week, then averaged into hourly points
+const MEDIUM_RETENTION: i64 = 7 * 24 * 3600;
+const COARSE_STEP: i64 = 3600;
+/// Hourly points are kept for 30 days
+const COARSE_RETENTION: i64 = 30 * 24 * 3600;
+
+/// Columns of a sparkline, and rows of a table
+const CHART_WIDTH: usize = 60;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Point {
+    /// Unix timestamp in seconds
+    pub ts: i64,
+    pub value: f64,
+}
+
+/// One metric at three resolutions; each tier only holds data older than the next finer one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Series {
+    raw: Vec<Point>,
+    medium: Vec<Point>,
+    coarse: Vec<Point>,
+}
+
+impl Series {
+    /// Every stored point at or after `since`, oldest first
+    pub fn points_since(&self, since: i64) -> Vec<Point> {
+        self.coarse
+            .iter()
+            .chain(&self.medium)
+            .chain(&self.raw)
+            .filter(|p| p.ts >= since)
+            .copied()
+            .collect()
+    }
+
+    /// Downsample expired points into the next tier and drop what is past retention
+    fn compact(&mut self, now: i64) {
+        roll_up(&mut self.raw, &mut self.medium, now - RAW_RETENTION, MEDIUM_STEP);
+        roll_up(&mut self.medium, &mut self.coarse, now - MEDIUM_RETENTION, COARSE_STEP);
+        self.coarse.retain(|p| p.ts >= now - COARSE_RETENTION);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.raw.is_empty() && self.medium.is_empty() && self.coarse.is_empty()
+    }
+}
+
+/// Move whole `step`-sized buckets older than `cutoff` from one tier into the next as averages
+fn roll_up(from: &mut Vec<Point>, into: &mut Vec<Point>, cutoff: i64, step: i64) {
+    // Only whole buckets move, so a bucket is never split across two tiers
+    let cutoff = cutoff - cutoff.rem_euclid(step);
+    let split = from.partition_point(|p| p.ts < cutoff);
+    let expired: Vec<Point> = from.drain(..split).collect();
+
+    for bucket in expired.chunk_by(|a, b| a.ts.div_euclid(step) == b.ts.div_euclid(step)) {
+        let value = bucket.iter().map(|p| p.value).sum::<f64>() / bucket.len() as f64;
+        into.push(Point { ts: bucket[0].ts - bucket[0].ts.rem_euclid(step), value });
+    }
+}
+
+/// Time series for every recorded metric, persisted in the state directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryStore {
+    pub series: BTreeMap<String, Series>,
+}
+
+impl HistoryStore {
+    pub fn load() -> Result<Self> {
+        Ok(state::load(HISTORY_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        state::save(HISTORY_FILE, self)
+    }
+
+    /// Append one sample per metric and apply retention
+    pub fn record(&mut self, at: DateTime<Utc>, samples: &[(String, f64)]) {
+        let ts = at.timestamp();
+        for (metric, value) in samples {
+            self.series.entry(metric.clone()).or_default().raw.push(Point { ts, value: *value });
+        }
+        for series in self.series.values_mut() {
+            series.compact(ts);
+        }
+        self.series.retain(|_, series| !series.is_empty());
+    }
+
+    /// Series named `metric`, or every `metric.*` series (e.g. `rx` for `rx.eth0`)
+    pub fn matching(&self, metric: &str) -> Vec<(&String, &Series)> {
+        if let Some((name, series)) = self.series.get_key_value(metric) {
+            return vec![(name, series)];
+        }
+        let prefix = format!("{}.", metric);
+        self.series.iter().filter(|(name, _)| name.starts_with(&prefix)).collect()
+    }
+}
+
+/// Interface byte counters as name -> (rx, tx)
+type Counters = HashMap<String, (u64, u64)>;
+
+/// Read interface byte counters
+fn read_interface_counters() -> Result<Counters> {
+    Ok(network::get_interface_counters()?
+        .into_iter()
+        .map(|c| (c.name, (c.rx_bytes, c.tx_bytes)))
+        .collect())
+}
+
+/// Established TCP connections over IPv4 and IPv6
+fn count_connections() -> Result<usize> {
+    let mut count = 0;
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(table) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        // sl local_address rem_address st ...; state 01 is ESTABLISHED
+        count += table
+            .lines()
+            .skip(1)
+            .filter(|line| line.split_whitespace().nth(3) == Some("01"))
+            .count();
+    }
+    Ok(count)
+}
+
+/// Round-trip time to a host in milliseconds, or None if it did not answer
+fn ping_rtt(host: &str) -> Option<f64> {
+    let output = Command::new("ping").args(["-n", "-c", "1", "-W", "1", host]).output().ok()?;
+    // 64 bytes from 192.168.1.1: icmp_seq=1 ttl=64 time=0.412 ms
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("time="))
+        .and_then(|rtt| rtt.parse().ok())
+}
+
+/// Collects samples, turning byte counters into rates between calls
+#[derive(Default)]
+pub struct Sampler {
+    previous: Option<(DateTime<Utc>, Counters)>,
+}
+
+impl Sampler {
+    /// Sample every metric; peers and alerts come from the daemon's snapshot
+    pub fn sample(&mut self, snapshot: &StatusSnapshot) -> Vec<(String, f64)> {
+        let now = Utc::now();
+        let mut samples = Vec::new();
+
+        if let Ok(counters) = read_interface_counters() {
+            if let Some((then, previous)) = &self.previous {
+                let elapsed = (now - *then).num_milliseconds() as f64 / 1000.0;
+                for (name, (rx, tx)) in &counters {
+                    // Counters reset when an interface is recreated
+                    let Some((prev_rx, prev_tx)) = previous.get(name).filter(|(r, t)| rx >= r && tx >= t) else {
+                        continue;
+                    };
+                    if name != "lo" && elapsed > 0.0 {
+                        samples.push((format!("rx.{}", name), (rx - prev_rx) as f64 / elapsed));
+                        samples.push((format!("tx.{}", name), (tx - prev_tx) as f64 / elapsed));
+                    }
+                }
+            }
+            self.previous = Some((now, counters));
+        }
+
+        if let Ok(connections) = count_connections() {
+            samples.push(("connections".to_string(), connections as f64));
+        }
+        if let Some(peers) = &snapshot.peers {
+            samples.push(("peers".to_string(), peers.len() as f64));
+        }
+        if let Some(alerts) = &snapshot.alerts {
+            samples.push(("alerts".to_string(), alerts.len() as f64));
+        }
+        for (gateway, _) in network::get_default_gateways().unwrap_or_default() {
+            if let Some(rtt) = ping_rtt(&gateway) {
+                samples.push((format!("ping.{}", gateway), rtt));
+            }
+        }
+
+        samples
+    }
+}
+
+/// Format a value in the unit of its metric
+fn format_value(metric: &str, value: f64) -> String {
+    if metric.starts_with("rx.") || metric.starts_with("tx.") {
+        format!("{}/s", vpn::format_bytes(value as u64))
+    } else if metric.starts_with("ping.") {
+        format!("{:.1} ms", value)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+/// Average the points into `CHART_WIDTH` equal time buckets between `since` and `until`
+fn bucketize(points: &[Point], since: i64, until: i64) -> Vec<Option<f64>> {
+    let span = (until - since).max(1) as f64;
+    let mut sums = vec![(0.0, 0usize); CHART_WIDTH];
+    for point in points {
+        let column = (((point.ts - since) as f64 / span) * CHART_WIDTH as f64) as usize;
+        let (sum, count) = &mut sums[column.min(CHART_WIDTH - 1)];
+        *sum += point.value;
+        *count += 1;
+    }
+    sums.into_iter()
+        .map(|(sum, count)| (count > 0).then(|| sum / count as f64))
+        .collect()
+}
+
+/// One character per bucket, scaled between the lowest and highest bucket
+fn sparkline(buckets: &[Option<f64>]) -> String {
+    let values: Vec<f64> = buckets.iter().flatten().copied().collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    buckets
+        .iter()
+        .map(|bucket| match bucket {
+            None => ' ',
+            Some(_) if max <= min => SPARKS[0],
+            Some(v) => SPARKS[(((v - min) / (max - min)) * (SPARKS.len() - 1) as f64).round() as usize],
+        })
+        .collect()
+}
+
+fn local_time(ts: i64) -> String {
+    Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn print_series(name: &str, points: &[Point], since: i64, until: i64, table: bool) {
+    let values: Vec<f64> = points.iter().map(|p| p.value).collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let avg = values.iter().sum::<f64>() / values.len() as f64;
+    let latest = values.last().copied().unwrap_or_default();
+
+    println!("📈 {}", name);
+    println!(
+        "   Latest: {}   Min: {}   Avg: {}   Max: {}",
+        format_value(name, latest),
+        format_value(name, min),
+        format_value(name, avg),
+        format_value(name, max)
+    );
+
+    let buckets = bucketize(points, since, until);
+    if table {
+        let step = (until - since) / CHART_WIDTH as i64;
+        println!("   {:<18} Value", "Time");
+        for (i, bucket) in buckets.iter().enumerate() {
+            if let Some(value) = bucket {
+                println!("   {:<18} {}", local_time(since + i as i64 * step), format_value(name, *value));
+            }
+        }
+    } else {
+        println!("   {} │{}│ {}", local_time(since), sparkline(&buckets), local_time(until));
+    }
+    println!();
+}
+
+/// Print recorded metrics over a time range as sparklines or tables
+pub async fn show_history(metric: Option<String>, since: Duration, table: bool) -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("                📈  NetNinja Metric History  📈            ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let store = HistoryStore::load().context("Failed to load metric history")?;
+    let until = Utc::now().timestamp();
+    let since = until - since.num_seconds();
+
+    let selected: Vec<(&String, &Series)> = match &metric {
+        Some(metric) => store.matching(metric),
+        None => store.series.iter().collect(),
+    };
+
+    if store.series.is_empty() {
+        println!("No history recorded yet. Run `netninja-cli daemon` to start recording.\n");
+        return Ok(());
+    }
+    if selected.is_empty() {
+        let names: Vec<&str> = store.series.keys().map(String::as_str).collect();
+        println!("No metric named '{}'. Recorded metrics: {}\n", metric.unwrap_or_default(), names.join(", "));
+        return Ok(());
+    }
+
+    let mut shown = 0;
+    for (name, series) in selected {
+        let points = series.points_since(since);
+        if !points.is_empty() {
+            print_series(name, &points, since, until, table);
+            shown += 1;
+        }
+    }
+    if shown == 0 {
+        println!("No samples in the last {}.\n", format_duration(until - since));
+    }
+
+    Ok(())
+}
+
+/// Compact duration such as "6h" or "90m"
+pub fn format_duration(secs: i64) -> String {
+    match secs {
+        s if s % 86400 == 0 => format!("{}d", s / 86400),
+        s if s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
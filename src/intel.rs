@@ -0,0 +1,406 @@
+use anyhow::{anyhow, Context, Result};
+use crate::config::{self, BlocklistConfig};
+use crate::logs::{self, LogSource};
+use crate::network;
+use crate::process;
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// How far back the firewall and login logs are searched for addresses
+const LOG_LOOKBACK_MINUTES: i64 = 60;
+
+/// Lists older than this are flagged as stale
+const STALE_DAYS: i64 = 7;
+
+/// Inclusive address ranges, sorted and merged so a lookup is a binary search
+#[derive(Debug, Default)]
+struct RangeSet(Vec<(u128, u128)>);
+
+impl RangeSet {
+    fn from_ranges(mut ranges: Vec<(u128, u128)>) -> Self {
+        ranges.sort_unstable();
+        let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        RangeSet(merged)
+    }
+
+    fn contains(&self, value: u128) -> bool {
+        // The last range starting at or before the value is the only one that can hold it
+        let index = self.0.partition_point(|(start, _)| *start <= value);
+        index > 0 && self.0[index - 1].1 >= value
+    }
+
+    fn addresses(&self) -> u128 {
+        self.0.iter().map(|(start, end)| end - start + 1).sum()
+    }
+}
+
+/// A loaded IP/CIDR blocklist
+#[derive(Debug, Clone)]
+pub struct Blocklist {
+    pub name: String,
+    pub severity: AlertSeverity,
+    /// From the list's header, or the file's modification time
+    pub updated: Option<DateTime<Utc>>,
+    pub entries: usize,
+    /// Lines that were neither comments nor addresses
+    pub invalid: usize,
+    /// Shared with the cache, so handing out a list does not copy its ranges
+    v4: Arc<RangeSet>,
+    v6: Arc<RangeSet>,
+}
+
+/// Parsed lists by path, with the modification time and size they were parsed at
+type BlocklistCache = HashMap<PathBuf, (SystemTime, u64, Blocklist)>;
+
+/// An address, CIDR block or `first-last` range as an inclusive range; IPv4 yields `true`
+fn parse_entry(entry: &str) -> Option<(bool, u128, u128)> {
+    if let Some((first, last)) = entry.split_once('-') {
+        let (first, last): (IpAddr, IpAddr) = (first.trim().parse().ok()?, last.trim().parse().ok()?);
+        return match (first, last) {
+            (IpAddr::V4(a), IpAddr::V4(b)) if a <= b => Some((true, u32::from(a) as u128, u32::from(b) as u128)),
+            (IpAddr::V6(a), IpAddr::V6(b)) if a <= b => Some((false, u128::from(a), u128::from(b))),
+            _ => None,
+        };
+    }
+
+    let (address, prefix) = match entry.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix.parse::<u32>().ok()?)),
+        None => (entry, None),
+    };
+    let (v4, value, bits) = match address.parse::<IpAddr>().ok()? {
+        IpAddr::V4(ip) => (true, u32::from(ip) as u128, 32),
+        IpAddr::V6(ip) => (false, u128::from(ip), 128),
+    };
+    let prefix = prefix.unwrap_or(bits);
+    if prefix > bits {
+        return None;
+    }
+    let host_bits = bits - prefix;
+    let host_mask = if host_bits == 128 { u128::MAX } else { (1u128 << host_bits) - 1 };
+    Some((v4, value & !host_mask, (value & !host_mask) | host_mask))
+}
+
+/// When the list was generated, from the headers FireHOL and Spamhaus write
+fn parse_updated(line: &str) -> Option<DateTime<Utc>> {
+    // FireHOL: "# This File Date  : Sat Oct 18 10:34:26 UTC 2026"
+    if let Some((_, date)) = line.split_once("This File Date") {
+        let date = date.trim_start_matches([' ', ':']).trim();
+        return NaiveDateTime::parse_from_str(date, "%a %b %e %H:%M:%S UTC %Y").ok().map(|d| d.and_utc());
+    }
+    // Spamhaus DROP text: "; Last-Modified: Sat, 18 Oct 2026 09:23:47 GMT"
+    if let Some((_, date)) = line.split_once("Last-Modified:") {
+        return DateTime::parse_from_rfc2822(date.trim()).ok().map(|d| d.with_timezone(&Utc));
+    }
+    // Spamhaus DROP JSON: {"type":"metadata","timestamp":1792315427,...}
+    if line.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        if value["type"] == "metadata" {
+            return DateTime::from_timestamp(value["timestamp"].as_i64()?, 0);
+        }
+    }
+    None
+}
+
+/// Configured name, or the file name without its extension
+fn list_name(config: &BlocklistConfig) -> String {
+    config.name.clone().unwrap_or_else(|| {
+        config.path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+    })
+}
+
+impl Blocklist {
+    /// Load a plain list, FireHOL netset or Spamhaus DROP file (text or JSON lines)
+    pub fn load(config: &BlocklistConfig) -> Result<Self> {
+        let content = std::fs::read_to_string(&config.path)
+            .with_context(|| format!("Failed to read {}", config.path.display()))?;
+        let name = list_name(config);
+
+        let mut updated = None;
+        let (mut v4, mut v6) = (Vec::new(), Vec::new());
+        let mut invalid = 0;
+        for line in content.lines().map(str::trim) {
+            if updated.is_none() {
+                updated = parse_updated(line);
+            }
+            // {"cidr":"1.10.16.0/20","sblid":"SBL256894","rir":"apnic"}
+            let entry = if line.starts_with('{') {
+                match serde_json::from_str::<serde_json::Value>(line) {
+                    Ok(value) => match value["cidr"].as_str() {
+                        Some(cidr) => cidr.to_string(),
+                        None => continue,
+                    },
+                    Err(_) => {
+                        invalid += 1;
+                        continue;
+                    }
+                }
+            } else {
+                // "1.10.16.0/20 ; SBL256894" or "192.0.2.7 # comment"
+                let entry = line.split(['#', ';']).next().unwrap_or("").trim();
+                match entry.split_whitespace().next() {
+                    Some(entry) => entry.to_string(),
+                    None => continue,
+                }
+            };
+            match parse_entry(&entry) {
+                Some((true, start, end)) => v4.push((start, end)),
+                Some((false, start, end)) => v6.push((start, end)),
+                None => invalid += 1,
+            }
+        }
+
+        let updated = updated.or_else(|| {
+            let modified = std::fs::metadata(&config.path).and_then(|m| m.modified()).ok()?;
+            Some(DateTime::<Utc>::from(modified))
+        });
+        Ok(Blocklist {
+            name,
+            severity: config.severity.clone(),
+            updated,
+            entries: v4.len() + v6.len(),
+            invalid,
+            v4: Arc::new(RangeSet::from_ranges(v4)),
+            v6: Arc::new(RangeSet::from_ranges(v6)),
+        })
+    }
+
+    /// Like `load`, but a file unchanged since it was last parsed is not read again;
+    /// lists can have hundreds of thousands of lines and every security scan checks them
+    pub fn load_cached(config: &BlocklistConfig) -> Result<Self> {
+        static CACHE: OnceLock<Mutex<BlocklistCache>> = OnceLock::new();
+        let metadata = std::fs::metadata(&config.path)
+            .with_context(|| format!("Failed to read {}", config.path.display()))?;
+        let version = (metadata.modified()?, metadata.len());
+
+        let mut cache = CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((modified, len, list)) = cache.get(&config.path) {
+            if (*modified, *len) == version {
+                // The name and severity come from the config, which may have changed
+                let mut list = list.clone();
+                list.name = list_name(config);
+                list.severity = config.severity.clone();
+                return Ok(list);
+            }
+        }
+        let list = Blocklist::load(config)?;
+        cache.insert(config.path.clone(), (version.0, version.1, list.clone()));
+        Ok(list)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match ip.to_canonical() {
+            IpAddr::V4(ip) => self.v4.contains(u32::from(ip) as u128),
+            IpAddr::V6(ip) => self.v6.contains(u128::from(ip)),
+        }
+    }
+
+    /// IPv4 addresses covered, after merging overlapping entries
+    pub fn ipv4_addresses(&self) -> u128 {
+        self.v4.addresses()
+    }
+
+    fn describe_update(&self) -> String {
+        match self.updated {
+            Some(updated) => format!("updated {}", updated.format("%Y-%m-%d %H:%M UTC")),
+            None => "update time unknown".to_string(),
+        }
+    }
+}
+
+/// Every configured blocklist, and the ones that failed to load
+#[derive(Debug, Default)]
+pub struct Blocklists {
+    pub lists: Vec<Blocklist>,
+    pub errors: Vec<(String, String)>,
+}
+
+impl Blocklists {
+    pub fn load() -> Result<Self> {
+        let config = config::load()?;
+        let mut blocklists = Blocklists::default();
+        for list in &config.threat_intel.lists {
+            match Blocklist::load_cached(list) {
+                Ok(loaded) => blocklists.lists.push(loaded),
+                Err(e) => blocklists.errors.push((list.path.display().to_string(), format!("{:#}", e))),
+            }
+        }
+        Ok(blocklists)
+    }
+
+    /// The lists that contain `ip`
+    pub fn lookup(&self, ip: IpAddr) -> Vec<&Blocklist> {
+        self.lists.iter().filter(|list| list.contains(ip)).collect()
+    }
+}
+
+/// Where an address was seen, worded to start an alert message
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Sighting {
+    Connection,
+    FirewallBlock,
+    FailedLogin,
+    AcceptedLogin,
+    Neighbor(String),
+}
+
+impl std::fmt::Display for Sighting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sighting::Connection => write!(f, "Connection peer"),
+            Sighting::FirewallBlock => write!(f, "Firewall-blocked source"),
+            Sighting::FailedLogin => write!(f, "Failed SSH login source"),
+            Sighting::AcceptedLogin => write!(f, "Successful SSH login source"),
+            Sighting::Neighbor(interface) => write!(f, "Neighbor on {}", interface),
+        }
+    }
+}
+
+/// Public addresses seen in connections, firewall and SSH logs, and the neighbor table
+fn collect_sightings() -> BTreeSet<(IpAddr, Sighting)> {
+    let mut sightings = BTreeSet::new();
+    let mut add = |ip: IpAddr, sighting: Sighting| {
+        let ip = ip.to_canonical();
+        if network::is_public_ip(&ip) {
+            sightings.insert((ip, sighting));
+        }
+    };
+
+    if let Ok(sockets) = process::read_proc_sockets() {
+        for socket in sockets.iter().filter(|s| matches!(s.state.as_str(), "ESTABLISHED" | "SYN_SENT" | "SYN_RECV")) {
+            add(socket.remote.ip(), Sighting::Connection);
+        }
+    }
+
+    let since = Utc::now() - chrono::Duration::minutes(LOG_LOOKBACK_MINUTES);
+    if let Ok(lines) = logs::read(&LogSource::Dmesg, since) {
+        // [UFW BLOCK] IN=eth0 OUT= SRC=203.0.113.9 DST=192.0.2.10 ...
+        for line in &lines {
+            let source = line.text.split_whitespace().find_map(|token| token.strip_prefix("SRC="));
+            if let Some(ip) = source.and_then(|ip| ip.parse().ok()) {
+                add(ip, Sighting::FirewallBlock);
+            }
+        }
+    }
+
+    let auth = ["/var/log/auth.log", "/var/log/secure"]
+        .iter()
+        .find(|path| Path::new(path).exists())
+        .map(|path| LogSource::File(path.to_string()))
+        // The unit is ssh.service on Debian but sshd.service elsewhere; the identifier is sshd everywhere
+        .unwrap_or(LogSource::JournalIdentifier("sshd".to_string()));
+    if let Ok(lines) = logs::read(&auth, since) {
+        let login = Regex::new(r"(Failed password|Invalid user|Accepted \w+) .*?from ([0-9A-Fa-f.:]+)")
+            .expect("login regex is valid");
+        for caps in lines.iter().filter_map(|line| login.captures(&line.text)) {
+            let sighting = if caps[1].starts_with("Accepted") { Sighting::AcceptedLogin } else { Sighting::FailedLogin };
+            if let Ok(ip) = caps[2].parse() {
+                add(ip, sighting);
+            }
+        }
+    }
+
+    if let Ok(peers) = network::get_network_peers() {
+        for peer in peers {
+            if let Ok(ip) = peer.ip.parse() {
+                add(ip, Sighting::Neighbor(peer.interface.clone()));
+            }
+        }
+    }
+
+    sightings
+}
+
+/// Alerts for addresses that appear on a blocklist
+pub fn check_threat_intel() -> Result<Vec<SecurityAlert>> {
+    let blocklists = Blocklists::load()?;
+    if blocklists.lists.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut alerts = Vec::new();
+    for (ip, sighting) in collect_sightings() {
+        let lists = blocklists.lookup(ip);
+        if lists.is_empty() {
+            continue;
+        }
+        // A login that succeeded from a listed address matters more than any list says
+        let severity = match sighting {
+            Sighting::AcceptedLogin => AlertSeverity::Critical,
+            _ => lists.iter().map(|l| l.severity.clone()).max_by_key(|s| s.rank()).unwrap_or(AlertSeverity::High),
+        };
+        let names: Vec<&str> = lists.iter().map(|l| l.name.as_str()).collect();
+        let details: Vec<String> = lists.iter().map(|l| format!("{} ({})", l.name, l.describe_update())).collect();
+        alerts.push(SecurityAlert {
+            timestamp: Utc::now(),
+            severity,
+            category: AlertCategory::ThreatIntel,
+            message: format!("{} {} is on blocklist {}", sighting, ip, names.join(", ")),
+            details: Some(format!("Listed in {}", details.join("; "))),
+            subject: Some(format!("{} {}", sighting, ip)),
+        });
+    }
+    Ok(alerts)
+}
+
+/// Show the configured blocklists
+pub async fn show_blocklists() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("               🕵️  NetNinja Threat Intelligence  🕵️         ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let blocklists = Blocklists::load()?;
+    if blocklists.lists.is_empty() && blocklists.errors.is_empty() {
+        println!("No blocklists configured in {}\n", config::config_path()?.display());
+        return Ok(());
+    }
+
+    let stale_before = Utc::now() - chrono::Duration::days(STALE_DAYS);
+    for list in &blocklists.lists {
+        let updated = match list.updated {
+            Some(updated) if updated < stale_before => format!("{} ⚠️  stale", updated.format("%Y-%m-%d %H:%M UTC")),
+            Some(updated) => updated.format("%Y-%m-%d %H:%M UTC").to_string(),
+            None => "unknown".to_string(),
+        };
+        println!("📋 {} [{}]", list.name, list.severity);
+        println!("   Entries:  {} ({} IPv4 addresses)", list.entries, list.ipv4_addresses());
+        println!("   Updated:  {}", updated);
+        if list.invalid > 0 {
+            println!("   Invalid:  {} line(s) skipped", list.invalid);
+        }
+        println!();
+    }
+    for (path, error) in &blocklists.errors {
+        println!("❌ {}: {}", path, error);
+    }
+    Ok(())
+}
+
+/// Look addresses up in every blocklist
+pub async fn check_addresses(ips: &[IpAddr]) -> Result<()> {
+    let blocklists = Blocklists::load()?;
+    if blocklists.lists.is_empty() {
+        return Err(anyhow!("No blocklists loaded; add [[threat_intel.lists]] to {}", config::config_path()?.display()));
+    }
+    for ip in ips {
+        let lists = blocklists.lookup(*ip);
+        if lists.is_empty() {
+            println!("✅ {}: not listed", ip);
+        }
+        for list in lists {
+            println!("🚨 {}: on {} [{}] ({})", ip, list.name, list.severity, list.describe_update());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use anyhow::Result;
+use crate::network::{self, NetworkPeer};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use crate::state;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// State file with every device ever seen, keyed by MAC
+const INVENTORY_STATE_FILE: &str = "peer-inventory.json";
+
+/// IP addresses kept per device, most recent last
+const MAX_IP_HISTORY: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpSighting {
+    pub ip: String,
+    pub interface: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    pub mac: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub ip_history: Vec<IpSighting>,
+    pub vendor: String,
+    pub device_type: String,
+    pub os_guess: String,
+    pub hostname: Option<String>,
+    /// Name assigned with `peers label`
+    pub label: Option<String>,
+    /// Whether the security scan has raised its new-device alert, which `peers` and `discover` leave to it
+    #[serde(default = "default_alerted")]
+    pub alerted: bool,
+}
+
+/// Entries saved before alerts were tracked have already been reported
+fn default_alerted() -> bool {
+    true
+}
+
+impl InventoryEntry {
+    pub fn last_ip(&self) -> Option<&str> {
+        self.ip_history.last().map(|s| s.ip.as_str())
+    }
+
+    /// Label, then hostname, then vendor
+    pub fn display_name(&self) -> &str {
+        self.label
+            .as_deref()
+            .or(self.hostname.as_deref())
+            .unwrap_or(&self.vendor)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    pub peers: BTreeMap<String, InventoryEntry>,
+    /// Whether a security scan has run; the first one only records the devices already present
+    #[serde(default)]
+    pub baselined: bool,
+}
+
+impl Inventory {
+    pub fn load() -> Result<Option<Inventory>> {
+        state::load(INVENTORY_STATE_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        state::save(INVENTORY_STATE_FILE, self)
+    }
+
+    /// Merge a sighting of peers
+    pub fn record(&mut self, peers: &[NetworkPeer], now: DateTime<Utc>) {
+        for peer in peers {
+            let Some(mac) = peer.mac.as_ref().map(|m| m.to_lowercase()) else {
+                continue;
+            };
+            let entry = self.peers.entry(mac.clone()).or_insert_with(|| InventoryEntry {
+                mac: mac.clone(),
+                first_seen: now,
+                last_seen: now,
+                ip_history: Vec::new(),
+                vendor: peer.vendor.clone(),
+                device_type: peer.device_type.clone(),
+                os_guess: peer.os_guess.clone(),
+                hostname: None,
+                label: None,
+                alerted: false,
+            });
+
+            entry.last_seen = now;
+            entry.vendor = peer.vendor.clone();
+            entry.device_type = peer.device_type.clone();
+            entry.os_guess = peer.os_guess.clone();
+            if peer.hostname.is_some() {
+                entry.hostname = peer.hostname.clone();
+            }
+
+            match entry.ip_history.iter().position(|s| s.ip == peer.ip && s.interface == peer.interface) {
+                Some(index) => {
+                    // Move to the end so the history stays ordered by last sighting
+                    let mut sighting = entry.ip_history.remove(index);
+                    sighting.last_seen = now;
+                    entry.ip_history.push(sighting);
+                }
+                None => entry.ip_history.push(IpSighting {
+                    ip: peer.ip.clone(),
+                    interface: peer.interface.clone(),
+                    first_seen: now,
+                    last_seen: now,
+                }),
+            }
+            if entry.ip_history.len() > MAX_IP_HISTORY {
+                let excess = entry.ip_history.len() - MAX_IP_HISTORY;
+                entry.ip_history.drain(..excess);
+            }
+        }
+    }
+}
+
+/// Add the current neighbor table to the inventory and return it
+pub fn refresh() -> Result<Inventory> {
+    let mut inventory = Inventory::load()?.unwrap_or_default();
+    inventory.record(&network::get_network_peers()?, Utc::now());
+    inventory.save()?;
+    Ok(inventory)
+}
+
+/// Record peers found by other means, such as an active sweep
+pub fn record_peers(peers: &[NetworkPeer]) -> Result<()> {
+    let mut inventory = Inventory::load()?.unwrap_or_default();
+    inventory.record(peers, Utc::now());
+    inventory.save()
+}
+
+/// Raise an alert for every device that has not been reported yet, however it was first seen
+pub fn check_new_devices() -> Result<Vec<SecurityAlert>> {
+    let peers = network::get_network_peers()?;
+    let mut inventory = Inventory::load()?.unwrap_or_default();
+    let now = Utc::now();
+    inventory.record(&peers, now);
+
+    let first_run = !inventory.baselined;
+    inventory.baselined = true;
+    let mut unreported = Vec::new();
+    for entry in inventory.peers.values_mut().filter(|e| !e.alerted) {
+        entry.alerted = true;
+        unreported.push(entry.clone());
+    }
+    inventory.save()?;
+
+    // The first run only records the devices already present
+    if first_run {
+        return Ok(Vec::new());
+    }
+
+    Ok(unreported
+        .iter()
+        .map(|entry| SecurityAlert {
+            timestamp: now,
+            severity: AlertSeverity::Medium,
+            category: AlertCategory::NewDevice,
+            message: format!(
+                "New device on the network: {} ({}) at {}",
+                entry.mac,
+                entry.vendor,
+                entry.last_ip().unwrap_or("unknown address")
+            ),
+            details: Some(format!(
+                "device type {}, OS guess {}, interface {}",
+                entry.device_type,
+                entry.os_guess,
+                entry.ip_history.last().map(|s| s.interface.as_str()).unwrap_or("unknown")
+            )),
+            subject: Some(entry.mac.clone()),
+        })
+        .collect())
+}
+
+/// Print the inventory, optionally only devices first seen within `new_since`
+pub async fn show_peers(new_since: Option<Duration>) -> Result<()> {
+    let inventory = refresh()?;
+    let now = Utc::now();
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("               📒  NetNinja Peer Inventory  📒             ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let mut entries: Vec<&InventoryEntry> = inventory
+        .peers
+        .values()
+        .filter(|e| match new_since.map(|d| now.checked_sub_signed(d)) {
+            // Too long to subtract reaches back past the beginning
+            Some(Some(since)) => e.first_seen >= since,
+            _ => true,
+        })
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.last_seen));
+
+    if let Some(d) = new_since {
+        println!("Devices first seen in the last {}h {}m\n", d.num_hours(), d.num_minutes() % 60);
+    }
+    if entries.is_empty() {
+        println!("No devices recorded");
+        println!();
+        return Ok(());
+    }
+
+    println!("{:<18} {:<24} {:<16} {:<15} {:<17} {:<17}", "MAC Address", "Name", "Last IP", "Device Type", "First Seen", "Last Seen");
+    println!("{}", "─".repeat(112));
+    for entry in &entries {
+        let name: String = entry.display_name().chars().take(23).collect();
+        println!(
+            "{:<18} {:<24} {:<16} {:<15} {:<17} {:<17}",
+            entry.mac,
+            name,
+            entry.last_ip().unwrap_or("-"),
+            entry.device_type,
+            entry.first_seen.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            entry.last_seen.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+        );
+        // Other addresses the device has used
+        let others: Vec<&str> = entry
+            .ip_history
+            .iter()
+            .rev()
+            .skip(1)
+            .map(|s| s.ip.as_str())
+            .take(4)
+            .collect();
+        if !others.is_empty() {
+            println!("{:<18} previously: {}", "", others.join(", "));
+        }
+    }
+    println!("\n{} device(s)", entries.len());
+    println!();
+
+    Ok(())
+}
+
+/// Assign or clear a device label
+pub async fn set_label(mac: &str, label: Option<String>) -> Result<()> {
+    let mut inventory = Inventory::load()?.unwrap_or_default();
+    let mac = mac.to_lowercase().replace('-', ":");
+    let entry = inventory
+        .peers
+        .get_mut(&mac)
+        .ok_or_else(|| anyhow::anyhow!("{} is not in the inventory", mac))?;
+
+    entry.label = label.clone();
+    inventory.save()?;
+
+    match label {
+        Some(label) => println!("🏷️  {} is now labelled \"{}\"", mac, label),
+        None => println!("🏷️  Cleared label for {}", mac),
+    }
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Utc};
+use std::io::{Read, Seek, SeekFrom};
+use std::process::Command;
+
+/// Most lines read from one source per scan
+const MAX_LINES: usize = 5000;
+
+/// How much of the end of a log file is read
+const FILE_TAIL_BYTES: u64 = 1024 * 1024;
+
+/// Highest syslog facility number (local7)
+const MAX_FACILITY: u8 = 23;
+
+/// Where log lines come from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogSource {
+    /// The systemd journal, optionally a single unit
+    Journal(Option<String>),
+    /// Journal entries logged under one syslog identifier, such as sshd whatever its unit is called
+    JournalIdentifier(String),
+    /// Journal entries from any of these syslog facilities, such as 4 (auth) and 10 (authpriv)
+    JournalFacilities(Vec<u8>),
+    /// The kernel ring buffer
+    Dmesg,
+    /// A plain text log such as /var/log/auth.log
+    File(String),
+}
+
+impl std::str::FromStr for LogSource {
+    type Err = String;
+
+    /// "journal", "journal:<unit>", "identifier:<name>", "facility:<n>[,<n>...]", "dmesg" or "file:<path>"
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            None if value == "journal" => Ok(LogSource::Journal(None)),
+            None if value == "dmesg" => Ok(LogSource::Dmesg),
+            Some(("journal", unit)) if !unit.is_empty() => Ok(LogSource::Journal(Some(unit.to_string()))),
+            Some(("identifier", name)) if !name.is_empty() => Ok(LogSource::JournalIdentifier(name.to_string())),
+            Some(("facility", list)) => list
+                .split(',')
+                .map(|n| n.trim().parse::<u8>().ok().filter(|n| *n <= MAX_FACILITY))
+                .collect::<Option<Vec<u8>>>()
+                .map(LogSource::JournalFacilities)
+                .ok_or_else(|| format!("invalid syslog facility list '{}' (use numbers 0-{})", list, MAX_FACILITY)),
+            Some(("file", path)) if !path.is_empty() => Ok(LogSource::File(path.to_string())),
+            _ => Err(format!(
+                "unknown log source '{}' (use journal, journal:<unit>, identifier:<name>, facility:<n>, dmesg or file:<path>)",
+                value
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for LogSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogSource::Journal(None) => write!(f, "journal"),
+            LogSource::Journal(Some(unit)) => write!(f, "journal:{}", unit),
+            LogSource::JournalIdentifier(name) => write!(f, "identifier:{}", name),
+            LogSource::JournalFacilities(facilities) => write!(
+                f,
+                "facility:{}",
+                facilities.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",")
+            ),
+            LogSource::Dmesg => write!(f, "dmesg"),
+            LogSource::File(path) => write!(f, "file:{}", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+}
+
+/// Lines logged since `since`, oldest first
+pub fn read(source: &LogSource, since: DateTime<Utc>) -> Result<Vec<LogLine>> {
+    let mut lines = match source {
+        LogSource::Journal(None) => read_journal(&[], since)?,
+        LogSource::Journal(Some(unit)) => read_journal(&["-u".to_string(), unit.clone()], since)?,
+        LogSource::JournalIdentifier(name) => read_journal(&["-t".to_string(), name.clone()], since)?,
+        // Matches on the same field are ORed by journalctl
+        LogSource::JournalFacilities(facilities) => {
+            let matches: Vec<String> = facilities.iter().map(|n| format!("SYSLOG_FACILITY={}", n)).collect();
+            read_journal(&matches, since)?
+        }
+        LogSource::Dmesg => read_dmesg()?,
+        LogSource::File(path) => read_file(path)?,
+    };
+    lines.retain(|line| line.timestamp >= since);
+    Ok(lines)
+}
+
+/// Read the journal, narrowed by extra journalctl arguments such as `-u <unit>` or field matches
+fn read_journal(filter: &[String], since: DateTime<Utc>) -> Result<Vec<LogLine>> {
+    let since = since.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string();
+    let max_lines = MAX_LINES.to_string();
+    let mut args = vec!["-o", "json", "--no-pager", "--since", since.as_str(), "-n", max_lines.as_str()];
+    args.extend(filter.iter().map(String::as_str));
+    let output = Command::new("journalctl")
+        .args(&args)
+        .output()
+        .context("Failed to execute journalctl")?;
+
+    let mut lines = Vec::new();
+    for entry in String::from_utf8_lossy(&output.stdout).lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(entry) else {
+            continue;
+        };
+        // Binary messages come as byte arrays and are skipped
+        let Some(message) = entry["MESSAGE"].as_str() else {
+            continue;
+        };
+        let micros: i64 = entry["__REALTIME_TIMESTAMP"].as_str().and_then(|t| t.parse().ok()).unwrap_or_default();
+        let Some(timestamp) = DateTime::from_timestamp_micros(micros) else {
+            continue;
+        };
+        // sshd[1234]: Failed password for root from 203.0.113.9 port 52214 ssh2
+        let text = match (entry["SYSLOG_IDENTIFIER"].as_str(), entry["_PID"].as_str()) {
+            (Some(ident), Some(pid)) => format!("{}[{}]: {}", ident, pid, message),
+            (Some(ident), None) => format!("{}: {}", ident, message),
+            _ => message.to_string(),
+        };
+        lines.push(LogLine { timestamp, text });
+    }
+    Ok(lines)
+}
+
+fn read_dmesg() -> Result<Vec<LogLine>> {
+    let output = Command::new("dmesg")
+        .args(["--time-format", "iso"])
+        .output()
+        .context("Failed to execute dmesg")?;
+    if !output.status.success() {
+        return Err(anyhow!("dmesg failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // 2026-10-18T19:15:02,123456+00:00 [UFW BLOCK] IN=eth0 ...
+    let text = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<LogLine> = text
+        .lines()
+        .filter_map(|line| {
+            let (stamp, message) = line.split_once(' ')?;
+            let timestamp = DateTime::parse_from_str(stamp, "%Y-%m-%dT%H:%M:%S,%6f%:z").ok()?;
+            Some(LogLine { timestamp: timestamp.with_timezone(&Utc), text: message.to_string() })
+        })
+        .collect();
+    Ok(lines.into_iter().rev().take(MAX_LINES).rev().collect())
+}
+
+/// Timestamp at the start of a syslog line: RFC 3339 (rsyslog) or "Oct 18 19:15:02" (traditional)
+fn parse_syslog_timestamp(line: &str) -> Option<(DateTime<Utc>, &str)> {
+    if let Some((stamp, rest)) = line.split_once(' ') {
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(stamp) {
+            return Some((timestamp.with_timezone(&Utc), rest));
+        }
+    }
+
+    let stamp = line.get(..15)?;
+    let now = Local::now();
+    let naive = NaiveDateTime::parse_from_str(&format!("{} {}", now.year(), stamp), "%Y %b %e %H:%M:%S").ok()?;
+    let mut timestamp = Local.from_local_datetime(&naive).single()?;
+    // The traditional format has no year; a date in the future belongs to last year
+    if timestamp > now + chrono::Duration::days(1) {
+        timestamp = Local.from_local_datetime(&naive.with_year(now.year() - 1)?).single()?;
+    }
+    Some((timestamp.with_timezone(&Utc), line[15..].trim_start()))
+}
+
+fn read_file(path: &str) -> Result<Vec<LogLine>> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let length = file.metadata()?.len();
+    let start = length.saturating_sub(FILE_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    let content = String::from_utf8_lossy(&content);
+
+    // Starting mid-file means the first line is probably cut off
+    let skip = if start > 0 { 1 } else { 0 };
+    let lines: Vec<LogLine> = content
+        .lines()
+        .skip(skip)
+        .filter_map(|line| {
+            let (timestamp, text) = parse_syslog_timestamp(line)?;
+            Some(LogLine { timestamp, text: text.to_string() })
+        })
+        .collect();
+    Ok(lines.into_iter().rev().take(MAX_LINES).rev().collect())
+}
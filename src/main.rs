@@ -3,23 +3,152 @@ mod monitor;
 mod tmux;
 mod network;
 mod security;
+mod firewall;
+mod exposure;
+mod state;
+mod process;
+mod changes;
+mod arpwatch;
+mod dhcp;
+mod oui;
+mod dnswire;
+mod fingerprint;
+mod discover;
+mod services;
+mod inventory;
+mod vpn;
+mod wireless;
+mod netns;
+mod containers;
+mod topology;
+mod daemon;
+mod history;
+mod exporter;
+mod config;
+mod notify;
+mod alerts;
+mod logs;
+mod rules;
+mod sigma;
+mod intel;
 
 use anyhow::Result;
 use clap::Parser;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     let args = cli::Args::parse();
     
+    // Namespaces are per thread, so switch before the runtime starts its workers
+    if let Some(spec) = &args.netns {
+        netns::enter(spec)?;
+    }
+    
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(args))
+}
+
+async fn run(args: cli::Args) -> Result<()> {
     match args.command {
         Some(cli::Commands::Monitor) => {
             // Launch the tmux-based monitoring dashboard
             monitor::launch_dashboard().await?;
         }
-        Some(cli::Commands::Status) => {
+        Some(cli::Commands::Status { json, section }) => {
             // Show quick network status
-            monitor::show_status().await?;
+            monitor::show_status(json, section).await?;
+        }
+        Some(cli::Commands::Daemon) => {
+            // Keep a cached snapshot fresh for status, the dashboard and scripts
+            daemon::run_daemon().await?;
+        }
+        Some(cli::Commands::History { metric, since, table }) => {
+            // Chart interface, connection, peer, ping and alert history
+            history::show_history(metric, since, table).await?;
+        }
+        Some(cli::Commands::Exporter { listen }) => {
+            // Expose metrics in the Prometheus text format
+            exporter::run_exporter(listen).await?;
+        }
+        Some(cli::Commands::Alerts { action }) => match action {
+            // Work through the alert lifecycle, or check that every notification sink is reachable
+            cli::AlertCommands::List { all } => alerts::show_alerts(all).await?,
+            cli::AlertCommands::Ack { ids } => alerts::set_status(&ids, alerts::AlertStatus::Acknowledged).await?,
+            cli::AlertCommands::Resolve { ids } => alerts::set_status(&ids, alerts::AlertStatus::Resolved).await?,
+            cli::AlertCommands::Suppress { category, source_ip, matching, duration, reason } => {
+                alerts::suppress(category, source_ip, matching, duration, reason).await?
+            }
+            cli::AlertCommands::Test => notify::send_test().await?,
+        },
+        Some(cli::Commands::Rules { action }) => match action {
+            // Check the rules file and see what the rules would raise right now
+            cli::RuleCommands::List => rules::show_rules().await?,
+            cli::RuleCommands::Run => rules::run_rules().await?,
+        },
+        Some(cli::Commands::Sigma { action }) => match action {
+            // Check which Sigma rules apply here and what they would raise right now
+            cli::SigmaCommands::List => sigma::show_rules().await?,
+            cli::SigmaCommands::Run => sigma::run_rules().await?,
+        },
+        Some(cli::Commands::Intel { action }) => match action {
+            // See which blocklists are loaded and how fresh they are, or check addresses by hand
+            cli::IntelCommands::List => intel::show_blocklists().await?,
+            cli::IntelCommands::Check { ips } => intel::check_addresses(&ips).await?,
+        },
+        Some(cli::Commands::Interfaces { tree, json }) => {
+            // Show how bridges, bonds, VLANs and veths fit together
+            topology::show_interfaces(tree, json).await?;
+        }
+        Some(cli::Commands::Firewall) => {
+            // Show firewall policies, rule counts and exposed ports
+            firewall::show_report().await?;
+        }
+        Some(cli::Commands::Exposure) => {
+            // Show listener reachability and risk
+            exposure::show_exposure().await?;
+        }
+        Some(cli::Commands::Dhcp { interface, timeout }) => {
+            // Broadcast a DHCPDISCOVER and report every server that answers
+            dhcp::show_dhcp(interface, timeout).await?;
+        }
+        Some(cli::Commands::Fingerprint { interface, duration }) => {
+            // Listen passively and fingerprint the hosts that talk
+            fingerprint::show_fingerprints(interface, duration).await?;
+        }
+        Some(cli::Commands::Discover { interface, timeout }) => {
+            // Actively sweep the LAN for hosts missing from the neighbor cache
+            discover::show_discovery(interface, timeout).await?;
+        }
+        Some(cli::Commands::Services { duration }) => {
+            // Browse mDNS/DNS-SD and SSDP advertisements
+            services::show_services(duration).await?;
+        }
+        Some(cli::Commands::Peers { new_since, action }) => match action {
+            // Show the device inventory or label a device
+            None => inventory::show_peers(new_since).await?,
+            Some(cli::PeerCommands::Label { mac, label }) => inventory::set_label(&mac, label).await?,
+        },
+        Some(cli::Commands::Wifi { interface, scan }) => {
+            // Report link quality from nl80211 and optionally scan the air
+            wireless::show_wifi(interface, scan).await?;
+        }
+        Some(cli::Commands::Containers) => {
+            // Map container networking onto host veths and bridges
+            containers::show_containers().await?;
+        }
+        Some(cli::Commands::Netns) => {
+            // Enumerate named and per-process network namespaces
+            netns::show_namespaces().await?;
         }
+        Some(cli::Commands::Vpn { action }) => match action {
+            // Look for IPv6, DNS and WebRTC leaks around the tunnel
+            cli::VpnCommands::Check => vpn::show_leak_test().await?,
+        },
+        Some(cli::Commands::Oui { action }) => match action {
+            cli::OuiCommands::Update => oui::update_registry().await?,
+            cli::OuiCommands::Lookup { mac } => oui::show_lookup(&mac).await?,
+        },
         None => {
             // Default: show help
             cli::Args::parse_from(&["netninja-cli", "--help"]);
@@ -1,5 +1,9 @@
-use anyhow::Result;
-use crate::{network, security, tmux};
+use anyhow::{anyhow, Result};
+use crate::{alerts, containers, daemon, netns, network, security, tmux, vpn, wireless};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::process::Command;
 
 /// Launch the full tmux monitoring dashboard
@@ -21,170 +25,430 @@ pub async fn launch_dashboard() -> Result<()> {
     Ok(())
 }
 
-/// Show quick network status
-pub async fn show_status() -> Result<()> {
-    println!("═══════════════════════════════════════════════════════════");
-    println!("              🥷  NetNinja Status Report  🥷              ");
-    println!("═══════════════════════════════════════════════════════════\n");
-    
-    // Network Interface Status
-    println!("📡 NETWORK INTERFACES");
-    println!("───────────────────────────────────────────────────────────");
-    match network::get_primary_interface() {
-        Ok(iface) => {
-            println!("Primary Interface: {}", iface.name);
-            println!("Status: {}", if iface.is_up { "🟢 UP" } else { "🔴 DOWN" });
-            if let Some(mac) = &iface.mac_address {
-                println!("MAC Address: {}", mac);
+/// Sections of the status report; the daemon refreshes each on its own schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Section {
+    Interfaces,
+    Wireless,
+    Containers,
+    Vpn,
+    Ports,
+    Peers,
+    Security,
+}
+
+impl Section {
+    pub const ALL: [Section; 7] = [
+        Section::Interfaces,
+        Section::Wireless,
+        Section::Containers,
+        Section::Vpn,
+        Section::Ports,
+        Section::Peers,
+        Section::Security,
+    ];
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            Section::Interfaces => "interfaces",
+            Section::Wireless => "wireless",
+            Section::Containers => "containers",
+            Section::Vpn => "vpn",
+            Section::Ports => "ports",
+            Section::Peers => "peers",
+            Section::Security => "security",
+        }
+    }
+    
+    /// Run this section's collectors and store the results in the snapshot
+    pub fn collect_into(self, snapshot: &mut StatusSnapshot) {
+        let result = match self {
+            Section::Interfaces => store(&mut snapshot.primary_interface, network::get_primary_interface()),
+            Section::Wireless => {
+                // Only reported when the primary interface is wireless
+                let primary = snapshot.primary_interface.clone().or_else(|| network::get_primary_interface().ok());
+                match primary.filter(|iface| wireless::is_wireless(&iface.name)) {
+                    Some(iface) => store(&mut snapshot.wireless, wireless::summarize(&iface.name)),
+                    None => {
+                        snapshot.wireless = None;
+                        Ok(())
+                    }
+                }
+            }
+            Section::Containers => store(&mut snapshot.containers, containers::list_containers().map(|mut found| {
+                let _ = containers::attach_host_links(&mut found);
+                found
+            })),
+            Section::Vpn => store(&mut snapshot.vpn, network::get_vpn_status()),
+            Section::Ports => store(&mut snapshot.open_ports, network::get_open_ports()),
+            Section::Peers => store(&mut snapshot.peers, network::get_network_peers()),
+            Section::Security => {
+                snapshot.firewall_active = security::check_firewall_status().ok();
+                // Only open alerts are reported, timestamped when they were first seen
+                let scan = security::scan_security_logs();
+                let errors = scan.as_ref().map(|s| s.errors.join("; ")).unwrap_or_default();
+                let tracked = scan.and_then(|scan| alerts::track(scan.alerts));
+                snapshot.new_alert_ids = tracked.as_ref().map(|t| t.new.clone()).unwrap_or_default();
+                // Rules that could not run are reported without hiding the alerts the rest of the scan found
+                store(&mut snapshot.alerts, tracked.map(|t| t.active))
+                    .and_then(|()| if errors.is_empty() { Ok(()) } else { Err(anyhow!(errors)) })
+            }
+        };
+        
+        match result {
+            Ok(()) => {
+                snapshot.errors.remove(self.name());
+            }
+            Err(e) => {
+                snapshot.errors.insert(self.name().to_string(), e.to_string());
             }
-            println!("IP Addresses:");
-            for ip in &iface.ip_addresses {
-                println!("  • {}", ip);
+        }
+        snapshot.updated.insert(self.name().to_string(), Utc::now());
+    }
+    
+    /// Copy this section from a freshly collected snapshot into another
+    pub fn merge(self, from: StatusSnapshot, into: &mut StatusSnapshot) {
+        match self {
+            Section::Interfaces => into.primary_interface = from.primary_interface,
+            Section::Wireless => into.wireless = from.wireless,
+            Section::Containers => into.containers = from.containers,
+            Section::Vpn => into.vpn = from.vpn,
+            Section::Ports => into.open_ports = from.open_ports,
+            Section::Peers => into.peers = from.peers,
+            Section::Security => {
+                into.alerts = from.alerts;
+                into.new_alert_ids = from.new_alert_ids;
+                into.firewall_active = from.firewall_active;
             }
         }
+        match from.errors.get(self.name()) {
+            Some(e) => into.errors.insert(self.name().to_string(), e.clone()),
+            None => into.errors.remove(self.name()),
+        };
+        if let Some(at) = from.updated.get(self.name()) {
+            into.updated.insert(self.name().to_string(), *at);
+        }
+    }
+}
+
+/// Everything the status report shows, as collected at one point in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    pub netns: Option<String>,
+    pub primary_interface: Option<network::NetworkInterface>,
+    pub wireless: Option<wireless::WirelessSummary>,
+    pub containers: Option<Vec<containers::Container>>,
+    pub vpn: Option<network::VpnStatus>,
+    pub open_ports: Option<Vec<(String, u16, String)>>,
+    pub peers: Option<Vec<network::NetworkPeer>>,
+    pub alerts: Option<Vec<security::SecurityAlert>>,
+    /// Ids of the alerts the latest security scan found for the first time
+    #[serde(default)]
+    pub new_alert_ids: Vec<String>,
+    pub firewall_active: Option<bool>,
+    /// Error per section name when its collector failed
+    pub errors: BTreeMap<String, String>,
+    /// When each section was last refreshed
+    pub updated: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl StatusSnapshot {
+    /// Time of the oldest section in the snapshot
+    pub fn oldest_update(&self) -> Option<DateTime<Utc>> {
+        self.updated.values().min().copied()
+    }
+}
+
+/// Keep a collector's value, clearing the stale one and passing the error on when it fails
+fn store<T>(slot: &mut Option<T>, result: Result<T>) -> Result<()> {
+    match result {
+        Ok(value) => {
+            *slot = Some(value);
+            Ok(())
+        }
         Err(e) => {
-            println!("⚠️  Error: {}", e);
+            *slot = None;
+            Err(e)
         }
     }
+}
+
+/// Run the collectors for the given sections
+pub fn collect_snapshot(sections: &[Section]) -> StatusSnapshot {
+    let mut snapshot = StatusSnapshot {
+        netns: netns::entered().map(str::to_string),
+        ..Default::default()
+    };
+    for section in sections {
+        section.collect_into(&mut snapshot);
+    }
+    snapshot
+}
+
+fn print_error(snapshot: &StatusSnapshot, section: Section) {
+    if let Some(e) = snapshot.errors.get(section.name()) {
+        println!("⚠️  Error: {}", e);
+    }
+}
+
+/// Show quick network status, from the daemon's cached snapshot when it is running
+pub async fn show_status(json: bool, section: Option<Section>) -> Result<()> {
+    let sections: Vec<Section> = match section {
+        Some(section) => vec![section],
+        None => Section::ALL.to_vec(),
+    };
     
-    println!();
+    let (snapshot, from_daemon) = match daemon::fetch_snapshot().await {
+        Some(snapshot) => (snapshot, true),
+        None => (collect_snapshot(&sections), false),
+    };
     
-    // VPN Status
-    println!("🔒 VPN STATUS");
-    println!("───────────────────────────────────────────────────────────");
-    match network::get_vpn_status() {
-        Ok(vpn) => {
-            if vpn.is_connected {
-                println!("Status: 🟢 CONNECTED");
-                if let Some(iface) = &vpn.interface {
-                    println!("Interface: {}", iface);
-                }
-                if let Some(ip) = &vpn.ip_address {
-                    println!("VPN IP: {}", ip);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        return Ok(());
+    }
+    
+    render_status(&snapshot, &sections, from_daemon);
+    Ok(())
+}
+
+/// Print the status report for the given sections of a snapshot
+pub fn render_status(snapshot: &StatusSnapshot, sections: &[Section], from_daemon: bool) {
+    let full_report = sections.len() == Section::ALL.len();
+    
+    if full_report {
+        println!("═══════════════════════════════════════════════════════════");
+        println!("              🥷  NetNinja Status Report  🥷              ");
+        println!("═══════════════════════════════════════════════════════════\n");
+        
+        if let Some(ns) = &snapshot.netns {
+            println!("🗂️  Network namespace: {}\n", ns);
+        }
+        if from_daemon {
+            let age = snapshot.oldest_update().map(|at| (Utc::now() - at).num_seconds()).unwrap_or(0);
+            println!("⚡ From the background daemon (data up to {}s old)\n", age);
+        }
+    }
+    
+    if sections.contains(&Section::Interfaces) {
+        // Network Interface Status
+        println!("📡 NETWORK INTERFACES");
+        println!("───────────────────────────────────────────────────────────");
+        match &snapshot.primary_interface {
+            Some(iface) => {
+                println!("Primary Interface: {}", iface.name);
+                println!("Status: {}", if iface.is_up { "🟢 UP" } else { "🔴 DOWN" });
+                if let Some(mac) = &iface.mac_address {
+                    println!("MAC Address: {}", mac);
                 }
-                if let Some(vpn_type) = &vpn.vpn_type {
-                    println!("Type: {}", vpn_type);
+                println!("IP Addresses:");
+                for ip in &iface.ip_addresses {
+                    println!("  • {}", ip);
                 }
-            } else {
-                println!("Status: 🔴 NOT CONNECTED");
             }
+            None => print_error(snapshot, Section::Interfaces),
         }
-        Err(e) => {
-            println!("⚠️  Error: {}", e);
+        
+        println!();
+    }
+    
+    // Wi-Fi link quality, only when the primary interface is wireless
+    if sections.contains(&Section::Wireless) && (snapshot.wireless.is_some() || snapshot.errors.contains_key(Section::Wireless.name())) {
+        println!("📶 WIRELESS");
+        println!("───────────────────────────────────────────────────────────");
+        match &snapshot.wireless {
+            Some(summary) => {
+                wireless::print_link(&summary.link);
+                if summary.nearby_networks > 0 {
+                    println!("Nearby Networks: {} ({} on this channel, {} overlapping)",
+                        summary.nearby_networks, summary.same_channel, summary.overlapping);
+                }
+            }
+            None => print_error(snapshot, Section::Wireless),
         }
+        println!();
     }
     
-    println!();
+    // Containers, so their veths and bridges are not anonymous
+    if sections.contains(&Section::Containers) {
+        if let Some(found) = snapshot.containers.as_ref().filter(|found| !found.is_empty()) {
+            println!("🐳 CONTAINERS");
+            println!("───────────────────────────────────────────────────────────");
+            containers::print_summary(found);
+            println!();
+        }
+    }
     
-    // Open Ports
-    println!("🔓 OPEN PORTS");
-    println!("───────────────────────────────────────────────────────────");
-    match network::get_open_ports() {
-        Ok(ports) => {
-            if ports.is_empty() {
-                println!("No listening ports detected");
-            } else {
-                println!("{:<10} {:<10} {:<15}", "Protocol", "Port", "State");
-                println!("{}", "─".repeat(35));
-                for (proto, port, state) in ports.iter().take(15) {
-                    println!("{:<10} {:<10} {:<15}", proto, port, state);
+    if sections.contains(&Section::Vpn) {
+        // VPN Status
+        println!("🔒 VPN STATUS");
+        println!("───────────────────────────────────────────────────────────");
+        match &snapshot.vpn {
+            Some(vpn) => {
+                if vpn.is_connected {
+                    println!("Status: 🟢 CONNECTED ({} tunnel(s))", vpn.tunnels.iter().filter(|t| t.is_up).count());
+                    if vpn.default_route_via_vpn {
+                        println!("Default Route: 🟢 through VPN");
+                    } else {
+                        println!("Default Route: 🟡 bypasses VPN (split tunnel)");
+                    }
+                } else {
+                    println!("Status: 🔴 NOT CONNECTED");
                 }
-                if ports.len() > 15 {
-                    println!("... and {} more", ports.len() - 15);
+                for tunnel in &vpn.tunnels {
+                    println!("• {} — {} ({})", tunnel.name, tunnel.vpn_type, if tunnel.is_up { "up" } else { "down" });
+                    if !tunnel.addresses.is_empty() {
+                        println!("  VPN IP: {}", tunnel.addresses.join(", "));
+                    }
+                    if let Some(endpoint) = &tunnel.endpoint {
+                        println!("  Endpoint: {}", endpoint);
+                    }
+                    if tunnel.carries_default_route {
+                        println!("  Carries default route");
+                    }
+                    for peer in &tunnel.wireguard_peers {
+                        let handshake = match peer.latest_handshake {
+                            Some(at) => format!("{}s ago", (Utc::now() - at).num_seconds()),
+                            None => "never".to_string(),
+                        };
+                        let key: String = peer.public_key.chars().take(12).collect();
+                        println!("  Peer {}… handshake {}, rx {} / tx {}",
+                            key, handshake, vpn::format_bytes(peer.rx_bytes), vpn::format_bytes(peer.tx_bytes));
+                    }
                 }
             }
+            None => print_error(snapshot, Section::Vpn),
         }
-        Err(e) => {
-            println!("⚠️  Error: {}", e);
-        }
-    }
-    
-    println!();
-    
-    // Network Peers
-    println!("👥 NETWORK PEERS");
-    println!("───────────────────────────────────────────────────────────");
-    match network::get_network_peers() {
-        Ok(peers) => {
-            if peers.is_empty() {
-                println!("No active network peers detected");
-            } else {
-                println!("{:<20} {:<20} {:<15} {:<10}", "IP Address", "MAC Address", "Device Type", "State");
-                println!("{}", "─".repeat(65));
-                for peer in peers.iter().take(10) {
-                    let mac = peer.mac.as_deref().unwrap_or("N/A");
-                    println!("{:<20} {:<20} {:<15} {:<10}", 
-                        peer.ip, mac, peer.device_type, peer.state);
-                }
-                if peers.len() > 10 {
-                    println!("... and {} more", peers.len() - 10);
+        
+        println!();
+    }
+    
+    if sections.contains(&Section::Ports) {
+        // Open Ports
+        println!("🔓 OPEN PORTS");
+        println!("───────────────────────────────────────────────────────────");
+        match &snapshot.open_ports {
+            Some(ports) => {
+                if ports.is_empty() {
+                    println!("No listening ports detected");
+                } else {
+                    println!("{:<10} {:<10} {:<15}", "Protocol", "Port", "State");
+                    println!("{}", "─".repeat(35));
+                    for (proto, port, state) in ports.iter().take(15) {
+                        println!("{:<10} {:<10} {:<15}", proto, port, state);
+                    }
+                    if ports.len() > 15 {
+                        println!("... and {} more", ports.len() - 15);
+                    }
                 }
             }
+            None => print_error(snapshot, Section::Ports),
         }
-        Err(e) => {
-            println!("⚠️  Error: {}", e);
-        }
+        
+        println!();
     }
     
-    println!();
-    
-    // Security Status
-    println!("🛡️  SECURITY STATUS");
-    println!("───────────────────────────────────────────────────────────");
-    match security::scan_security_logs() {
-        Ok(alerts) => {
-            let critical = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::Critical)).count();
-            let high = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::High)).count();
-            let medium = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::Medium)).count();
-            let low = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::Low)).count();
-            
-            println!("Alert Summary:");
-            if critical > 0 {
-                println!("  🔴 Critical: {}", critical);
-            }
-            if high > 0 {
-                println!("  🟠 High: {}", high);
-            }
-            if medium > 0 {
-                println!("  🟡 Medium: {}", medium);
-            }
-            if low > 0 {
-                println!("  🟢 Low: {}", low);
-            }
-            
-            if critical == 0 && high == 0 && medium == 0 && low == 0 {
-                println!("  ✅ No alerts detected");
-            }
-            
-            // Show recent alerts
-            if !alerts.is_empty() {
-                println!("\nRecent Alerts:");
-                for alert in alerts.iter().take(5) {
-                    println!("  [{}] {}: {}", 
-                        alert.severity, 
-                        alert.timestamp.format("%Y-%m-%d %H:%M:%S"),
-                        alert.message);
+    if sections.contains(&Section::Peers) {
+        // Network Peers
+        println!("👥 NETWORK PEERS");
+        println!("───────────────────────────────────────────────────────────");
+        match &snapshot.peers {
+            Some(peers) => {
+                if peers.is_empty() {
+                    println!("No active network peers detected");
+                } else {
+                    println!("{:<20} {:<20} {:<24} {:<15} {:<10}", "IP Address", "MAC Address", "Vendor", "Device Type", "State");
+                    println!("{}", "─".repeat(90));
+                    for peer in peers.iter().take(10) {
+                        let mac = peer.mac.as_deref().unwrap_or("N/A");
+                        let vendor: String = match &peer.container {
+                            Some(name) => format!("container {}", name),
+                            None => peer.vendor.clone(),
+                        }.chars().take(23).collect();
+                        println!("{:<20} {:<20} {:<24} {:<15} {:<10}", 
+                            peer.ip, mac, vendor, peer.device_type, peer.state);
+                    }
+                    if peers.len() > 10 {
+                        println!("... and {} more", peers.len() - 10);
+                    }
                 }
             }
+            None => print_error(snapshot, Section::Peers),
         }
-        Err(e) => {
-            println!("⚠️  Error scanning security logs: {}", e);
-        }
+        
+        println!();
     }
     
-    match security::check_firewall_status() {
-        Ok(active) => {
-            println!("\nFirewall: {}", if active { "🟢 Active" } else { "🔴 Inactive" });
+    if sections.contains(&Section::Security) {
+        // Security Status
+        println!("🛡️  SECURITY STATUS");
+        println!("───────────────────────────────────────────────────────────");
+        match &snapshot.alerts {
+            Some(alerts) => {
+                let critical = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::Critical)).count();
+                let high = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::High)).count();
+                let medium = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::Medium)).count();
+                let low = alerts.iter().filter(|a| matches!(a.severity, security::AlertSeverity::Low)).count();
+                
+                println!("Alert Summary:");
+                if critical > 0 {
+                    println!("  🔴 Critical: {}", critical);
+                }
+                if high > 0 {
+                    println!("  🟠 High: {}", high);
+                }
+                if medium > 0 {
+                    println!("  🟡 Medium: {}", medium);
+                }
+                if low > 0 {
+                    println!("  🟢 Low: {}", low);
+                }
+                
+                if critical == 0 && high == 0 && medium == 0 && low == 0 {
+                    println!("  ✅ No alerts detected");
+                }
+                
+                // Show recent alerts
+                if !alerts.is_empty() {
+                    println!("\nRecent Alerts:");
+                    for alert in alerts.iter().take(5) {
+                        let id = alert.id();
+                        let marker = if snapshot.new_alert_ids.contains(&id) { " 🆕" } else { "" };
+                        println!("  [{}] {} {}: {}{}", 
+                            alert.severity, 
+                            id,
+                            alert.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                            alert.message,
+                            marker);
+                    }
+                    println!("Manage with: netninja-cli alerts list | ack <id> | resolve <id> | suppress");
+                }
+            }
+            None => {
+                if let Some(e) = snapshot.errors.get(Section::Security.name()) {
+                    println!("⚠️  Error scanning security logs: {}", e);
+                }
+            }
         }
-        Err(_) => {
-            println!("\nFirewall: ⚠️  Status unknown");
+        if let (Some(_), Some(e)) = (&snapshot.alerts, snapshot.errors.get(Section::Security.name())) {
+            println!("\n⚠️  Some detections could not run: {}", e);
         }
+        
+        match snapshot.firewall_active {
+            Some(active) => {
+                println!("\nFirewall: {}", if active { "🟢 Active" } else { "🔴 Inactive" });
+            }
+            None => {
+                println!("\nFirewall: ⚠️  Status unknown");
+            }
+        }
+        
+        println!();
     }
     
-    println!();
-    println!("═══════════════════════════════════════════════════════════");
-    println!("\n💡 Tip: Run 'netninja-cli monitor' for live monitoring dashboard");
-    println!();
-    
-    Ok(())
+    if full_report {
+        println!("═══════════════════════════════════════════════════════════");
+        println!("\n💡 Tip: Run 'netninja-cli monitor' for live monitoring dashboard");
+        println!();
+    }
 }
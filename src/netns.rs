@@ -0,0 +1,219 @@
+use anyhow::{anyhow, Context, Result};
+use crate::network;
+use nix::sched::{self, CloneFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Where `ip netns add` bind-mounts named namespaces
+const NAMED_NETNS_DIR: &str = "/run/netns";
+
+/// Processes listed per namespace in the overview
+const MAX_LISTED_PROCESSES: usize = 3;
+
+/// The namespace selected with `--netns`, if any
+static ENTERED: OnceLock<String> = OnceLock::new();
+
+/// Stable name of the entered namespace for keying state files
+static STATE_KEY: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetNamespace {
+    /// Name under /run/netns, if the namespace has one
+    pub name: Option<String>,
+    pub inode: u64,
+    pub is_current: bool,
+    /// Processes living in the namespace as (pid, command)
+    pub processes: Vec<(u32, String)>,
+}
+
+impl NetNamespace {
+    /// Name if it has one, otherwise the first process in it
+    pub fn label(&self) -> String {
+        match (&self.name, self.processes.first()) {
+            (Some(name), _) => name.clone(),
+            (None, Some((pid, comm))) => format!("pid {} ({})", pid, comm),
+            (None, None) => format!("net:[{}]", self.inode),
+        }
+    }
+
+    /// Value to pass to `--netns` to enter this namespace
+    pub fn selector(&self) -> Option<String> {
+        self.name
+            .clone()
+            .or_else(|| self.processes.first().map(|(pid, _)| pid.to_string()))
+    }
+}
+
+/// Path of the namespace file for a name in /run/netns or a PID
+pub fn resolve(spec: &str) -> Result<PathBuf> {
+    let path = if spec.chars().all(|c| c.is_ascii_digit()) {
+        PathBuf::from(format!("/proc/{}/ns/net", spec))
+    } else {
+        Path::new(NAMED_NETNS_DIR).join(spec)
+    };
+    if !path.exists() {
+        return Err(anyhow!("No network namespace named or owned by '{}'", spec));
+    }
+    Ok(path)
+}
+
+/// Move the calling thread into a network namespace.
+/// Threads and processes started afterwards inherit it.
+fn setns(spec: &str) -> Result<()> {
+    let path = resolve(spec)?;
+    let file = File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    sched::setns(&file, CloneFlags::CLONE_NEWNET)
+        .with_context(|| format!("Failed to enter network namespace '{}' (needs root)", spec))
+}
+
+/// Switch the whole process into a namespace; must run before any other thread is started
+pub fn enter(spec: &str) -> Result<()> {
+    let key = state_key_for(spec)?;
+    setns(spec)?;
+    let _ = ENTERED.set(spec.to_string());
+    let _ = STATE_KEY.set(key);
+    Ok(())
+}
+
+/// The namespace entered with `--netns`, if any
+pub fn entered() -> Option<&'static str> {
+    ENTERED.get().map(|s| s.as_str())
+}
+
+/// Key for the entered namespace's state, the same however it was selected
+pub fn state_key() -> Option<&'static str> {
+    STATE_KEY.get().map(|s| s.as_str())
+}
+
+/// The /run/netns name of the namespace, or else its inode; a PID changes whenever its process restarts
+fn state_key_for(spec: &str) -> Result<String> {
+    let path = resolve(spec)?;
+    let inode = namespace_inode(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let name = fs::read_dir(NAMED_NETNS_DIR).ok().and_then(|dir| {
+        dir.flatten()
+            .find(|file| namespace_inode(&file.path()) == Some(inode))
+            .map(|file| file.file_name().to_string_lossy().to_string())
+    });
+    Ok(name.unwrap_or_else(|| format!("net{}", inode)))
+}
+
+/// Run a closure on a separate thread inside another namespace, leaving the caller untouched
+pub fn run_in<T, F>(spec: &str, f: F) -> Result<T>
+where
+    T: Send,
+    F: FnOnce() -> Result<T> + Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                setns(spec)?;
+                f()
+            })
+            .join()
+            .map_err(|_| anyhow!("Namespace worker thread panicked"))?
+    })
+}
+
+fn namespace_inode(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().map(|m| m.ino())
+}
+
+/// Every network namespace that is named or has a process in it
+pub fn list_namespaces() -> Result<Vec<NetNamespace>> {
+    let current = namespace_inode(Path::new("/proc/self/ns/net"));
+    let mut namespaces: BTreeMap<u64, NetNamespace> = BTreeMap::new();
+    let empty = |inode: u64| NetNamespace {
+        name: None,
+        inode,
+        is_current: Some(inode) == current,
+        processes: Vec::new(),
+    };
+
+    if let Ok(dir) = fs::read_dir(NAMED_NETNS_DIR) {
+        for file in dir.flatten() {
+            if let Some(inode) = namespace_inode(&file.path()) {
+                namespaces.entry(inode).or_insert_with(|| empty(inode)).name =
+                    Some(file.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+
+    for proc_entry in fs::read_dir("/proc").context("Failed to read /proc")?.flatten() {
+        let Ok(pid) = proc_entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        // Processes of other users are unreadable without root
+        let Some(inode) = namespace_inode(&proc_entry.path().join("ns/net")) else {
+            continue;
+        };
+        let comm = fs::read_to_string(proc_entry.path().join("comm"))
+            .map(|c| c.trim().to_string())
+            .unwrap_or_default();
+        namespaces.entry(inode).or_insert_with(|| empty(inode)).processes.push((pid, comm));
+    }
+
+    let mut list: Vec<NetNamespace> = namespaces.into_values().collect();
+    for ns in &mut list {
+        ns.processes.sort();
+    }
+    list.sort_by_key(|ns| (!ns.is_current, ns.name.is_none(), ns.processes.first().map(|p| p.0)));
+    Ok(list)
+}
+
+/// List namespaces with the interfaces inside each
+pub async fn show_namespaces() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("           🗂️  NetNinja Network Namespaces  🗂️             ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let namespaces = tokio::task::spawn_blocking(list_namespaces).await??;
+
+    for ns in &namespaces {
+        let marker = if ns.is_current { " (current)" } else { "" };
+        println!("🗂️  {}{}  net:[{}]", ns.label(), marker, ns.inode);
+
+        if !ns.processes.is_empty() {
+            let listed: Vec<String> = ns
+                .processes
+                .iter()
+                .take(MAX_LISTED_PROCESSES)
+                .map(|(pid, comm)| format!("{} ({})", comm, pid))
+                .collect();
+            let more = ns.processes.len().saturating_sub(MAX_LISTED_PROCESSES);
+            let suffix = if more > 0 { format!(" and {} more", more) } else { String::new() };
+            println!("   Processes: {}{}", listed.join(", "), suffix);
+        }
+
+        let interfaces = match ns.selector() {
+            _ if ns.is_current => network::get_interfaces(),
+            Some(selector) => run_in(&selector, network::get_interfaces),
+            None => Err(anyhow!("namespace has no name or process to enter")),
+        };
+        match interfaces {
+            Ok(interfaces) => {
+                for iface in interfaces {
+                    let state = if iface.is_up { "🟢" } else { "🔴" };
+                    let addresses = if iface.ip_addresses.is_empty() {
+                        "no addresses".to_string()
+                    } else {
+                        iface.ip_addresses.join(", ")
+                    };
+                    println!("   {} {:<16} {}", state, iface.name, addresses);
+                }
+            }
+            Err(e) => println!("   ⚠️  Cannot inspect interfaces: {}", e),
+        }
+        if let Some(selector) = ns.selector().filter(|_| !ns.is_current) {
+            println!("   Inspect with: netninja-cli --netns {} status", selector);
+        }
+        println!();
+    }
+    println!("{} namespace(s)", namespaces.len());
+    println!();
+
+    Ok(())
+}
@@ -3,6 +3,8 @@ use pnet::datalink;
 use std::net::IpAddr;
 use std::process::Command;
 use serde::{Deserialize, Serialize};
+use crate::vpn::{self, VpnTunnel};
+use crate::{containers, fingerprint, oui};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInterface {
@@ -15,9 +17,42 @@ pub struct NetworkInterface {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VpnStatus {
     pub is_connected: bool,
-    pub interface: Option<String>,
-    pub ip_address: Option<String>,
-    pub vpn_type: Option<String>,
+    pub tunnels: Vec<VpnTunnel>,
+    /// Whether ordinary traffic to the internet leaves through one of the tunnels
+    pub default_route_via_vpn: bool,
+}
+
+/// Link details from `ip -d -j link show`, including the netlink link kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkDetails {
+    pub name: String,
+    pub index: u32,
+    /// e.g. "wireguard", "tun", "vti", "bridge", "veth"; None for plain devices
+    pub kind: Option<String>,
+    pub link_type: String,
+    pub operstate: String,
+    pub flags: Vec<String>,
+    /// Kind-specific attributes, such as the tun type or tunnel endpoints
+    pub info_data: serde_json::Value,
+    /// Index of the peer device, which for a veth may live in another namespace
+    pub link_index: Option<u32>,
+    /// Bridge or bond the device is enslaved to
+    pub master: Option<String>,
+    /// Lower device of a VLAN, macvlan or ipvlan, or the peer of a veth in this namespace
+    pub parent: Option<String>,
+    /// Set when the peer device lives in another namespace
+    pub link_netnsid: Option<i64>,
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    /// Kind of master the device is enslaved to ("bridge", "bond") and its port attributes
+    pub slave_kind: Option<String>,
+    pub slave_data: serde_json::Value,
+}
+
+impl LinkDetails {
+    pub fn is_up(&self) -> bool {
+        self.flags.iter().any(|f| f == "UP")
+    }
 }
 
 /// Get all network interfaces
@@ -77,43 +112,55 @@ pub fn get_primary_interface() -> Result<NetworkInterface> {
     Err(anyhow::anyhow!("No suitable network interface found"))
 }
 
-/// Detect VPN connection status
+/// Detect VPN tunnels and whether the default route uses one of them
 pub fn get_vpn_status() -> Result<VpnStatus> {
-    let interfaces = get_interfaces()?;
-    
-    // Common VPN interface names
-    let vpn_patterns = vec!["tun", "tap", "wg", "ppp", "vpn"];
-    
-    for iface in interfaces {
-        for pattern in &vpn_patterns {
-            if iface.name.contains(pattern) && iface.is_up {
-                let ip_address = iface.ip_addresses.first().cloned();
-                let vpn_type = if iface.name.starts_with("wg") {
-                    Some("WireGuard".to_string())
-                } else if iface.name.starts_with("tun") || iface.name.starts_with("tap") {
-                    Some("OpenVPN/Generic".to_string())
-                } else {
-                    Some("Unknown".to_string())
-                };
-                
-                return Ok(VpnStatus {
-                    is_connected: true,
-                    interface: Some(iface.name),
-                    ip_address,
-                    vpn_type,
-                });
-            }
-        }
-    }
+    let tunnels = vpn::detect_tunnels()?;
     
     Ok(VpnStatus {
-        is_connected: false,
-        interface: None,
-        ip_address: None,
-        vpn_type: None,
+        is_connected: tunnels.iter().any(|t| t.is_up),
+        default_route_via_vpn: tunnels.iter().any(|t| t.carries_default_route),
+        tunnels,
     })
 }
 
+/// Read every link with its kind and attributes from `ip -d -j link show`
+pub fn get_link_details() -> Result<Vec<LinkDetails>> {
+    let output = Command::new("ip")
+        .args(["-d", "-j", "link", "show"])
+        .output()
+        .context("Failed to execute ip link command")?;
+    
+    let links: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse ip link output")?;
+    
+    Ok(links
+        .iter()
+        .filter_map(|link| {
+            let linkinfo = &link["linkinfo"];
+            Some(LinkDetails {
+                name: link["ifname"].as_str()?.to_string(),
+                index: link["ifindex"].as_u64().unwrap_or(0) as u32,
+                kind: linkinfo["info_kind"].as_str().map(str::to_string),
+                link_type: link["link_type"].as_str().unwrap_or_default().to_string(),
+                operstate: link["operstate"].as_str().unwrap_or("UNKNOWN").to_string(),
+                flags: link["flags"]
+                    .as_array()
+                    .map(|flags| flags.iter().filter_map(|f| f.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default(),
+                info_data: linkinfo["info_data"].clone(),
+                link_index: link["link_index"].as_u64().map(|i| i as u32),
+                master: link["master"].as_str().map(str::to_string),
+                parent: link["link"].as_str().map(str::to_string),
+                link_netnsid: link["link_netnsid"].as_i64(),
+                mac_address: link["address"].as_str().map(str::to_string),
+                mtu: link["mtu"].as_u64().map(|m| m as u32),
+                slave_kind: linkinfo["info_slave_kind"].as_str().map(str::to_string),
+                slave_data: linkinfo["info_slave_data"].clone(),
+            })
+        })
+        .collect())
+}
+
 /// Get open ports using netstat or ss
 pub fn get_open_ports() -> Result<Vec<(String, u16, String)>> {
     let output = Command::new("ss")
@@ -146,6 +193,166 @@ pub fn get_open_ports() -> Result<Vec<(String, u16, String)>> {
     Ok(ports)
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListeningSocket {
+    pub protocol: String,
+    pub address: String,
+    pub scope_interface: Option<String>,
+    pub port: u16,
+    pub process: Option<String>,
+}
+
+impl ListeningSocket {
+    /// Whether the socket is bound to the wildcard address of its family
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self.address.as_str(), "*" | "0.0.0.0" | "::")
+    }
+
+    /// Whether the socket only accepts connections from the host itself
+    pub fn is_loopback(&self) -> bool {
+        self.address
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+    }
+}
+
+/// Get listening TCP/UDP sockets with their bind addresses and owning process
+pub fn get_listening_sockets() -> Result<Vec<ListeningSocket>> {
+    let output = Command::new("ss")
+        .args(["-tulnp"])
+        .output()
+        .context("Failed to execute ss command")?;
+    
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    let mut sockets = Vec::new();
+    
+    for line in output_str.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        
+        // Local address looks like 0.0.0.0:22, [::]:22, *:5353 or 127.0.0.53%lo:53
+        let Some((host, port_str)) = parts[4].rsplit_once(':') else {
+            continue;
+        };
+        let Ok(port) = port_str.parse::<u16>() else {
+            continue;
+        };
+        let (host, scope_interface) = match host.split_once('%') {
+            Some((h, scope)) => (h, Some(scope.to_string())),
+            None => (host, None),
+        };
+        let address = host.trim_start_matches('[').trim_end_matches(']').to_string();
+        
+        // users:(("sshd",pid=812,fd=3))
+        let process = parts.get(6)
+            .and_then(|p| p.split('"').nth(1))
+            .map(|p| p.to_string());
+        
+        sockets.push(ListeningSocket {
+            protocol: parts[0].to_string(),
+            address,
+            scope_interface,
+            port,
+            process,
+        });
+    }
+    
+    Ok(sockets)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InterfaceClass {
+    Loopback,
+    Public,
+    Private,
+    Vpn,
+    Container,
+}
+
+impl std::fmt::Display for InterfaceClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterfaceClass::Loopback => f.pad("loopback"),
+            InterfaceClass::Public => f.pad("public"),
+            InterfaceClass::Private => f.pad("private"),
+            InterfaceClass::Vpn => f.pad("vpn"),
+            InterfaceClass::Container => f.pad("container"),
+        }
+    }
+}
+
+/// Classify an interface by its name and the kind of addresses it carries
+pub fn classify_interface(iface: &NetworkInterface) -> InterfaceClass {
+    let name = iface.name.as_str();
+    
+    if name == "lo" {
+        return InterfaceClass::Loopback;
+    }
+    
+    let container_prefixes = ["docker", "br-", "veth", "cni", "podman", "virbr", "lxcbr", "lxdbr", "flannel", "cali", "vxlan"];
+    if container_prefixes.iter().any(|p| name.starts_with(p)) {
+        return InterfaceClass::Container;
+    }
+    
+    let vpn_prefixes = ["tun", "tap", "wg", "ppp", "tailscale", "nordlynx", "zt"];
+    if vpn_prefixes.iter().any(|p| name.starts_with(p)) || name.contains("vpn") {
+        return InterfaceClass::Vpn;
+    }
+    
+    let has_public = iface.ip_addresses.iter()
+        .filter_map(|ip| ip.parse::<IpAddr>().ok())
+        .any(|ip| is_public_ip(&ip));
+    
+    if has_public {
+        InterfaceClass::Public
+    } else {
+        InterfaceClass::Private
+    }
+}
+
+/// Whether an address is globally routable (not private, loopback, link-local or CGNAT)
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let octets = v4.octets();
+            let cgnat = octets[0] == 100 && (octets[1] & 0xc0) == 64;
+            !(v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified()
+                || v4.is_multicast() || v4.is_broadcast() || v4.is_documentation() || cgnat)
+        }
+        IpAddr::V6(v6) => {
+            // Only global unicast (2000::/3) is reachable from the internet
+            (v6.segments()[0] & 0xe000) == 0x2000
+        }
+    }
+}
+
+/// Get default gateways as (gateway address, interface) pairs for IPv4 and IPv6
+pub fn get_default_gateways() -> Result<Vec<(String, String)>> {
+    let mut gateways = Vec::new();
+    
+    for family in ["-4", "-6"] {
+        let output = Command::new("ip")
+            .args([family, "route", "show", "default"])
+            .output()
+            .context("Failed to execute ip route command")?;
+        
+        // default via 192.168.1.1 dev eth0 proto dhcp metric 100
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let via = parts.iter().position(|p| *p == "via").and_then(|i| parts.get(i + 1));
+            let dev = parts.iter().position(|p| *p == "dev").and_then(|i| parts.get(i + 1));
+            if let (Some(via), Some(dev)) = (via, dev) {
+                gateways.push((via.to_string(), dev.to_string()));
+            }
+        }
+    }
+    
+    Ok(gateways)
+}
+
 /// Get active network peers
 pub fn get_network_peers() -> Result<Vec<NetworkPeer>> {
     let output = Command::new("ip")
@@ -162,6 +369,12 @@ pub fn get_network_peers() -> Result<Vec<NetworkPeer>> {
         }
     }
     
+    // Refine the MAC-based guesses with passive fingerprints from earlier captures
+    fingerprint::apply_fingerprints(&mut peers);
+    
+    // Name local containers instead of showing anonymous locally administered MACs
+    containers::annotate_peers(&mut peers);
+    
     Ok(peers)
 }
 
@@ -173,6 +386,11 @@ pub struct NetworkPeer {
     pub state: String,
     pub device_type: String,
     pub os_guess: String,
+    pub vendor: String,
+    pub randomized_mac: bool,
+    pub hostname: Option<String>,
+    /// Name of the local container that owns this address
+    pub container: Option<String>,
 }
 
 fn parse_neighbor_line(line: &str) -> Option<NetworkPeer> {
@@ -197,16 +415,29 @@ fn parse_neighbor_line(line: &str) -> Option<NetworkPeer> {
         }
     }
     
-    let (device_type, os_guess) = guess_device_from_mac(&mac);
-    
-    Some(NetworkPeer {
-        ip,
-        mac,
-        interface,
-        state,
-        device_type,
-        os_guess,
-    })
+    Some(NetworkPeer::new(ip, mac, interface, state))
+}
+
+impl NetworkPeer {
+    /// Build a peer, deriving vendor and device guesses from its MAC
+    pub fn new(ip: String, mac: Option<String>, interface: String, state: String) -> Self {
+        let (device_type, os_guess) = guess_device_from_mac(&mac);
+        let vendor = mac.as_deref().map(oui::vendor_for_mac).unwrap_or_else(|| "Unknown".to_string());
+        let randomized_mac = mac.as_deref().map(oui::is_locally_administered).unwrap_or(false);
+        
+        NetworkPeer {
+            ip,
+            mac,
+            interface,
+            state,
+            device_type,
+            os_guess,
+            vendor,
+            randomized_mac,
+            hostname: None,
+            container: None,
+        }
+    }
 }
 
 fn guess_device_from_mac(mac: &Option<String>) -> (String, String) {
@@ -216,22 +447,55 @@ fn guess_device_from_mac(mac: &Option<String>) -> (String, String) {
         // Simple OUI-based device detection
         match oui.to_lowercase().as_str() {
             s if s.starts_with("00:50:56") || s.starts_with("00:0c:29") => {
-                ("Virtual Machine".to_string(), "VMware".to_string())
+                return ("Virtual Machine".to_string(), "VMware".to_string());
             }
             s if s.starts_with("08:00:27") => {
-                ("Virtual Machine".to_string(), "VirtualBox".to_string())
+                return ("Virtual Machine".to_string(), "VirtualBox".to_string());
+            }
+            s if s.starts_with("52:54:00") => {
+                return ("Virtual Machine".to_string(), "QEMU/KVM".to_string());
+            }
+            s if s.starts_with("02:42:") => {
+                return ("Container".to_string(), "Docker".to_string());
             }
             s if s.starts_with("dc:a6:32") || s.starts_with("b8:27:eb") => {
-                ("IoT Device".to_string(), "Raspberry Pi".to_string())
+                return ("IoT Device".to_string(), "Raspberry Pi".to_string());
             }
             s if s.starts_with("00:1b:63") => {
-                ("Computer".to_string(), "Apple".to_string())
+                return ("Computer".to_string(), "Apple".to_string());
             }
-            _ => ("Unknown".to_string(), "Unknown".to_string())
+            _ => {}
+        }
+        
+        // Fall back to keywords in the registered vendor name
+        let vendor = oui::vendor_for_mac(mac_addr).to_lowercase();
+        let guesses: &[(&[&str], &str, &str)] = &[
+            (&["vmware"], "Virtual Machine", "VMware"),
+            (&["pcs systemtechnik", "oracle"], "Virtual Machine", "VirtualBox"),
+            (&["xensource"], "Virtual Machine", "Xen"),
+            (&["raspberry pi"], "IoT Device", "Raspberry Pi"),
+            (&["espressif", "tuya", "shelly"], "IoT Device", "Embedded"),
+            (&["apple"], "Computer", "Apple"),
+            (&["samsung", "xiaomi", "oneplus", "huawei", "motorola", "oppo", "vivo"], "Mobile Device", "Android"),
+            (&["google"], "Mobile Device", "Android/ChromeOS"),
+            (&["microsoft"], "Computer", "Windows"),
+            (&["cisco", "juniper", "routerboard", "mikrotik", "ubiquiti", "tp-link", "netgear", "aruba", "arista"], "Network Device", "Network OS"),
+            (&["synology", "qnap"], "NAS", "Linux"),
+            (&["brother", "canon", "epson", "lexmark", "xerox"], "Printer", "Embedded"),
+            (&["intel", "realtek", "dell", "lenovo", "hewlett", "asustek", "giga-byte", "micro-star"], "Computer", "Unknown"),
+        ];
+        for (keywords, device_type, os_guess) in guesses {
+            if keywords.iter().any(|k| vendor.contains(k)) {
+                return (device_type.to_string(), os_guess.to_string());
+            }
+        }
+        
+        if oui::is_locally_administered(mac_addr) {
+            return ("Randomized MAC".to_string(), "Unknown".to_string());
         }
-    } else {
-        ("Unknown".to_string(), "Unknown".to_string())
     }
+    
+    ("Unknown".to_string(), "Unknown".to_string())
 }
 
 /// Get network statistics from /proc/net/dev
@@ -240,3 +504,47 @@ pub fn get_network_stats() -> Result<String> {
         .context("Failed to read /proc/net/dev")?;
     Ok(stats)
 }
+
+/// Per-interface traffic counters since the interface was created
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterfaceCounters {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+/// Parse the counters of every interface from /proc/net/dev
+pub fn get_interface_counters() -> Result<Vec<InterfaceCounters>> {
+    let stats = get_network_stats()?;
+    let mut counters = Vec::new();
+    
+    // eth0: rx_bytes packets errs drop fifo frame compressed multicast tx_bytes packets errs drop ...
+    for line in stats.lines().skip(2) {
+        let Some((name, fields)) = line.split_once(':') else {
+            continue;
+        };
+        let fields: Vec<u64> = fields.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+        if fields.len() < 12 {
+            continue;
+        }
+        counters.push(InterfaceCounters {
+            name: name.trim().to_string(),
+            rx_bytes: fields[0],
+            rx_packets: fields[1],
+            rx_errors: fields[2],
+            rx_dropped: fields[3],
+            tx_bytes: fields[8],
+            tx_packets: fields[9],
+            tx_errors: fields[10],
+            tx_dropped: fields[11],
+        });
+    }
+    
+    Ok(counters)
+}
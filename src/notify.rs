@@ -0,0 +1,515 @@
+use anyhow::{anyhow, Context, Result};
+use crate::config::{self, SinkConfig, SinkKind, SyslogProtocol};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Connect, read and write timeout for every sink
+const SINK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Local syslog socket used when a syslog sink has no address
+const DEV_LOG: &str = "/dev/log";
+
+/// Private enterprise number reserved for documentation (RFC 5612), used for structured data
+const SD_ID: &str = "netninja@32473";
+
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|h| h.trim().to_string())
+        .unwrap_or_else(|_| "localhost".to_string())
+}
+
+/// Alert as sent to webhooks and commands
+fn alert_json(alert: &SecurityAlert) -> serde_json::Value {
+    serde_json::json!({
+        "source": "netninja",
+        "host": hostname(),
+        "timestamp": alert.timestamp,
+        "severity": alert.severity.label(),
+        "category": alert.category.label(),
+        "message": alert.message,
+        "details": alert.details,
+    })
+}
+
+fn send_webhook(url: &str, headers: &std::collections::BTreeMap<String, String>, alert: &SecurityAlert) -> Result<()> {
+    let body = alert_json(alert).to_string();
+
+    // curl handles TLS; plain HTTP needs nothing beyond a socket
+    let Some(rest) = url.strip_prefix("http://") else {
+        let mut args = vec!["-fsS".to_string(), "--max-time".to_string(), SINK_TIMEOUT.as_secs().to_string()];
+        args.extend(["-H".to_string(), "Content-Type: application/json".to_string()]);
+        for (name, value) in headers {
+            args.extend(["-H".to_string(), format!("{}: {}", name, value)]);
+        }
+        args.extend(["--data-binary".to_string(), "@-".to_string(), url.to_string()]);
+        return run_with_stdin(Command::new("curl").args(&args), body.as_bytes());
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+    let mut stream = connect(&address)?;
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: netninja\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        path,
+        authority,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(&body);
+    stream.write_all(request.as_bytes())?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    // HTTP/1.1 204 No Content
+    match status_line.split_whitespace().nth(1).and_then(|code| code.parse::<u16>().ok()) {
+        Some(code) if (200..300).contains(&code) => Ok(()),
+        Some(_) => Err(anyhow!("Webhook answered {}", status_line.trim())),
+        None => Err(anyhow!("Webhook sent no HTTP response")),
+    }
+}
+
+fn connect(address: &str) -> Result<TcpStream> {
+    let socket_address = address
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {}", address))?
+        .next()
+        .ok_or_else(|| anyhow!("No address for {}", address))?;
+    let stream = TcpStream::connect_timeout(&socket_address, SINK_TIMEOUT)
+        .with_context(|| format!("Failed to connect to {}", address))?;
+    stream.set_read_timeout(Some(SINK_TIMEOUT))?;
+    stream.set_write_timeout(Some(SINK_TIMEOUT))?;
+    Ok(stream)
+}
+
+/// Syslog severity for an alert severity
+fn syslog_severity(severity: &AlertSeverity) -> u8 {
+    match severity {
+        AlertSeverity::Critical => 2,
+        AlertSeverity::High => 3,
+        AlertSeverity::Medium => 4,
+        AlertSeverity::Low => 5,
+        AlertSeverity::Info => 6,
+    }
+}
+
+/// Format an alert as an RFC 5424 message
+fn syslog_message(alert: &SecurityAlert, facility: u8) -> String {
+    let message = match &alert.details {
+        Some(details) => format!("{}: {}", alert.message, details),
+        None => alert.message.clone(),
+    };
+    // <PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] MSG
+    format!(
+        "<{}>1 {} {} netninja {} {} [{} severity=\"{}\" category=\"{}\"] {}",
+        facility as u16 * 8 + syslog_severity(&alert.severity) as u16,
+        alert.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        hostname(),
+        std::process::id(),
+        alert.category.label(),
+        SD_ID,
+        alert.severity.label(),
+        alert.category.label(),
+        message.replace('\n', " ")
+    )
+}
+
+fn send_syslog(address: Option<&str>, protocol: SyslogProtocol, facility: u8, alert: &SecurityAlert) -> Result<()> {
+    let message = syslog_message(alert, facility);
+    match (address, protocol) {
+        (None, _) => {
+            let socket = UnixDatagram::unbound()?;
+            socket.send_to(message.as_bytes(), DEV_LOG)
+                .with_context(|| format!("Failed to write to {}", DEV_LOG))?;
+        }
+        (Some(address), SyslogProtocol::Udp) => {
+            let socket = UdpSocket::bind(if address.starts_with('[') { "[::]:0" } else { "0.0.0.0:0" })?;
+            socket.send_to(message.as_bytes(), address)
+                .with_context(|| format!("Failed to send to {}", address))?;
+        }
+        (Some(address), SyslogProtocol::Tcp) => {
+            // Octet-counting framing (RFC 6587)
+            let mut stream = connect(address)?;
+            stream.write_all(format!("{} {}", message.len(), message).as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Read one SMTP reply, which may span several lines, and check its code
+fn smtp_expect(reader: &mut BufReader<TcpStream>, expected: u16) -> Result<()> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(anyhow!("SMTP server closed the connection"));
+        }
+        // 250-PIPELINING continues, 250 OK ends the reply
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        let code: u16 = line.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+        if code != expected {
+            return Err(anyhow!("SMTP server answered {}", line.trim()));
+        }
+        return Ok(());
+    }
+}
+
+fn send_email(server: &str, from: &str, to: &[String], alert: &SecurityAlert) -> Result<()> {
+    let stream = connect(server)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut command = |line: String, expected: u16, reader: &mut BufReader<TcpStream>| -> Result<()> {
+        writer.write_all(format!("{}\r\n", line).as_bytes())?;
+        smtp_expect(reader, expected)
+    };
+
+    smtp_expect(&mut reader, 220)?;
+    command(format!("EHLO {}", hostname()), 250, &mut reader)?;
+    command(format!("MAIL FROM:<{}>", from), 250, &mut reader)?;
+    for recipient in to {
+        command(format!("RCPT TO:<{}>", recipient), 250, &mut reader)?;
+    }
+    command("DATA".to_string(), 354, &mut reader)?;
+
+    let mut body = format!(
+        "Severity: {}\r\nCategory: {}\r\nTime: {}\r\nHost: {}\r\n\r\n{}\r\n",
+        alert.severity,
+        alert.category.label(),
+        alert.timestamp.to_rfc3339(),
+        hostname(),
+        alert.message
+    );
+    if let Some(details) = &alert.details {
+        body.push_str(&format!("\r\n{}\r\n", details.replace('\n', "\r\n")));
+    }
+    // Dot-stuffing: a line starting with "." must not end the message early
+    let body = body.replace("\r\n.", "\r\n..");
+    let message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: [NetNinja] {} {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}.",
+        from,
+        to.join(", "),
+        alert.severity,
+        alert.message.replace(['\r', '\n'], " "),
+        alert.timestamp.to_rfc2822(),
+        body
+    );
+    command(message, 250, &mut reader)?;
+    command("QUIT".to_string(), 221, &mut reader)
+}
+
+/// Run a command with `input` on stdin, killing it if it outlives the sink timeout
+fn run_with_stdin(command: &mut Command, input: &[u8]) -> Result<()> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start command")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores stdin closes the pipe; that is not a failure
+        let _ = stdin.write_all(input);
+    }
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > SINK_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("Command timed out after {}s", SINK_TIMEOUT.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    if !status.success() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        return Err(anyhow!("Command exited with {}: {}", status, stderr.trim()));
+    }
+    Ok(())
+}
+
+fn send_command(command: &str, alert: &SecurityAlert) -> Result<()> {
+    run_with_stdin(
+        Command::new("sh")
+            .args(["-c", command])
+            .env("NETNINJA_SEVERITY", alert.severity.label())
+            .env("NETNINJA_CATEGORY", alert.category.label())
+            .env("NETNINJA_MESSAGE", &alert.message)
+            .env("NETNINJA_DETAILS", alert.details.as_deref().unwrap_or("")),
+        alert_json(alert).to_string().as_bytes(),
+    )
+}
+
+/// Deliver one alert to a sink, ignoring its filters and limits
+pub fn deliver(sink: &SinkConfig, alert: &SecurityAlert) -> Result<()> {
+    match &sink.kind {
+        SinkKind::Webhook { url, headers } => send_webhook(url, headers, alert),
+        SinkKind::Syslog { address, protocol, facility } => send_syslog(address.as_deref(), *protocol, *facility, alert),
+        SinkKind::Email { server, from, to } => send_email(server, from, to, alert),
+        SinkKind::Command { command } => send_command(command, alert),
+    }
+}
+
+/// A sink with the state behind its deduplication window and rate limit
+struct SinkState {
+    config: SinkConfig,
+    /// Last notification per alert key
+    recent: HashMap<String, DateTime<Utc>>,
+    /// When the notifications within the current rate window were sent
+    sent: VecDeque<DateTime<Utc>>,
+    /// Alerts dropped by the rate limit since the last notification
+    rate_limited: usize,
+    /// Repeats held back by the dedup window since the last notification
+    duplicates: usize,
+}
+
+impl SinkState {
+    fn wants(&self, alert: &SecurityAlert) -> bool {
+        alert.severity.rank() >= self.config.min_severity.rank()
+            && (self.config.categories.is_empty()
+                || self.config.categories.iter().any(|c| c.label() == alert.category.label()))
+    }
+}
+
+/// Alerts about the same subject share a key, and so do identical messages; details such as
+/// individual log lines are ignored
+fn dedup_key(alert: &SecurityAlert) -> String {
+    match &alert.subject {
+        Some(_) => alert.fingerprint(),
+        None => format!("{}|{}", alert.category.label(), alert.message),
+    }
+}
+
+/// Fans alerts out to the configured sinks with filtering, deduplication and rate limiting
+pub struct Dispatcher {
+    sinks: Vec<SinkState>,
+}
+
+impl Dispatcher {
+    pub fn new(sinks: Vec<SinkConfig>) -> Self {
+        Dispatcher {
+            sinks: sinks
+                .into_iter()
+                .map(|config| SinkState {
+                    config,
+                    recent: HashMap::new(),
+                    sent: VecDeque::new(),
+                    rate_limited: 0,
+                    duplicates: 0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Dispatcher for the sinks in the config file
+    pub fn from_config() -> Result<Self> {
+        Ok(Self::new(config::load()?.alerts.sinks))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    pub fn sink_labels(&self) -> Vec<String> {
+        self.sinks.iter().map(|s| s.config.label()).collect()
+    }
+
+    /// Send each alert to every sink that wants it, returning the delivery failures
+    pub fn dispatch(&mut self, alerts: &[SecurityAlert]) -> Vec<(String, anyhow::Error)> {
+        let now = Utc::now();
+        let mut failures = Vec::new();
+
+        for sink in &mut self.sinks {
+            let dedup_window = sink.config.dedup_window;
+            sink.recent.retain(|_, at| now - *at < dedup_window);
+            let rate_window = sink.config.rate_window;
+            while sink.sent.front().is_some_and(|at| now - *at >= rate_window) {
+                sink.sent.pop_front();
+            }
+
+            for alert in alerts {
+                if !sink.wants(alert) {
+                    continue;
+                }
+                let key = dedup_key(alert);
+                if sink.recent.contains_key(&key) {
+                    sink.duplicates += 1;
+                    continue;
+                }
+                if sink.sent.len() >= sink.config.rate_limit {
+                    sink.rate_limited += 1;
+                    continue;
+                }
+
+                // Say what was held back so a quiet channel is not mistaken for a quiet network
+                let mut alert = alert.clone();
+                let mut notes = Vec::new();
+                if sink.rate_limited > 0 {
+                    notes.push(format!("{} alert(s) were dropped by the rate limit", sink.rate_limited));
+                }
+                if sink.duplicates > 0 {
+                    notes.push(format!("{} repeated alert(s) were held back by the dedup window", sink.duplicates));
+                }
+                if let Some(details) = alert.details.take() {
+                    notes.insert(0, details);
+                }
+                alert.details = (!notes.is_empty()).then(|| notes.join("\n"));
+
+                match deliver(&sink.config, &alert) {
+                    Ok(()) => {
+                        sink.rate_limited = 0;
+                        sink.duplicates = 0;
+                        sink.sent.push_back(now);
+                        sink.recent.insert(key, now);
+                    }
+                    Err(e) => failures.push((sink.config.label(), e)),
+                }
+            }
+        }
+
+        failures
+    }
+}
+
+/// Send a test alert to every configured sink and report the result of each
+pub async fn send_test() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              🔔  NetNinja Alert Notifications  🔔         ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let config = config::load()?;
+    if config.alerts.sinks.is_empty() {
+        println!("No notification sinks configured in {}\n", config::config_path()?.display());
+        return Ok(());
+    }
+
+    let alert = SecurityAlert {
+        timestamp: Utc::now(),
+        severity: AlertSeverity::Info,
+        category: AlertCategory::SystemChange,
+        message: "NetNinja test notification".to_string(),
+        details: Some("Sent by `netninja-cli alerts test`".to_string()),
+        subject: None,
+    };
+
+    let sinks = config.alerts.sinks;
+    let results = tokio::task::spawn_blocking(move || {
+        sinks
+            .iter()
+            .map(|sink| (sink.label(), deliver(sink, &alert)))
+            .collect::<Vec<_>>()
+    })
+    .await?;
+
+    for (label, result) in results {
+        match result {
+            Ok(()) => println!("✅ {}", label),
+            Err(e) => println!("❌ {}: {:#}", label, e),
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Accept webhook POSTs on 127.0.0.1, answer 204 and pass each JSON body on
+    fn webhook_stand_in() -> (String, mpsc::Receiver<serde_json::Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                // Passed on before answering, so it has arrived by the time dispatch returns
+                tx.send(serde_json::from_slice(&body).unwrap()).unwrap();
+                reader.get_mut().write_all(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+            }
+        });
+        (url, rx)
+    }
+
+    fn alert(message: &str) -> SecurityAlert {
+        SecurityAlert {
+            timestamp: Utc::now(),
+            severity: AlertSeverity::High,
+            category: AlertCategory::FailedLogin,
+            message: message.to_string(),
+            details: None,
+            subject: Some(message.to_string()),
+        }
+    }
+
+    #[test]
+    fn webhook_dedup_and_rate_limit() {
+        let (url, posted) = webhook_stand_in();
+        let mut dispatcher = Dispatcher::new(vec![SinkConfig {
+            name: None,
+            kind: SinkKind::Webhook { url, headers: Default::default() },
+            min_severity: AlertSeverity::Low,
+            categories: Vec::new(),
+            dedup_window: chrono::Duration::minutes(10),
+            rate_limit: 2,
+            rate_window: chrono::Duration::hours(1),
+        }]);
+
+        assert!(dispatcher.dispatch(&[alert("first")]).is_empty());
+        let body = posted.try_recv().unwrap();
+        assert_eq!(body["source"], "netninja");
+        assert_eq!(body["severity"], "high");
+        assert_eq!(body["category"], "failed_login");
+        assert_eq!(body["message"], "first");
+
+        // The same alert within the dedup window is held back
+        assert!(dispatcher.dispatch(&[alert("first")]).is_empty());
+        assert!(posted.try_recv().is_err());
+
+        // The next notification says how many repeats were held back
+        assert!(dispatcher.dispatch(&[alert("second")]).is_empty());
+        let body = posted.try_recv().unwrap();
+        assert_eq!(body["message"], "second");
+        assert!(body["details"].as_str().unwrap().contains("1 repeated alert(s)"));
+
+        // Two notifications fill the rate window
+        assert!(dispatcher.dispatch(&[alert("third")]).is_empty());
+        assert!(posted.try_recv().is_err());
+        assert_eq!(dispatcher.sinks[0].rate_limited, 1);
+    }
+}
@@ -0,0 +1,283 @@
+use anyhow::{Context, Result};
+use crate::state;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Compressed registry written by `netninja-cli oui update`
+const REGISTRY_FILE: &str = "oui.csv.gz";
+
+/// IEEE registries for MA-L (24-bit), MA-M (28-bit) and MA-S (36-bit) assignments
+const REGISTRY_URLS: &[&str] = &[
+    "https://standards-oui.ieee.org/oui/oui.csv",
+    "https://standards-oui.ieee.org/oui28/mam.csv",
+    "https://standards-oui.ieee.org/oui36/oui36.csv",
+];
+
+/// Registry copies shipped by distribution packages (ieee-data, hwdata, nmap)
+const SYSTEM_REGISTRY_PATHS: &[&str] = &[
+    "/usr/share/ieee-data/oui.csv",
+    "/usr/share/ieee-data/mam.csv",
+    "/usr/share/ieee-data/oui36.csv",
+    "/usr/share/hwdata/oui.txt",
+    "/usr/share/misc/oui.txt",
+    "/usr/share/nmap/nmap-mac-prefixes",
+];
+
+/// Minimal built-in table used when no registry file is available
+const BUILTIN_OUIS: &[(&str, &str)] = &[
+    ("000569", "VMware, Inc."),
+    ("000C29", "VMware, Inc."),
+    ("001C14", "VMware, Inc."),
+    ("005056", "VMware, Inc."),
+    ("080027", "PCS Systemtechnik GmbH"),
+    ("00155D", "Microsoft Corporation"),
+    ("00163E", "Xensource, Inc."),
+    ("B827EB", "Raspberry Pi Foundation"),
+    ("DCA632", "Raspberry Pi Trading Ltd"),
+    ("E45F01", "Raspberry Pi Trading Ltd"),
+    ("28CDC1", "Raspberry Pi Trading Ltd"),
+    ("000393", "Apple, Inc."),
+    ("000A95", "Apple, Inc."),
+    ("001B63", "Apple, Inc."),
+    ("001A11", "Google, Inc."),
+    ("00000C", "Cisco Systems, Inc"),
+    ("240AC4", "Espressif Inc."),
+    ("30AEA4", "Espressif Inc."),
+    ("00E04C", "Realtek Semiconductor Corp."),
+    ("000C42", "Routerboard.com"),
+    ("4C5E0C", "Routerboard.com"),
+    ("00156D", "Ubiquiti Inc"),
+    ("24A43C", "Ubiquiti Inc"),
+    ("802AA8", "Ubiquiti Inc"),
+    ("001132", "Synology Incorporated"),
+];
+
+/// Vendor names indexed by prefix length in bits (24, 28 and 36)
+pub struct OuiDatabase {
+    vendors: Vec<String>,
+    prefixes: [HashMap<u64, usize>; 3],
+    pub source: String,
+}
+
+const PREFIX_BITS: [u32; 3] = [36, 28, 24];
+
+impl OuiDatabase {
+    fn new(source: &str) -> Self {
+        OuiDatabase {
+            vendors: Vec::new(),
+            prefixes: [HashMap::new(), HashMap::new(), HashMap::new()],
+            source: source.to_string(),
+        }
+    }
+
+    /// Add an assignment given as 6, 7 or 9 hex digits
+    fn insert(&mut self, assignment: &str, vendor: &str) {
+        let hex: String = assignment.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+        let slot = match hex.len() {
+            9 => 0,
+            7 => 1,
+            6 => 2,
+            _ => return,
+        };
+        let Ok(prefix) = u64::from_str_radix(&hex, 16) else {
+            return;
+        };
+
+        let index = self.vendors.len();
+        self.vendors.push(vendor.trim().to_string());
+        self.prefixes[slot].insert(prefix, index);
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefixes.iter().map(|p| p.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Longest-prefix lookup of a MAC address in any common notation
+    pub fn lookup(&self, mac: &str) -> Option<&str> {
+        let value = mac_to_u64(mac)?;
+        for (slot, bits) in PREFIX_BITS.iter().enumerate() {
+            if let Some(index) = self.prefixes[slot].get(&(value >> (48 - bits))) {
+                return Some(&self.vendors[*index]);
+            }
+        }
+        None
+    }
+
+    fn load_csv(&mut self, content: &str) {
+        // Registry,Assignment,Organization Name,Organization Address
+        for line in content.lines() {
+            let fields = split_csv(line);
+            if fields.len() >= 3 && fields[0].starts_with("MA-") {
+                self.insert(&fields[1], &fields[2]);
+            }
+        }
+    }
+
+    fn load_text(&mut self, content: &str) {
+        // IEEE text repeats each prefix as "002272     (base 16)", which the nmap form would misread
+        let ieee = content.contains("(hex)");
+        for line in content.lines() {
+            // hwdata/IEEE text: "00-22-72   (hex)\t\tAmerican Micro-Fuel Device Corp."
+            if let Some((prefix, vendor)) = line.split_once("(hex)") {
+                self.insert(prefix, vendor);
+                continue;
+            }
+            // nmap-mac-prefixes: "002272 American Micro-Fuel Device"
+            if ieee || line.starts_with('#') {
+                continue;
+            }
+            if let Some((prefix, vendor)) = line.split_once(char::is_whitespace) {
+                if prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+                    self.insert(prefix, vendor);
+                }
+            }
+        }
+    }
+}
+
+fn mac_to_u64(mac: &str) -> Option<u64> {
+    let hex: String = mac.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    if hex.len() != 12 {
+        return None;
+    }
+    u64::from_str_radix(&hex, 16).ok()
+}
+
+/// Split one CSV record, honoring double-quoted fields
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+/// Whether the locally administered bit is set, as with randomized/private MACs
+pub fn is_locally_administered(mac: &str) -> bool {
+    mac_to_u64(mac)
+        .map(|value| (value >> 40) & 0x02 != 0)
+        .unwrap_or(false)
+}
+
+/// The vendor database, loaded once from the user registry, system files or the built-in table
+pub fn database() -> &'static OuiDatabase {
+    static DATABASE: OnceLock<OuiDatabase> = OnceLock::new();
+    DATABASE.get_or_init(load_database)
+}
+
+fn load_database() -> OuiDatabase {
+    if let Ok(path) = state::data_dir().map(|dir| dir.join(REGISTRY_FILE)) {
+        if let Ok(file) = std::fs::File::open(&path) {
+            let mut content = String::new();
+            if GzDecoder::new(file).read_to_string(&mut content).is_ok() {
+                let mut db = OuiDatabase::new(&path.to_string_lossy());
+                db.load_csv(&content);
+                if !db.is_empty() {
+                    return db;
+                }
+            }
+        }
+    }
+
+    let mut db = OuiDatabase::new("system registry files");
+    for path in SYSTEM_REGISTRY_PATHS {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            if path.ends_with(".csv") {
+                db.load_csv(&content);
+            } else {
+                db.load_text(&content);
+            }
+        }
+    }
+    if !db.is_empty() {
+        return db;
+    }
+
+    let mut db = OuiDatabase::new("built-in table");
+    for (prefix, vendor) in BUILTIN_OUIS {
+        db.insert(prefix, vendor);
+    }
+    db
+}
+
+/// Vendor name for a MAC, noting randomized/locally administered addresses
+pub fn vendor_for_mac(mac: &str) -> String {
+    if let Some(vendor) = database().lookup(mac) {
+        return vendor.to_string();
+    }
+    if is_locally_administered(mac) {
+        return "Locally administered (randomized)".to_string();
+    }
+    "Unknown".to_string()
+}
+
+/// Download the IEEE registries and store them compressed in the data directory
+pub async fn update_registry() -> Result<()> {
+    let mut combined = String::new();
+
+    for url in REGISTRY_URLS {
+        println!("⬇️  Fetching {}", url);
+        let output = Command::new("curl")
+            .args(["-fsSL", "--max-time", "120", url])
+            .output()
+            .context("Failed to execute curl")?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to download {}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        combined.push_str(&String::from_utf8_lossy(&output.stdout));
+        combined.push('\n');
+    }
+
+    let mut db = OuiDatabase::new("download");
+    db.load_csv(&combined);
+    if db.is_empty() {
+        return Err(anyhow::anyhow!("Downloaded registry contained no assignments"));
+    }
+
+    let path = state::data_dir()?.join(REGISTRY_FILE);
+    let file = std::fs::File::create(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut encoder = GzEncoder::new(file, Compression::best());
+    encoder.write_all(combined.as_bytes())?;
+    encoder.finish()?;
+
+    println!("✅ Stored {} assignments in {}", db.len(), path.display());
+    Ok(())
+}
+
+/// Print the vendor for a MAC address
+pub async fn show_lookup(mac: &str) -> Result<()> {
+    let db = database();
+    println!("MAC: {}", mac);
+    println!("Vendor: {}", vendor_for_mac(mac));
+    println!("Locally administered: {}", if is_locally_administered(mac) { "yes" } else { "no" });
+    println!("Database: {} ({} assignments)", db.source, db.len());
+    Ok(())
+}
@@ -0,0 +1,323 @@
+use anyhow::{Context, Result};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::OnceLock;
+
+/// First UID handed out to regular login users
+const FIRST_USER_UID: u32 = 1000;
+
+/// Directories that legitimate long-running binaries are never executed from
+const SUSPICIOUS_EXE_DIRS: &[&str] = &["/tmp/", "/dev/shm/", "/var/tmp/", "/run/shm/"];
+
+/// Interactive shells that should not hold sockets to remote hosts
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh", "mksh", "ash", "csh", "tcsh", "fish", "busybox"];
+
+/// Stratum ports commonly used by cryptocurrency mining pools
+const MINER_POOL_PORTS: &[u16] = &[3333, 3334, 3357, 4444, 5555, 5556, 5588, 6666, 7777, 8899, 9999, 14433, 14444, 45560, 45700];
+
+/// Where the kernel picks ephemeral ports from, and its default range
+const LOCAL_PORT_RANGE_PATH: &str = "/proc/sys/net/ipv4/ip_local_port_range";
+const DEFAULT_LOCAL_PORT_RANGE: (u16, u16) = (32768, 60999);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcSocket {
+    pub protocol: String,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: String,
+    pub uid: u32,
+    pub inode: u64,
+}
+
+impl ProcSocket {
+    /// A TCP listener, or a UDP socket on a fixed port outside the ephemeral range;
+    /// UDP sockets on ephemeral ports are clients such as browsers, resolvers and VoIP
+    pub fn is_service(&self) -> bool {
+        match (self.protocol.as_str(), self.state.as_str()) {
+            ("tcp", "LISTEN") => true,
+            ("udp", "UNCONN") => {
+                let (first, last) = local_port_range();
+                !(first..=last).contains(&self.local.port())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The ephemeral port range, read once
+fn local_port_range() -> (u16, u16) {
+    static RANGE: OnceLock<(u16, u16)> = OnceLock::new();
+    *RANGE.get_or_init(|| {
+        let content = std::fs::read_to_string(LOCAL_PORT_RANGE_PATH).unwrap_or_default();
+        let ports: Vec<u16> = content.split_whitespace().filter_map(|p| p.parse().ok()).collect();
+        match ports.as_slice() {
+            [first, last] => (*first, *last),
+            _ => DEFAULT_LOCAL_PORT_RANGE,
+        }
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub exe: Option<String>,
+    pub uid: u32,
+    pub sockets: Vec<ProcSocket>,
+    /// Whether stdin or stdout is a socket, as with `sh -i >& /dev/tcp/...`
+    pub stdio_is_socket: bool,
+}
+
+/// Read the kernel socket tables from /proc/net
+pub fn read_proc_sockets() -> Result<Vec<ProcSocket>> {
+    let mut sockets = Vec::new();
+
+    for protocol in ["tcp", "tcp6", "udp", "udp6"] {
+        let path = format!("/proc/net/{}", protocol);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+
+        for line in content.lines().skip(1) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 10 {
+                continue;
+            }
+
+            let (Some(local), Some(remote)) = (parse_proc_addr(parts[1]), parse_proc_addr(parts[2])) else {
+                continue;
+            };
+            let state = match parts[3] {
+                "01" => "ESTABLISHED",
+                "02" => "SYN_SENT",
+                "03" => "SYN_RECV",
+                "06" => "TIME_WAIT",
+                // Unconnected UDP sockets report TCP_CLOSE
+                "07" if protocol.starts_with("udp") => "UNCONN",
+                "07" => "CLOSE",
+                "08" => "CLOSE_WAIT",
+                "0A" => "LISTEN",
+                _ => "OTHER",
+            };
+
+            sockets.push(ProcSocket {
+                protocol: protocol.trim_end_matches('6').to_string(),
+                local,
+                remote,
+                state: state.to_string(),
+                uid: parts[7].parse().unwrap_or(0),
+                inode: parts[9].parse().unwrap_or(0),
+            });
+        }
+    }
+
+    Ok(sockets)
+}
+
+/// Parse "0100007F:0035" or a 32-digit IPv6 address in kernel byte order
+fn parse_proc_addr(field: &str) -> Option<SocketAddr> {
+    let (addr_hex, port_hex) = field.split_once(':')?;
+    let port = u16::from_str_radix(port_hex, 16).ok()?;
+
+    let ip = match addr_hex.len() {
+        8 => {
+            let raw = u32::from_str_radix(addr_hex, 16).ok()?;
+            IpAddr::V4(Ipv4Addr::from(raw.to_le_bytes()))
+        }
+        32 => {
+            let mut octets = [0u8; 16];
+            for word in 0..4 {
+                let raw = u32::from_str_radix(&addr_hex[word * 8..word * 8 + 8], 16).ok()?;
+                octets[word * 4..word * 4 + 4].copy_from_slice(&raw.to_le_bytes());
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+
+    Some(SocketAddr::new(ip, port))
+}
+
+/// Enumerate processes that own at least one socket
+pub fn get_network_processes() -> Result<Vec<ProcessInfo>> {
+    let sockets_by_inode: HashMap<u64, ProcSocket> = read_proc_sockets()?
+        .into_iter()
+        .map(|s| (s.inode, s))
+        .collect();
+
+    let mut processes = Vec::new();
+    let entries = std::fs::read_dir("/proc").context("Failed to read /proc")?;
+
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let proc_dir = entry.path();
+
+        // fd directories of other users' processes are unreadable without root
+        let Ok(fds) = std::fs::read_dir(proc_dir.join("fd")) else {
+            continue;
+        };
+
+        let mut sockets = Vec::new();
+        let mut stdio_is_socket = false;
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|s| s.strip_suffix(']'))
+                .and_then(|s| s.parse::<u64>().ok())
+            else {
+                continue;
+            };
+
+            if let Some(socket) = sockets_by_inode.get(&inode) {
+                let fd_num = fd.file_name().to_string_lossy().parse::<u32>().unwrap_or(u32::MAX);
+                if fd_num <= 1 {
+                    stdio_is_socket = true;
+                }
+                sockets.push(socket.clone());
+            }
+        }
+
+        if sockets.is_empty() {
+            continue;
+        }
+
+        let name = std::fs::read_to_string(proc_dir.join("comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        let exe = std::fs::read_link(proc_dir.join("exe"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+        let uid = std::fs::read_to_string(proc_dir.join("status"))
+            .ok()
+            .and_then(|status| {
+                status
+                    .lines()
+                    .find(|l| l.starts_with("Uid:"))
+                    .and_then(|l| l.split_whitespace().nth(1))
+                    .and_then(|u| u.parse().ok())
+            })
+            .unwrap_or(0);
+
+        processes.push(ProcessInfo {
+            pid,
+            name,
+            exe,
+            uid,
+            sockets,
+            stdio_is_socket,
+        });
+    }
+
+    Ok(processes)
+}
+
+/// Flag processes with network activity that look like compromise indicators
+pub fn check_suspicious_processes() -> Result<Vec<SecurityAlert>> {
+    let processes = get_network_processes()?;
+    let mut alerts = Vec::new();
+
+    for process in &processes {
+        let describe = || {
+            format!(
+                "pid {} ({}) uid {} exe {} sockets: {}",
+                process.pid,
+                process.name,
+                process.uid,
+                process.exe.as_deref().unwrap_or("unknown"),
+                process
+                    .sockets
+                    .iter()
+                    .map(|s| format!("{} {} -> {} {}", s.protocol, s.local, s.remote, s.state))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )
+        };
+        // The subject names the process and what was found, since the socket list in the details keeps changing
+        let mut alert = |severity: AlertSeverity, subject: String, message: String| {
+            alerts.push(SecurityAlert {
+                timestamp: Utc::now(),
+                severity,
+                category: AlertCategory::SuspiciousProcess,
+                message,
+                details: Some(describe()),
+                subject: Some(format!("pid {} ({}) {}", process.pid, process.name, subject)),
+            });
+        };
+
+        // Binaries deleted after start, fileless memfd payloads, or dropped into temp dirs
+        if let Some(exe) = &process.exe {
+            if exe.ends_with(" (deleted)") {
+                alert(
+                    AlertSeverity::High,
+                    "deleted binary".to_string(),
+                    format!("Process {} ({}) runs from a deleted binary and has network activity", process.pid, process.name),
+                );
+            } else if exe.starts_with("/memfd:") {
+                alert(
+                    AlertSeverity::Critical,
+                    "memfd binary".to_string(),
+                    format!("Process {} ({}) runs a fileless memfd binary with network activity", process.pid, process.name),
+                );
+            } else if SUSPICIOUS_EXE_DIRS.iter().any(|dir| exe.starts_with(dir)) {
+                alert(
+                    AlertSeverity::High,
+                    format!("binary in {}", exe),
+                    format!("Process {} ({}) runs from {} and has network activity", process.pid, process.name, exe),
+                );
+            }
+        }
+
+        let remote_tcp: Vec<&ProcSocket> = process
+            .sockets
+            .iter()
+            .filter(|s| s.protocol == "tcp" && s.state == "ESTABLISHED" && !s.remote.ip().is_loopback())
+            .collect();
+
+        // Shells talking to remote hosts are the classic reverse shell pattern
+        if SHELLS.contains(&process.name.as_str()) && !remote_tcp.is_empty() {
+            let remote = remote_tcp[0].remote;
+            let qualifier = if process.stdio_is_socket { " with stdio bound to the socket" } else { "" };
+            alert(
+                AlertSeverity::Critical,
+                "reverse shell".to_string(),
+                format!("Possible reverse shell: {} (pid {}) connected to {}{}", process.name, process.pid, remote, qualifier),
+            );
+        }
+
+        // Regular users should not be exposing services on external addresses
+        if process.uid >= FIRST_USER_UID && process.uid != 65534 {
+            for socket in process.sockets.iter().filter(|s| s.is_service() && !s.local.ip().is_loopback()) {
+                alert(
+                    AlertSeverity::Medium,
+                    format!("listener {} {}", socket.protocol, socket.local),
+                    format!(
+                        "Unexpected listener: {} (pid {}, uid {}) listening on {} {}",
+                        process.name, process.pid, process.uid, socket.protocol, socket.local
+                    ),
+                );
+            }
+        }
+
+        for socket in remote_tcp.iter().filter(|s| MINER_POOL_PORTS.contains(&s.remote.port())) {
+            alert(
+                AlertSeverity::High,
+                format!("mining pool {}", socket.remote),
+                format!(
+                    "{} (pid {}) connected to {} on a known mining pool port",
+                    process.name, process.pid, socket.remote
+                ),
+            );
+        }
+    }
+
+    Ok(alerts)
+}
@@ -0,0 +1,320 @@
+use anyhow::{anyhow, Context, Result};
+use crate::config;
+use crate::history::format_duration;
+use crate::logs::{self, LogLine, LogSource};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert, SecurityScan};
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Longest window a rule may look back over
+pub const MAX_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RuleFile {
+    rule: Vec<Rule>,
+}
+
+/// A user-defined detection: log lines matching `pattern`, counted over `window`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    #[serde(deserialize_with = "source")]
+    pub source: LogSource,
+    /// Regex whose named captures can group matches and fill in the message
+    #[serde(deserialize_with = "pattern")]
+    pub pattern: Regex,
+    /// Named capture counted separately per value, e.g. the source IP
+    pub group_by: Option<String>,
+    /// Matches within the window needed to raise an alert
+    #[serde(default = "default_threshold")]
+    pub threshold: usize,
+    #[serde(default = "default_window", deserialize_with = "config::duration")]
+    pub window: Duration,
+    #[serde(deserialize_with = "config::severity")]
+    pub severity: AlertSeverity,
+    #[serde(deserialize_with = "config::category")]
+    pub category: AlertCategory,
+    /// Template with {rule}, {count}, {window}, {group} and named-capture placeholders
+    pub message: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// What one rule found in a scan
+#[derive(Debug, Clone, Default)]
+pub struct Evaluation {
+    /// Matching lines within the window
+    pub matches: usize,
+    pub alerts: Vec<SecurityAlert>,
+}
+
+/// Matches of one group within the window
+#[derive(Default)]
+struct Hits {
+    count: usize,
+    last_seen: Option<DateTime<Utc>>,
+    /// Named captures of the latest match
+    captures: BTreeMap<String, String>,
+}
+
+fn default_threshold() -> usize {
+    1
+}
+
+fn default_window() -> Duration {
+    Duration::minutes(10)
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn source<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LogSource, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+}
+
+fn pattern<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(D::Error::custom)
+}
+
+impl Rule {
+    fn validate(&self) -> Result<()> {
+        if self.threshold == 0 {
+            return Err(anyhow!("threshold must be at least 1"));
+        }
+        if self.window <= Duration::zero() {
+            return Err(anyhow!("window must be positive"));
+        }
+        if self.window > Duration::days(MAX_WINDOW_DAYS) {
+            return Err(anyhow!("window must be at most {} days", MAX_WINDOW_DAYS));
+        }
+        if let Some(group_by) = &self.group_by {
+            if !self.pattern.capture_names().flatten().any(|name| name == group_by) {
+                return Err(anyhow!("group_by '{}' is not a named capture in the pattern", group_by));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count matches within the window and raise an alert for every group at or over the threshold
+    pub fn evaluate(&self, lines: &[LogLine], now: DateTime<Utc>) -> Evaluation {
+        let since = now - self.window;
+        let mut groups: BTreeMap<Option<String>, Hits> = BTreeMap::new();
+        let mut matches = 0;
+
+        for line in lines.iter().filter(|line| line.timestamp >= since) {
+            let Some(captures) = self.pattern.captures(&line.text) else {
+                continue;
+            };
+            // A line without the grouping capture cannot be attributed to a group
+            let group = match &self.group_by {
+                Some(name) => match captures.name(name) {
+                    Some(value) => Some(value.as_str().to_string()),
+                    None => continue,
+                },
+                None => None,
+            };
+            matches += 1;
+
+            let hits = groups.entry(group).or_default();
+            hits.count += 1;
+            hits.last_seen = Some(line.timestamp);
+            hits.captures = self
+                .pattern
+                .capture_names()
+                .flatten()
+                .filter_map(|name| captures.name(name).map(|value| (name.to_string(), value.as_str().to_string())))
+                .collect();
+        }
+
+        let alerts = groups
+            .into_iter()
+            .filter(|(_, hits)| hits.count >= self.threshold)
+            .map(|(group, hits)| self.alert(group.as_deref(), hits, now))
+            .collect();
+        Evaluation { matches, alerts }
+    }
+
+    fn alert(&self, group: Option<&str>, hits: Hits, now: DateTime<Utc>) -> SecurityAlert {
+        let mut values = hits.captures;
+        values.insert("rule".to_string(), self.name.clone());
+        values.insert("count".to_string(), hits.count.to_string());
+        values.insert("window".to_string(), format_duration(self.window.num_seconds()));
+        values.insert("group".to_string(), group.unwrap_or("").to_string());
+
+        let template = match (&self.message, group) {
+            (Some(message), _) => message.as_str(),
+            (None, Some(_)) => "Rule '{rule}' triggered for {group}",
+            (None, None) => "Rule '{rule}' triggered",
+        };
+
+        let mut details = format!(
+            "rule {}: {} or more match(es) within {} in {}",
+            self.name,
+            self.threshold,
+            format_duration(self.window.num_seconds()),
+            self.source
+        );
+        if let (Some(name), Some(value)) = (&self.group_by, group) {
+            details.push_str(&format!(", {} = {}", name, value));
+        }
+
+        SecurityAlert {
+            timestamp: hits.last_seen.unwrap_or(now),
+            severity: self.severity.clone(),
+            category: self.category.clone(),
+            message: render(template, &values),
+            details: Some(details),
+            // The message may show counts and captures of the latest match; the alert is the rule and group
+            subject: Some(match group {
+                Some(group) => format!("rule {} {}", self.name, group),
+                None => format!("rule {}", self.name),
+            }),
+        }
+    }
+}
+
+/// Replace {name} placeholders; unknown names are left as they are
+fn render(template: &str, values: &BTreeMap<String, String>) -> String {
+    let placeholder = Regex::new(r"\{(\w+)\}").expect("placeholder regex is valid");
+    placeholder
+        .replace_all(template, |caps: &regex::Captures| {
+            values.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Load the rules file; having none means no rules
+pub fn load_rules() -> Result<Vec<Rule>> {
+    let path = config::rules_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: RuleFile = toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let mut names = HashSet::new();
+    for rule in &file.rule {
+        if !names.insert(rule.name.as_str()) {
+            return Err(anyhow!("Duplicate rule '{}' in {}", rule.name, path.display()));
+        }
+        rule.validate()
+            .with_context(|| format!("Invalid rule '{}' in {}", rule.name, path.display()))?;
+    }
+    Ok(file.rule)
+}
+
+/// Evaluate every enabled rule, reading each log source once for the longest window on it
+pub fn evaluate_rules(rules: &[Rule], now: DateTime<Utc>) -> Vec<Result<Evaluation, String>> {
+    let mut windows: HashMap<&LogSource, Duration> = HashMap::new();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let window = windows.entry(&rule.source).or_insert(rule.window);
+        *window = (*window).max(rule.window);
+    }
+    let logs: HashMap<&LogSource, Result<Vec<LogLine>, String>> = windows
+        .into_iter()
+        .map(|(source, window)| (source, logs::read(source, now - window).map_err(|e| e.to_string())))
+        .collect();
+
+    rules
+        .iter()
+        .map(|rule| {
+            if !rule.enabled {
+                return Ok(Evaluation::default());
+            }
+            match &logs[&rule.source] {
+                Ok(lines) => Ok(rule.evaluate(lines, now)),
+                Err(e) => Err(e.clone()),
+            }
+        })
+        .collect()
+}
+
+/// Alerts raised by the user-defined rules, and the rules whose log source could not be read
+pub fn check_rules() -> Result<SecurityScan> {
+    let rules = load_rules()?;
+    let mut scan = SecurityScan::default();
+    for (rule, result) in rules.iter().zip(evaluate_rules(&rules, Utc::now())) {
+        match result {
+            Ok(evaluation) => scan.alerts.extend(evaluation.alerts),
+            Err(e) => scan.errors.push(format!("rule '{}': cannot read {}: {}", rule.name, rule.source, e)),
+        }
+    }
+    Ok(scan)
+}
+
+/// List the rules in the rules file
+pub async fn show_rules() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("                 📜  NetNinja Detection Rules  📜          ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let path = config::rules_path()?;
+    let rules = load_rules()?;
+    if rules.is_empty() {
+        println!("No rules defined in {}\n", path.display());
+        return Ok(());
+    }
+
+    println!("Rules file: {}\n", path.display());
+    for rule in &rules {
+        let state = if rule.enabled { "✅" } else { "⏸️ " };
+        println!("{} {} [{}, {}]", state, rule.name, rule.severity, rule.category.label());
+        println!("   Source:    {}", rule.source);
+        println!("   Pattern:   {}", rule.pattern);
+        let group = rule.group_by.as_deref().map(|g| format!(" per {}", g)).unwrap_or_default();
+        println!(
+            "   Threshold: {} match(es) within {}{}",
+            rule.threshold,
+            format_duration(rule.window.num_seconds()),
+            group
+        );
+        println!();
+    }
+    Ok(())
+}
+
+/// Evaluate the rules now and show what each one found
+pub async fn run_rules() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("               🔍  NetNinja Rule Evaluation  🔍            ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let rules = load_rules()?;
+    if rules.is_empty() {
+        println!("No rules defined in {}\n", config::rules_path()?.display());
+        return Ok(());
+    }
+
+    let results = tokio::task::spawn_blocking({
+        let rules = rules.clone();
+        move || evaluate_rules(&rules, Utc::now())
+    })
+    .await?;
+
+    for (rule, result) in rules.iter().zip(results) {
+        match result {
+            _ if !rule.enabled => println!("⏸️  {}: disabled", rule.name),
+            Err(e) => println!("❌ {}: cannot read {}: {}", rule.name, rule.source, e),
+            Ok(evaluation) if evaluation.alerts.is_empty() => println!(
+                "✅ {}: {} match(es), below the threshold of {}",
+                rule.name, evaluation.matches, rule.threshold
+            ),
+            Ok(evaluation) => {
+                println!("🚨 {}: {} match(es), {} alert(s)", rule.name, evaluation.matches, evaluation.alerts.len());
+                for alert in &evaluation.alerts {
+                    println!("   [{}] {}", alert.severity, alert.message);
+                }
+            }
+        }
+    }
+    println!();
+    Ok(())
+}
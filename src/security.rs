@@ -1,4 +1,6 @@
 use anyhow::{Context, Result};
+use crate::{arpwatch, changes, exposure, firewall, intel, inventory, process, rules, sigma};
+use std::net::{IpAddr, Ipv4Addr};
 use std::process::Command;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -10,6 +12,10 @@ pub struct SecurityAlert {
     pub category: AlertCategory,
     pub message: String,
     pub details: Option<String>,
+    /// What the alert is about, such as a source IP, a rule and group, or a port; alerts with the
+    /// same category and subject are the same alert however their message and details change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +27,14 @@ pub enum AlertSeverity {
     Info,
 }
 
+/// What a security scan found, and the detectors that could not run completely
+#[derive(Debug, Clone, Default)]
+pub struct SecurityScan {
+    pub alerts: Vec<SecurityAlert>,
+    /// Problems such as a broken rules file or an unreadable log source
+    pub errors: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlertCategory {
     FailedLogin,
@@ -29,6 +43,11 @@ pub enum AlertCategory {
     FirewallBlock,
     SuspiciousProcess,
     SystemChange,
+    ExposedService,
+    ArpSpoofing,
+    RogueDhcp,
+    NewDevice,
+    ThreatIntel,
 }
 
 impl std::fmt::Display for AlertSeverity {
@@ -43,9 +62,132 @@ impl std::fmt::Display for AlertSeverity {
     }
 }
 
+impl SecurityAlert {
+    /// Stable identity of the event: the category and subject, or for one-off events without a
+    /// subject the category, message and details, hash the same whenever the scan that found them ran
+    pub fn fingerprint(&self) -> String {
+        // FNV-1a, which unlike the std hasher is stable across Rust releases
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let fields = match &self.subject {
+            Some(subject) => vec![self.category.label(), subject.as_str()],
+            None => vec![self.category.label(), &self.message, self.details.as_deref().unwrap_or("")],
+        };
+        for field in fields {
+            for byte in field.bytes().chain([0]) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+    
+    /// Short id used to refer to the alert on the command line
+    pub fn id(&self) -> String {
+        self.fingerprint()[..8].to_string()
+    }
+    
+    /// IP addresses mentioned in the message or details, e.g. the source of failed logins
+    pub fn source_ips(&self) -> Vec<IpAddr> {
+        let text = format!("{} {}", self.message, self.details.as_deref().unwrap_or(""));
+        let mut ips: Vec<IpAddr> = Vec::new();
+        for token in text.split(|c: char| !(c.is_ascii_hexdigit() || c == '.' || c == ':')) {
+            let token = token.trim_matches(|c| c == '.' || c == ':');
+            // 192.0.2.7:22 carries a port; IPv6 addresses are only recognized bare
+            let ip = token
+                .parse::<IpAddr>()
+                .ok()
+                .or_else(|| token.rsplit_once(':').and_then(|(host, _)| host.parse::<Ipv4Addr>().ok().map(IpAddr::V4)));
+            if let Some(ip) = ip.filter(|ip| !ips.contains(ip)) {
+                ips.push(ip);
+            }
+        }
+        ips
+    }
+}
+
+impl AlertSeverity {
+    /// Stable lowercase name for metric labels
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertSeverity::Critical => "critical",
+            AlertSeverity::High => "high",
+            AlertSeverity::Medium => "medium",
+            AlertSeverity::Low => "low",
+            AlertSeverity::Info => "info",
+        }
+    }
+    
+    /// Position from Info (0) to Critical (4), for severity thresholds
+    pub fn rank(&self) -> u8 {
+        match self {
+            AlertSeverity::Critical => 4,
+            AlertSeverity::High => 3,
+            AlertSeverity::Medium => 2,
+            AlertSeverity::Low => 1,
+            AlertSeverity::Info => 0,
+        }
+    }
+}
+
+impl std::str::FromStr for AlertSeverity {
+    type Err = String;
+    
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "critical" => Ok(AlertSeverity::Critical),
+            "high" => Ok(AlertSeverity::High),
+            "medium" => Ok(AlertSeverity::Medium),
+            "low" => Ok(AlertSeverity::Low),
+            "info" => Ok(AlertSeverity::Info),
+            _ => Err(format!("unknown severity '{}' (use critical, high, medium, low or info)", value)),
+        }
+    }
+}
+
+impl AlertCategory {
+    /// Stable snake_case name for metric labels
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertCategory::FailedLogin => "failed_login",
+            AlertCategory::PortScan => "port_scan",
+            AlertCategory::UnusualTraffic => "unusual_traffic",
+            AlertCategory::FirewallBlock => "firewall_block",
+            AlertCategory::SuspiciousProcess => "suspicious_process",
+            AlertCategory::SystemChange => "system_change",
+            AlertCategory::ExposedService => "exposed_service",
+            AlertCategory::ArpSpoofing => "arp_spoofing",
+            AlertCategory::RogueDhcp => "rogue_dhcp",
+            AlertCategory::NewDevice => "new_device",
+            AlertCategory::ThreatIntel => "threat_intel",
+        }
+    }
+}
+
+impl std::str::FromStr for AlertCategory {
+    type Err = String;
+    
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "failed_login" => Ok(AlertCategory::FailedLogin),
+            "port_scan" => Ok(AlertCategory::PortScan),
+            "unusual_traffic" => Ok(AlertCategory::UnusualTraffic),
+            "firewall_block" => Ok(AlertCategory::FirewallBlock),
+            "suspicious_process" => Ok(AlertCategory::SuspiciousProcess),
+            "system_change" => Ok(AlertCategory::SystemChange),
+            "exposed_service" => Ok(AlertCategory::ExposedService),
+            "arp_spoofing" => Ok(AlertCategory::ArpSpoofing),
+            "rogue_dhcp" => Ok(AlertCategory::RogueDhcp),
+            "new_device" => Ok(AlertCategory::NewDevice),
+            "threat_intel" => Ok(AlertCategory::ThreatIntel),
+            _ => Err(format!("unknown alert category '{}'", value)),
+        }
+    }
+}
+
 /// Monitor system logs for security events
-pub fn scan_security_logs() -> Result<Vec<SecurityAlert>> {
+pub fn scan_security_logs() -> Result<SecurityScan> {
     let mut alerts = Vec::new();
+    let mut errors = Vec::new();
     
     // Check auth logs for failed login attempts
     if let Ok(failed_logins) = check_failed_logins() {
@@ -62,7 +204,48 @@ pub fn scan_security_logs() -> Result<Vec<SecurityAlert>> {
         alerts.extend(firewall_alerts);
     }
     
-    Ok(alerts)
+    // Check for processes with suspicious network activity
+    if let Ok(process_alerts) = process::check_suspicious_processes() {
+        alerts.extend(process_alerts);
+    }
+    
+    // Check the neighbor table for ARP spoofing and IP conflicts
+    if let Ok(arp_alerts) = arpwatch::check_arp_spoofing() {
+        alerts.extend(arp_alerts);
+    }
+    
+    // Check for devices that have never been seen on the network before
+    if let Ok(device_alerts) = inventory::check_new_devices() {
+        alerts.extend(device_alerts);
+    }
+    
+    // Check for changes to network-relevant system state since the last run
+    if let Ok(change_alerts) = changes::check_system_changes() {
+        alerts.extend(change_alerts);
+    }
+    
+    // Check for services that became reachable from untrusted networks
+    if let Ok(exposure_alerts) = check_service_exposure() {
+        alerts.extend(exposure_alerts);
+    }
+    
+    // Check connections, firewall and login sources, and neighbors against threat-intel blocklists
+    if let Ok(intel_alerts) = intel::check_threat_intel() {
+        alerts.extend(intel_alerts);
+    }
+    
+    // Check user-defined detection rules and Sigma rules; unlike the built-in checks, their failures are reported
+    for scan in [rules::check_rules(), sigma::check_sigma_rules()] {
+        match scan {
+            Ok(scan) => {
+                alerts.extend(scan.alerts);
+                errors.extend(scan.errors);
+            }
+            Err(e) => errors.push(format!("{:#}", e)),
+        }
+    }
+    
+    Ok(SecurityScan { alerts, errors })
 }
 
 fn check_failed_logins() -> Result<Vec<SecurityAlert>> {
@@ -83,6 +266,7 @@ fn check_failed_logins() -> Result<Vec<SecurityAlert>> {
                     category: AlertCategory::FailedLogin,
                     message: "Failed SSH login attempt detected".to_string(),
                     details: Some(line.to_string()),
+                    subject: line.split_once(" from ").and_then(|(_, rest)| rest.split_whitespace().next()).map(|ip| ip.to_string()),
                 });
             }
         }
@@ -129,6 +313,7 @@ fn check_network_connections() -> Result<Vec<SecurityAlert>> {
                 category: AlertCategory::UnusualTraffic,
                 message: format!("High connection count from {}: {} connections", ip, count),
                 details: Some("Possible port scan or DDoS attempt".to_string()),
+                subject: Some(ip),
             });
         }
     }
@@ -154,6 +339,7 @@ fn check_firewall_logs() -> Result<Vec<SecurityAlert>> {
                     category: AlertCategory::FirewallBlock,
                     message: "Firewall block detected".to_string(),
                     details: Some(line.to_string()),
+                    subject: line.split_whitespace().find_map(|token| token.strip_prefix("SRC=")).map(|ip| ip.to_string()),
                 });
             }
         }
@@ -162,6 +348,11 @@ fn check_firewall_logs() -> Result<Vec<SecurityAlert>> {
     Ok(alerts)
 }
 
+fn check_service_exposure() -> Result<Vec<SecurityAlert>> {
+    let exposures = exposure::analyze()?;
+    exposure::check_new_exposures(&exposures)
+}
+
 /// Check if firewall is active
 pub fn check_firewall_status() -> Result<bool> {
     let output = Command::new("ufw")
@@ -170,24 +361,22 @@ pub fn check_firewall_status() -> Result<bool> {
     
     if let Ok(output) = output {
         let output_str = String::from_utf8_lossy(&output.stdout);
-        return Ok(output_str.contains("Status: active"));
+        if output_str.contains("Status: active") {
+            return Ok(true);
+        }
     }
     
-    // Try iptables as fallback
-    let output = Command::new("iptables")
-        .args(&["-L", "-n"])
-        .output();
-    
-    if let Ok(output) = output {
-        return Ok(output.status.success());
+    // Fall back to the nftables/iptables ruleset; an empty accept-everything
+    // ruleset does not count as an active firewall
+    match firewall::read_ruleset() {
+        Ok(ruleset) => Ok(ruleset.is_filtering()),
+        Err(_) => Ok(false),
     }
-    
-    Ok(false)
 }
 
 /// Get summary of security status
 pub fn get_security_summary() -> Result<String> {
-    let alerts = scan_security_logs()?;
+    let alerts = scan_security_logs()?.alerts;
     let firewall_active = check_firewall_status()?;
     
     let critical_count = alerts.iter().filter(|a| matches!(a.severity, AlertSeverity::Critical)).count();
@@ -0,0 +1,365 @@
+use anyhow::{Context, Result};
+use crate::dnswire::{self, RecordData};
+use crate::network::{self, NetworkPeer};
+use nix::sys::socket::{self, sockopt, AddressFamily, SockFlag, SockType, SockaddrIn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+
+const MDNS_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+const SSDP_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 255, 250);
+const SSDP_PORT: u16 = 1900;
+
+/// DNS-SD meta-query that enumerates every advertised service type
+const SERVICE_ENUMERATION: &str = "_services._dns-sd._udp.local";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvertisedService {
+    /// "mdns" or "ssdp"
+    pub protocol: String,
+    /// DNS-SD instance name or SSDP USN
+    pub instance: String,
+    /// e.g. "_ipp._tcp" or "urn:schemas-upnp-org:device:MediaRenderer:1"
+    pub service_type: String,
+    pub hostname: Option<String>,
+    pub ip: Option<IpAddr>,
+    pub port: Option<u16>,
+    /// TXT record entries, or SERVER/LOCATION headers for SSDP
+    pub txt: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceHost {
+    pub ip: Option<IpAddr>,
+    pub hostname: Option<String>,
+    pub peer: Option<NetworkPeer>,
+    pub services: Vec<AdvertisedService>,
+}
+
+#[derive(Debug, Default)]
+struct MdnsInstance {
+    service_type: String,
+    target: Option<String>,
+    port: Option<u16>,
+    txt: Vec<String>,
+    /// Address the SRV answer came from, used when the target has no A/AAAA record
+    responder: Option<IpAddr>,
+}
+
+/// Accumulated DNS-SD answers and the queries already sent
+#[derive(Debug, Default)]
+struct MdnsBrowser {
+    service_types: BTreeSet<String>,
+    instances: BTreeMap<String, MdnsInstance>,
+    addresses: BTreeMap<String, BTreeSet<IpAddr>>,
+    queried: BTreeSet<String>,
+}
+
+impl MdnsBrowser {
+    fn absorb(&mut self, message: &dnswire::DnsMessage, responder: IpAddr) {
+        for record in &message.records {
+            match &record.data {
+                RecordData::Ptr(target) if record.name == SERVICE_ENUMERATION => {
+                    self.service_types.insert(target.clone());
+                }
+                RecordData::Ptr(target) if target.ends_with(&format!(".{}", record.name)) => {
+                    self.service_types.insert(record.name.clone());
+                    self.instances.entry(target.clone()).or_default().service_type = record.name.clone();
+                }
+                RecordData::Srv { port, target } => {
+                    let instance = self.instances.entry(record.name.clone()).or_default();
+                    instance.target = Some(target.clone());
+                    instance.port = Some(*port);
+                    instance.responder = Some(responder);
+                    if instance.service_type.is_empty() {
+                        instance.service_type = service_type_of(&record.name);
+                    }
+                }
+                RecordData::Txt(entries) if record.name.contains("._") => {
+                    let instance = self.instances.entry(record.name.clone()).or_default();
+                    instance.txt = entries.clone();
+                    if instance.service_type.is_empty() {
+                        instance.service_type = service_type_of(&record.name);
+                    }
+                }
+                RecordData::A(addr) => {
+                    self.addresses.entry(record.name.clone()).or_default().insert(IpAddr::V4(*addr));
+                }
+                RecordData::Aaaa(addr) => {
+                    self.addresses.entry(record.name.clone()).or_default().insert(IpAddr::V6(*addr));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Follow-up questions for types and instances not yet resolved
+    fn pending_queries(&mut self) -> Vec<(String, u16)> {
+        let mut questions = Vec::new();
+        for service_type in &self.service_types {
+            if self.queried.insert(format!("ptr {}", service_type)) {
+                questions.push((service_type.clone(), dnswire::TYPE_PTR));
+            }
+        }
+        for (name, instance) in &self.instances {
+            if instance.target.is_none() && self.queried.insert(format!("srv {}", name)) {
+                questions.push((name.clone(), dnswire::TYPE_SRV));
+                questions.push((name.clone(), dnswire::TYPE_TXT));
+            }
+            if let Some(target) = &instance.target {
+                if !self.addresses.contains_key(target) && self.queried.insert(format!("a {}", target)) {
+                    questions.push((target.clone(), dnswire::TYPE_A));
+                }
+            }
+        }
+        questions
+    }
+
+    fn into_services(self) -> Vec<AdvertisedService> {
+        self.instances
+            .into_iter()
+            .filter(|(_, instance)| !instance.service_type.is_empty())
+            .map(|(name, instance)| {
+                let ip = instance
+                    .target
+                    .as_ref()
+                    .and_then(|target| self.addresses.get(target))
+                    .and_then(|addrs| addrs.iter().find(|a| a.is_ipv4()).or(addrs.iter().next()).copied())
+                    .or(instance.responder);
+                AdvertisedService {
+                    protocol: "mdns".to_string(),
+                    instance: instance_label(&name),
+                    service_type: instance.service_type.trim_end_matches(".local").to_string(),
+                    hostname: instance.target.map(|t| t.trim_end_matches(".local").to_string()),
+                    ip,
+                    port: instance.port,
+                    txt: instance.txt,
+                }
+            })
+            .collect()
+    }
+}
+
+/// "_ipp._tcp.local" from "Office Printer._ipp._tcp.local"
+fn service_type_of(instance: &str) -> String {
+    let labels = dnswire::split_labels(instance);
+    labels.get(1..).map(|rest| rest.join(".")).unwrap_or_default()
+}
+
+fn instance_label(instance: &str) -> String {
+    dnswire::split_labels(instance).into_iter().next().unwrap_or_default()
+}
+
+/// Bind a UDP socket on a shared port and join a multicast group
+fn bind_multicast(group: Ipv4Addr, port: u16) -> Result<UdpSocket> {
+    let fd = socket::socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+        .context("Failed to create UDP socket")?;
+    // Share the port with avahi, minissdpd and the like
+    socket::setsockopt(&fd, sockopt::ReuseAddr, &true)?;
+    socket::setsockopt(&fd, sockopt::ReusePort, &true)?;
+    socket::bind(fd.as_raw_fd(), &SockaddrIn::from(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port)))
+        .with_context(|| format!("Failed to bind UDP port {}", port))?;
+
+    let socket = UdpSocket::from(fd);
+    socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_read_timeout(Some(POLL_INTERVAL))?;
+    Ok(socket)
+}
+
+fn ephemeral_socket() -> Result<UdpSocket> {
+    let socket = UdpSocket::bind("0.0.0.0:0").context("Failed to bind UDP socket")?;
+    socket.set_read_timeout(Some(POLL_INTERVAL))?;
+    Ok(socket)
+}
+
+fn receive(socket: &UdpSocket, buf: &mut [u8]) -> Option<(usize, SocketAddr)> {
+    socket.recv_from(buf).ok()
+}
+
+/// Query and listen for mDNS/DNS-SD services for the given duration
+pub fn browse_mdns(duration: Duration) -> Result<Vec<AdvertisedService>> {
+    // Fall back to one-shot queries from an ephemeral port, which responders answer by unicast
+    let socket = bind_multicast(MDNS_GROUP, MDNS_PORT).or_else(|_| ephemeral_socket())?;
+    let destination = SocketAddrV4::new(MDNS_GROUP, MDNS_PORT);
+
+    let mut browser = MdnsBrowser::default();
+    socket
+        .send_to(&dnswire::build_query(0, &[(SERVICE_ENUMERATION, dnswire::TYPE_PTR)]), destination)
+        .context("Failed to send mDNS query")?;
+
+    let started = Instant::now();
+    let mut buf = [0u8; 9000];
+    while started.elapsed() < duration {
+        if let Some((len, from)) = receive(&socket, &mut buf) {
+            if let Some(message) = dnswire::parse_message(&buf[..len]) {
+                if message.is_response() {
+                    browser.absorb(&message, from.ip());
+                }
+            }
+        }
+
+        let questions = browser.pending_queries();
+        // Keep each query well under the 1500 byte MTU
+        for chunk in questions.chunks(8) {
+            let refs: Vec<(&str, u16)> = chunk.iter().map(|(name, qtype)| (name.as_str(), *qtype)).collect();
+            let _ = socket.send_to(&dnswire::build_query(0, &refs), destination);
+        }
+    }
+
+    Ok(browser.into_services())
+}
+
+/// Send an SSDP M-SEARCH and collect responses and NOTIFY announcements
+pub fn browse_ssdp(duration: Duration) -> Result<Vec<AdvertisedService>> {
+    let search_socket = ephemeral_socket()?;
+    // NOTIFY announcements are only heard when the shared port can be joined
+    let notify_socket = bind_multicast(SSDP_GROUP, SSDP_PORT).ok();
+
+    let mx = duration.as_secs().clamp(1, 5);
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}:{}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: ssdp:all\r\n\r\n",
+        SSDP_GROUP, SSDP_PORT, mx
+    );
+    search_socket
+        .send_to(search.as_bytes(), SocketAddrV4::new(SSDP_GROUP, SSDP_PORT))
+        .context("Failed to send SSDP M-SEARCH")?;
+
+    let mut services: BTreeMap<String, AdvertisedService> = BTreeMap::new();
+    let started = Instant::now();
+    let mut buf = [0u8; 4096];
+    while started.elapsed() < duration {
+        for socket in std::iter::once(&search_socket).chain(notify_socket.as_ref()) {
+            if let Some((len, from)) = receive(socket, &mut buf) {
+                if let Some(service) = parse_ssdp(&String::from_utf8_lossy(&buf[..len]), from.ip()) {
+                    services.insert(format!("{} {}", service.instance, service.service_type), service);
+                }
+            }
+        }
+    }
+
+    Ok(services.into_values().collect())
+}
+
+/// Parse an M-SEARCH response or NOTIFY alive message
+fn parse_ssdp(text: &str, source: IpAddr) -> Option<AdvertisedService> {
+    let start = text.lines().next()?;
+    if start.starts_with("M-SEARCH") {
+        return None;
+    }
+
+    let header = |name: &str| {
+        text.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+        })
+    };
+    if header("NTS").map(|nts| nts == "ssdp:byebye").unwrap_or(false) {
+        return None;
+    }
+
+    let service_type = header("ST").or_else(|| header("NT"))?;
+    let location = header("LOCATION");
+    // http://192.168.1.1:49152/rootDesc.xml
+    let port = location
+        .as_deref()
+        .and_then(|l| l.split("://").nth(1))
+        .and_then(|rest| rest.split('/').next())
+        .and_then(|authority| authority.rsplit_once(':'))
+        .and_then(|(_, port)| port.parse().ok());
+
+    let mut txt = Vec::new();
+    if let Some(server) = header("SERVER") {
+        txt.push(format!("server={}", server));
+    }
+    if let Some(location) = location {
+        txt.push(format!("location={}", location));
+    }
+
+    Some(AdvertisedService {
+        protocol: "ssdp".to_string(),
+        instance: header("USN").unwrap_or_default(),
+        service_type,
+        hostname: None,
+        ip: Some(source),
+        port,
+        txt,
+    })
+}
+
+/// Browse both protocols and group the results by host, linked to known peers
+pub fn discover_services(duration: Duration) -> Result<Vec<ServiceHost>> {
+    let ssdp = std::thread::spawn(move || browse_ssdp(duration));
+    let mut services = browse_mdns(duration)?;
+    services.extend(ssdp.join().map_err(|_| anyhow::anyhow!("SSDP browser panicked"))??);
+
+    let peers = network::get_network_peers().unwrap_or_default();
+    let mut hosts: BTreeMap<Option<IpAddr>, ServiceHost> = BTreeMap::new();
+    for service in services {
+        let host = hosts.entry(service.ip).or_insert_with(|| ServiceHost {
+            ip: service.ip,
+            hostname: None,
+            peer: service
+                .ip
+                .and_then(|ip| peers.iter().find(|p| p.ip.parse::<IpAddr>().ok() == Some(ip)).cloned()),
+            services: Vec::new(),
+        });
+        if host.hostname.is_none() {
+            host.hostname = service.hostname.clone();
+        }
+        host.services.push(service);
+    }
+
+    Ok(hosts.into_values().collect())
+}
+
+/// Browse for a while and print the services each host advertises
+pub async fn show_services(duration_secs: u64) -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("            🛰️  NetNinja Service Discovery  🛰️              ");
+    println!("═══════════════════════════════════════════════════════════\n");
+    println!("Browsing mDNS/DNS-SD and SSDP for {}s...\n", duration_secs);
+
+    let hosts = tokio::task::spawn_blocking(move || discover_services(Duration::from_secs(duration_secs))).await??;
+
+    if hosts.is_empty() {
+        println!("⚠️  No advertised services found");
+        println!();
+        return Ok(());
+    }
+
+    for host in &hosts {
+        let ip = host.ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown address".to_string());
+        match &host.hostname {
+            Some(name) => println!("🖥️  {} ({})", ip, name),
+            None => println!("🖥️  {}", ip),
+        }
+        if let Some(peer) = &host.peer {
+            println!(
+                "   MAC {} · {} · {}",
+                peer.mac.as_deref().unwrap_or("N/A"),
+                peer.vendor,
+                peer.device_type
+            );
+        }
+        println!("───────────────────────────────────────────────────────────");
+        for service in &host.services {
+            let port = service.port.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string());
+            println!("   {:<5} {:<40} port {:<6} {}", service.protocol, service.service_type, port, service.instance);
+            if !service.txt.is_empty() {
+                println!("         {}", service.txt.join(", "));
+            }
+        }
+        println!();
+    }
+
+    let total: usize = hosts.iter().map(|h| h.services.len()).sum();
+    println!("✅ {} service(s) on {} host(s)", total, hosts.len());
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,767 @@
+use anyhow::{anyhow, Context, Result};
+use crate::cli::parse_duration;
+use crate::config;
+use crate::logs::{self, LogLine, LogSource};
+use crate::rules::{Evaluation, MAX_WINDOW_DAYS};
+use crate::security::{AlertCategory, AlertSeverity, SecurityAlert, SecurityScan};
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use serde_yaml_ng::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// How far back a rule without a timeframe looks
+const DEFAULT_WINDOW_MINUTES: i64 = 10;
+
+/// Syslog facilities of auth.log in the journal: auth and authpriv
+const AUTH_FACILITIES: [u8; 2] = [4, 10];
+
+/// Most alerts one rule raises per scan, one per source address; the newest events are kept
+const MAX_ALERTS_PER_RULE: usize = 20;
+
+/// The parts of a Sigma rule that are used; everything else is ignored
+#[derive(Debug, Deserialize)]
+struct Document {
+    title: String,
+    id: Option<String>,
+    #[serde(default)]
+    logsource: LogSourceSpec,
+    detection: BTreeMap<String, Value>,
+    level: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LogSourceSpec {
+    product: Option<String>,
+    category: Option<String>,
+    service: Option<String>,
+}
+
+/// A Sigma rule compiled against the log sources netninja reads
+#[derive(Debug, Clone)]
+pub struct SigmaRule {
+    pub title: String,
+    pub id: Option<String>,
+    pub path: PathBuf,
+    pub source: LogSource,
+    pub severity: AlertSeverity,
+    pub category: AlertCategory,
+    pub tags: Vec<String>,
+    /// The rule's timeframe, or how far back each scan looks
+    pub window: Duration,
+    searches: Vec<Search>,
+    condition: Condition,
+}
+
+/// A rule file or document that cannot be used, and why
+#[derive(Debug, Clone)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub rules: Vec<SigmaRule>,
+    pub skipped: Vec<Skipped>,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    /// Index into the rule's searches
+    Search(usize),
+    Not(Box<Condition>),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+/// A named search identifier: any alternative matches when all of its fields do
+#[derive(Debug, Clone)]
+struct Search(Vec<Vec<FieldMatch>>);
+
+/// Values for one field; an empty field name matches anywhere in the line, like a keyword
+#[derive(Debug, Clone)]
+struct FieldMatch {
+    field: String,
+    matchers: Vec<Matcher>,
+    /// The `all` modifier: every value must match rather than any
+    all: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// `null`: the field is absent
+    Missing,
+    Text(Regex),
+    Cidr(IpAddr, u8),
+}
+
+/// A log line split into the fields rules refer to
+struct Event<'a> {
+    line: &'a LogLine,
+    /// Lowercase field names
+    fields: HashMap<String, String>,
+}
+
+impl<'a> Event<'a> {
+    /// Lines from log files start with the host name; journal and kernel lines do not
+    fn new(line: &'a LogLine, has_host: bool) -> Self {
+        static FILE_HEADER: OnceLock<Regex> = OnceLock::new();
+        static HEADER: OnceLock<Regex> = OnceLock::new();
+        // "host sshd[1234]: message" from log files, "sshd[1234]: message" from the journal
+        let header = if has_host {
+            FILE_HEADER.get_or_init(|| {
+                Regex::new(r"^(?P<host>\S+) (?P<program>[\w./-]+)(?:\[(?P<pid>\d+)\])?: (?P<message>.*)$")
+                    .expect("syslog header regex is valid")
+            })
+        } else {
+            HEADER.get_or_init(|| {
+                Regex::new(r"^(?P<program>[\w./-]+)(?:\[(?P<pid>\d+)\])?: (?P<message>.*)$")
+                    .expect("syslog header regex is valid")
+            })
+        };
+
+        let mut fields = HashMap::new();
+        let message = match header.captures(&line.text) {
+            Some(caps) => {
+                for name in ["host", "program", "pid"] {
+                    if let Some(value) = caps.name(name) {
+                        fields.insert(name.to_string(), value.as_str().to_string());
+                    }
+                }
+                if let Some(program) = caps.name("program") {
+                    fields.insert("process".to_string(), program.as_str().to_string());
+                }
+                caps.name("message").map(|m| m.as_str()).unwrap_or("")
+            }
+            None => line.text.as_str(),
+        };
+        fields.insert("message".to_string(), message.to_string());
+
+        // Netfilter logs: [UFW BLOCK] IN=eth0 OUT= SRC=203.0.113.9 DST=192.0.2.10 PROTO=TCP SPT=4431 DPT=22
+        for (key, value) in message.split_whitespace().filter_map(|token| token.split_once('=')) {
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_uppercase()) {
+                continue;
+            }
+            let alias = match key {
+                "SRC" => Some("src_ip"),
+                "DST" => Some("dst_ip"),
+                "SPT" => Some("src_port"),
+                "DPT" => Some("dst_port"),
+                "PROTO" => Some("protocol"),
+                _ => None,
+            };
+            if let Some(alias) = alias {
+                fields.insert(alias.to_string(), value.to_string());
+            }
+            fields.insert(key.to_ascii_lowercase(), value.to_string());
+        }
+
+        Event { line, fields }
+    }
+
+    /// The source address, or else the first field (by name) holding an address
+    fn address(&self) -> Option<&str> {
+        if let Some(ip) = self.fields.get("src_ip") {
+            return Some(ip);
+        }
+        let mut names: Vec<&String> = self.fields.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| self.fields[name].as_str())
+            .find(|value| value.parse::<IpAddr>().is_ok())
+    }
+}
+
+impl FieldMatch {
+    fn matches(&self, event: &Event) -> bool {
+        let value = if self.field.is_empty() {
+            Some(event.line.text.as_str())
+        } else {
+            event.fields.get(&self.field.to_ascii_lowercase()).map(String::as_str)
+        };
+        let test = |matcher: &Matcher| match (matcher, value) {
+            (Matcher::Missing, value) => value.is_none(),
+            (Matcher::Text(re), Some(value)) => re.is_match(value),
+            (Matcher::Cidr(network, prefix), Some(value)) => value
+                .parse()
+                .map(|ip| in_network(ip, *network, *prefix))
+                .unwrap_or(false),
+            _ => false,
+        };
+        if self.all {
+            self.matchers.iter().all(test)
+        } else {
+            self.matchers.iter().any(test)
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, searches: &[Search], event: &Event) -> bool {
+        match self {
+            Condition::Search(index) => searches[*index]
+                .0
+                .iter()
+                .any(|fields| fields.iter().all(|field| field.matches(event))),
+            Condition::Not(inner) => !inner.matches(searches, event),
+            Condition::All(terms) => terms.iter().all(|term| term.matches(searches, event)),
+            Condition::Any(terms) => terms.iter().any(|term| term.matches(searches, event)),
+        }
+    }
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Case-insensitive regex for a Sigma value, where `*` and `?` are wildcards unless escaped
+fn wildcard(value: &str, anchor_start: bool, anchor_end: bool) -> Result<Regex> {
+    let mut pattern = String::from("(?i)");
+    if anchor_start {
+        pattern.push('^');
+    }
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '\\' if matches!(chars.peek(), Some('*' | '?' | '\\')) => {
+                let escaped = chars.next().expect("peeked character exists");
+                pattern.push_str(&regex::escape(&escaped.to_string()));
+            }
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    if anchor_end {
+        pattern.push('$');
+    }
+    Ok(Regex::new(&pattern)?)
+}
+
+fn scalar(value: &Value) -> Result<Option<String>> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s.clone())),
+        Value::Number(n) => Ok(Some(n.to_string())),
+        Value::Bool(b) => Ok(Some(b.to_string())),
+        _ => Err(anyhow!("expected a plain value, found {:?}", value)),
+    }
+}
+
+/// `field|modifier|...: value or list of values`
+fn compile_field(key: &str, value: &Value) -> Result<FieldMatch> {
+    let mut parts = key.split('|');
+    let field = parts.next().unwrap_or("").to_string();
+    let mut all = false;
+    let mut modifier = None;
+    for part in parts {
+        match part {
+            "all" => all = true,
+            "contains" | "startswith" | "endswith" | "re" | "cidr" if modifier.is_none() => modifier = Some(part),
+            _ => return Err(anyhow!("modifier '{}' in '{}' is not supported", part, key)),
+        }
+    }
+
+    let values = match value {
+        Value::Sequence(items) => items.iter().map(scalar).collect::<Result<Vec<_>>>()?,
+        value => vec![scalar(value)?],
+    };
+    let matchers = values
+        .into_iter()
+        .map(|value| {
+            let Some(value) = value else {
+                return Ok(Matcher::Missing);
+            };
+            match modifier {
+                None => Ok(Matcher::Text(wildcard(&value, true, true)?)),
+                Some("contains") => Ok(Matcher::Text(wildcard(&value, false, false)?)),
+                Some("startswith") => Ok(Matcher::Text(wildcard(&value, true, false)?)),
+                Some("endswith") => Ok(Matcher::Text(wildcard(&value, false, true)?)),
+                Some("re") => Ok(Matcher::Text(
+                    Regex::new(&value).with_context(|| format!("invalid regex '{}'", value))?,
+                )),
+                _ => {
+                    let (network, prefix) = value.split_once('/').unwrap_or((value.as_str(), ""));
+                    let network: IpAddr = network.parse().with_context(|| format!("invalid CIDR '{}'", value))?;
+                    let max = if network.is_ipv4() { 32 } else { 128 };
+                    let prefix = if prefix.is_empty() { max } else { prefix.parse().unwrap_or(u8::MAX) };
+                    if prefix > max {
+                        return Err(anyhow!("invalid CIDR '{}'", value));
+                    }
+                    Ok(Matcher::Cidr(network, prefix))
+                }
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(FieldMatch { field, matchers, all })
+}
+
+/// A search identifier: a map of fields, a list of such maps, or a list of keywords
+fn compile_search(value: &Value) -> Result<Search> {
+    let fields = |map: &serde_yaml_ng::Mapping| {
+        map.iter()
+            .map(|(key, value)| {
+                let key = key.as_str().ok_or_else(|| anyhow!("field names must be strings"))?;
+                compile_field(key, value)
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+    match value {
+        Value::Mapping(map) => Ok(Search(vec![fields(map)?])),
+        Value::Sequence(items) if items.iter().all(Value::is_mapping) => Ok(Search(
+            items
+                .iter()
+                .filter_map(Value::as_mapping)
+                .map(fields)
+                .collect::<Result<Vec<_>>>()?,
+        )),
+        // Keywords match anywhere in the line
+        keywords => Ok(Search(vec![vec![compile_field("|contains", keywords)?]])),
+    }
+}
+
+/// Recursive descent over `and`, `or`, `not`, parentheses and `1 of`/`all of` quantifiers
+struct ConditionParser<'a> {
+    tokens: Vec<String>,
+    position: usize,
+    names: &'a [String],
+}
+
+impl ConditionParser<'_> {
+    fn parse(condition: &str, names: &[String]) -> Result<Condition> {
+        if condition.contains('|') {
+            return Err(anyhow!("aggregations in conditions are not supported"));
+        }
+        let tokens = condition
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let mut parser = ConditionParser { tokens, position: 0, names };
+        let parsed = parser.or()?;
+        match parser.peek() {
+            None => Ok(parsed),
+            Some(token) => Err(anyhow!("unexpected '{}' in condition", token)),
+        }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, keyword: &str) -> bool {
+        let matched = self.peek().map(|t| t.eq_ignore_ascii_case(keyword)).unwrap_or(false);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn or(&mut self) -> Result<Condition> {
+        let mut terms = vec![self.and()?];
+        while self.eat("or") {
+            terms.push(self.and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Condition::Any(terms) })
+    }
+
+    fn and(&mut self) -> Result<Condition> {
+        let mut terms = vec![self.not()?];
+        while self.eat("and") {
+            terms.push(self.not()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Condition::All(terms) })
+    }
+
+    fn not(&mut self) -> Result<Condition> {
+        if self.eat("not") {
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Condition> {
+        let token = self.next().ok_or_else(|| anyhow!("condition ends unexpectedly"))?;
+        if token == "(" {
+            let inner = self.or()?;
+            if !self.eat(")") {
+                return Err(anyhow!("missing ')' in condition"));
+            }
+            return Ok(inner);
+        }
+
+        let quantifier = token.to_ascii_lowercase();
+        if matches!(quantifier.as_str(), "1" | "any" | "all") && self.eat("of") {
+            let target = self.next().ok_or_else(|| anyhow!("'{} of' needs a target", token))?;
+            let terms = self.resolve(&target)?.into_iter().map(Condition::Search).collect();
+            return Ok(if quantifier == "all" { Condition::All(terms) } else { Condition::Any(terms) });
+        }
+
+        let index = self
+            .names
+            .iter()
+            .position(|name| *name == token)
+            .ok_or_else(|| anyhow!("condition refers to unknown search '{}'", token))?;
+        Ok(Condition::Search(index))
+    }
+
+    /// Search indices for `them` or a name with `*` wildcards
+    fn resolve(&self, target: &str) -> Result<Vec<usize>> {
+        let indices: Vec<usize> = if target == "them" {
+            (0..self.names.len()).filter(|&i| !self.names[i].starts_with('_')).collect()
+        } else {
+            let pattern = format!("^{}$", regex::escape(target).replace(r"\*", ".*"));
+            let pattern = Regex::new(&pattern)?;
+            (0..self.names.len()).filter(|&i| pattern.is_match(&self.names[i])).collect()
+        };
+        if indices.is_empty() {
+            return Err(anyhow!("'{}' matches no search", target));
+        }
+        Ok(indices)
+    }
+}
+
+/// The log source a Sigma logsource refers to
+fn resolve_source(spec: &LogSourceSpec) -> Result<LogSource> {
+    if let Some(product) = spec.product.as_deref().filter(|p| !p.eq_ignore_ascii_case("linux")) {
+        return Err(anyhow!("product '{}' is not supported", product));
+    }
+    let first_file = |paths: &[&str], fallback: LogSource| {
+        paths
+            .iter()
+            .find(|path| Path::new(path).exists())
+            .map(|path| LogSource::File(path.to_string()))
+            .unwrap_or(fallback)
+    };
+    match (spec.service.as_deref(), spec.category.as_deref()) {
+        (Some("auth"), _) => Ok(first_file(
+            &["/var/log/auth.log", "/var/log/secure"],
+            LogSource::JournalFacilities(AUTH_FACILITIES.to_vec()),
+        )),
+        (Some("syslog"), _) => Ok(first_file(&["/var/log/syslog", "/var/log/messages"], LogSource::Journal(None))),
+        (Some("netfilter" | "iptables" | "nftables" | "ufw"), _) | (None, Some("firewall")) => Ok(LogSource::Dmesg),
+        (service, category) => Err(anyhow!(
+            "logsource service '{}' / category '{}' is not supported (use auth, syslog or netfilter)",
+            service.unwrap_or("-"),
+            category.unwrap_or("-")
+        )),
+    }
+}
+
+fn severity(level: Option<&str>) -> Result<AlertSeverity> {
+    match level.map(str::to_ascii_lowercase).as_deref() {
+        Some("informational") => Ok(AlertSeverity::Info),
+        Some(level) => level.parse().map_err(|e: String| anyhow!(e)),
+        None => Ok(AlertSeverity::Medium),
+    }
+}
+
+/// Pick a category from the ATT&CK tags, falling back on the kind of log
+fn category(tags: &[String], firewall: bool) -> AlertCategory {
+    let tagged = |prefixes: &[&str]| {
+        tags.iter()
+            .any(|tag| prefixes.iter().any(|prefix| tag.to_ascii_lowercase().starts_with(prefix)))
+    };
+    if tagged(&["attack.t1110", "attack.credential_access"]) {
+        AlertCategory::FailedLogin
+    } else if tagged(&["attack.t1046", "attack.discovery", "attack.reconnaissance"]) {
+        AlertCategory::PortScan
+    } else if tagged(&["attack.command_and_control", "attack.exfiltration", "attack.t1071", "attack.t1090"]) {
+        AlertCategory::UnusualTraffic
+    } else if firewall {
+        AlertCategory::FirewallBlock
+    } else if tagged(&["attack.execution", "attack.defense_evasion", "attack.t1059"]) {
+        AlertCategory::SuspiciousProcess
+    } else {
+        AlertCategory::SystemChange
+    }
+}
+
+impl SigmaRule {
+    fn compile(document: Document, path: &Path) -> Result<Self> {
+        let source = resolve_source(&document.logsource)?;
+        let mut detection = document.detection;
+        let condition = detection.remove("condition").ok_or_else(|| anyhow!("detection has no condition"))?;
+        let window = match detection.remove("timeframe") {
+            Some(timeframe) => {
+                let timeframe = scalar(&timeframe)?.unwrap_or_default();
+                let window = parse_duration(&timeframe).map_err(|e| anyhow!(e))?;
+                if window <= Duration::zero() || window > Duration::days(MAX_WINDOW_DAYS) {
+                    return Err(anyhow!("timeframe must be between 1s and {} days", MAX_WINDOW_DAYS));
+                }
+                window
+            }
+            None => Duration::minutes(DEFAULT_WINDOW_MINUTES),
+        };
+
+        let names: Vec<String> = detection.keys().cloned().collect();
+        let searches = detection
+            .iter()
+            .map(|(name, value)| compile_search(value).with_context(|| format!("in search '{}'", name)))
+            .collect::<Result<Vec<_>>>()?;
+        let condition = match &condition {
+            Value::String(condition) => ConditionParser::parse(condition, &names)?,
+            Value::Sequence(conditions) => Condition::Any(
+                conditions
+                    .iter()
+                    .map(|c| ConditionParser::parse(c.as_str().unwrap_or_default(), &names))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            _ => return Err(anyhow!("condition must be a string or a list of strings")),
+        };
+
+        Ok(SigmaRule {
+            severity: severity(document.level.as_deref())?,
+            category: category(&document.tags, source == LogSource::Dmesg),
+            title: document.title,
+            id: document.id,
+            path: path.to_path_buf(),
+            source,
+            tags: document.tags,
+            window,
+            searches,
+            condition,
+        })
+    }
+
+    /// The rule id, or its file name when it has none
+    pub fn reference(&self) -> String {
+        match &self.id {
+            Some(id) => id.clone(),
+            None => self.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        }
+    }
+
+    /// One alert per rule and source address; the latest matching line goes in the details
+    fn alert(&self, event: &Event) -> SecurityAlert {
+        let tags = if self.tags.is_empty() { String::new() } else { format!(" [{}]", self.tags.join(", ")) };
+        let subject = match event.address() {
+            Some(address) => format!("sigma {} {}", self.reference(), address),
+            None => format!("sigma {}", self.reference()),
+        };
+        SecurityAlert {
+            timestamp: event.line.timestamp,
+            severity: self.severity.clone(),
+            category: self.category.clone(),
+            message: self.title.clone(),
+            details: Some(format!("{}; Sigma rule {}{}", event.line.text, self.reference(), tags)),
+            subject: Some(subject),
+        }
+    }
+}
+
+/// Rule files under `dir`, recursively, in name order
+fn rule_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            rule_files(&path, files)?;
+        } else if matches!(path.extension().and_then(|e| e.to_str()), Some("yml" | "yaml")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Load every rule from the Sigma file or directory; rules that cannot be used are set aside with the reason
+pub fn load_rules() -> Result<RuleSet> {
+    let path = config::sigma_path()?;
+    let mut files = Vec::new();
+    if path.is_dir() {
+        rule_files(&path, &mut files)?;
+    } else if path.exists() {
+        files.push(path);
+    }
+
+    let mut set = RuleSet::default();
+    for file in files {
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) => {
+                set.skipped.push(Skipped { path: file, title: None, reason: e.to_string() });
+                continue;
+            }
+        };
+        for document in serde_yaml_ng::Deserializer::from_str(&content) {
+            let value = match Value::deserialize(document) {
+                Ok(value) => value,
+                Err(e) => {
+                    set.skipped.push(Skipped { path: file.clone(), title: None, reason: e.to_string() });
+                    break;
+                }
+            };
+            let title = value.get("title").and_then(Value::as_str).map(str::to_string);
+            let compiled = serde_yaml_ng::from_value::<Document>(value)
+                .map_err(anyhow::Error::from)
+                .and_then(|document| SigmaRule::compile(document, &file));
+            match compiled {
+                Ok(rule) => set.rules.push(rule),
+                Err(e) => set.skipped.push(Skipped { path: file.clone(), title, reason: format!("{:#}", e) }),
+            }
+        }
+    }
+    Ok(set)
+}
+
+/// Evaluate the rules, reading each log source once for the longest window on it
+pub fn evaluate_rules(rules: &[SigmaRule], now: DateTime<Utc>) -> Vec<Result<Evaluation, String>> {
+    let mut windows: HashMap<&LogSource, Duration> = HashMap::new();
+    for rule in rules {
+        let window = windows.entry(&rule.source).or_insert(rule.window);
+        *window = (*window).max(rule.window);
+    }
+    let logs: HashMap<&LogSource, Result<Vec<LogLine>, String>> = windows
+        .into_iter()
+        .map(|(source, window)| (source, logs::read(source, now - window).map_err(|e| e.to_string())))
+        .collect();
+    let events: HashMap<&LogSource, Vec<Event>> = logs
+        .iter()
+        .filter_map(|(source, lines)| {
+            let has_host = matches!(source, LogSource::File(_));
+            Some((*source, lines.as_ref().ok()?.iter().map(|line| Event::new(line, has_host)).collect()))
+        })
+        .collect();
+
+    rules
+        .iter()
+        .map(|rule| {
+            let Some(events) = events.get(&rule.source) else {
+                return Err(logs[&rule.source].as_ref().err().cloned().unwrap_or_default());
+            };
+            let since = now - rule.window;
+            let matched: Vec<&Event> = events
+                .iter()
+                .filter(|event| event.line.timestamp >= since && rule.condition.matches(&rule.searches, event))
+                .collect();
+            // Newest first, so the alert for each subject carries its latest line
+            let mut subjects = HashSet::new();
+            let alerts = matched
+                .iter()
+                .rev()
+                .map(|event| rule.alert(event))
+                .filter(|alert| subjects.insert(alert.subject.clone()))
+                .take(MAX_ALERTS_PER_RULE)
+                .collect();
+            Ok(Evaluation { matches: matched.len(), alerts })
+        })
+        .collect()
+}
+
+/// Alerts raised by the Sigma rules, and the rules whose log source could not be read
+pub fn check_sigma_rules() -> Result<SecurityScan> {
+    let set = load_rules()?;
+    let mut scan = SecurityScan::default();
+    for (rule, result) in set.rules.iter().zip(evaluate_rules(&set.rules, Utc::now())) {
+        match result {
+            Ok(evaluation) => scan.alerts.extend(evaluation.alerts),
+            Err(e) => scan.errors.push(format!("Sigma rule {}: cannot read {}: {}", rule.reference(), rule.source, e)),
+        }
+    }
+    Ok(scan)
+}
+
+fn print_skipped(skipped: &[Skipped]) {
+    if skipped.is_empty() {
+        return;
+    }
+    println!("⚠️  Skipped {} rule(s):", skipped.len());
+    for skip in skipped {
+        let title = skip.title.as_deref().map(|t| format!(" ({})", t)).unwrap_or_default();
+        println!("   {}{}: {}", skip.path.display(), title, skip.reason);
+    }
+    println!();
+}
+
+/// List the Sigma rules that were loaded and the ones that were not
+pub async fn show_rules() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("                 📜  NetNinja Sigma Rules  📜              ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let path = config::sigma_path()?;
+    let set = load_rules()?;
+    if set.rules.is_empty() && set.skipped.is_empty() {
+        println!("No Sigma rules in {}\n", path.display());
+        return Ok(());
+    }
+
+    println!("Rules from: {}\n", path.display());
+    for rule in &set.rules {
+        println!("✅ {} [{}, {}]", rule.title, rule.severity, rule.category.label());
+        println!("   Rule:   {}", rule.reference());
+        println!("   Source: {}", rule.source);
+        if !rule.tags.is_empty() {
+            println!("   Tags:   {}", rule.tags.join(", "));
+        }
+        println!();
+    }
+    print_skipped(&set.skipped);
+    Ok(())
+}
+
+/// Evaluate the Sigma rules now and show what each one found
+pub async fn run_rules() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              🔍  NetNinja Sigma Evaluation  🔍            ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let set = load_rules()?;
+    if set.rules.is_empty() {
+        println!("No usable Sigma rules in {}\n", config::sigma_path()?.display());
+        print_skipped(&set.skipped);
+        return Ok(());
+    }
+
+    let results = tokio::task::spawn_blocking({
+        let rules = set.rules.clone();
+        move || evaluate_rules(&rules, Utc::now())
+    })
+    .await?;
+
+    for (rule, result) in set.rules.iter().zip(results) {
+        match result {
+            Err(e) => println!("❌ {}: cannot read {}: {}", rule.title, rule.source, e),
+            Ok(evaluation) if evaluation.matches == 0 => println!("✅ {}: no matching events", rule.title),
+            Ok(evaluation) => {
+                println!("🚨 {}: {} matching event(s) [{}]", rule.title, evaluation.matches, rule.severity);
+                for alert in &evaluation.alerts {
+                    println!("   {}", alert.details.as_deref().unwrap_or_default());
+                }
+            }
+        }
+    }
+    println!();
+    print_skipped(&set.skipped);
+    Ok(())
+}
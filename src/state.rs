@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use crate::netns;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Directory for persistent state: `$XDG_DATA_HOME/netninja` or `~/.local/share/netninja`
+pub fn data_dir() -> Result<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var_os("HOME").context("HOME is not set")?;
+            PathBuf::from(home).join(".local").join("share")
+        }
+    };
+    
+    let dir = base.join("netninja");
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create state directory {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// File name for the current namespace: `name.json`, or `name-{namespace}.json` under `--netns`
+pub fn namespaced(name: &str) -> String {
+    match (netns::state_key(), name.rsplit_once('.')) {
+        (Some(key), Some((stem, extension))) => format!("{}-{}.{}", stem, key, extension),
+        (Some(key), None) => format!("{}-{}", name, key),
+        (None, _) => name.to_string(),
+    }
+}
+
+/// Load the current namespace's JSON state file, returning None if it has never been written
+pub fn load<T: DeserializeOwned>(name: &str) -> Result<Option<T>> {
+    let path = data_dir()?.join(namespaced(name));
+    if !path.exists() {
+        return Ok(None);
+    }
+    
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(value))
+}
+
+/// Atomically replace the current namespace's JSON state file
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let path = data_dir()?.join(namespaced(name));
+    let tmp_path = path.with_extension("tmp");
+    
+    let content = serde_json::to_string_pretty(value)?;
+    std::fs::write(&tmp_path, content)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, &path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
@@ -177,19 +177,19 @@ pub fn populate_panes(session: &TmuxSession) -> Result<()> {
     
     // Pane 2: Security Alerts
     session.send_keys("2", "clear")?;
-    session.send_keys("2", "watch -n 5 'echo \"=== Security Status ===\"  && netninja-cli status 2>/dev/null | grep -A20 \"Security\" || journalctl -u ssh -n 10 --no-pager | tail -5'")?;
+    session.send_keys("2", "watch -n 5 'netninja-cli status --section security 2>/dev/null || journalctl -u ssh -n 10 --no-pager | tail -5'")?;
     
     // Pane 3: VPN Status
     session.send_keys("3", "clear")?;
-    session.send_keys("3", "watch -n 5 'echo \"=== VPN Status ===\" && ip addr show | grep -E \"tun|tap|wg\" || echo \"No VPN detected\"'")?;
+    session.send_keys("3", "watch -n 5 'netninja-cli status --section vpn'")?;
     
     // Pane 4: Open Ports
     session.send_keys("4", "clear")?;
-    session.send_keys("4", "watch -n 5 'echo \"=== Open Ports ===\" && ss -tuln | head -20'")?;
+    session.send_keys("4", "watch -n 5 'netninja-cli status --section ports'")?;
     
     // Pane 5: Network Peers
     session.send_keys("5", "clear")?;
-    session.send_keys("5", "watch -n 10 'echo \"=== Network Peers ===\" && ip neigh show | head -15'")?;
+    session.send_keys("5", "watch -n 10 'netninja-cli status --section peers'")?;
     
     Ok(())
 }
@@ -217,6 +217,10 @@ tmux new-session -d -s "$SESSION_NAME"
 # Rename window
 tmux rename-window -t "$SESSION_NAME:0" "NetNinja Monitor"
 
+# Run the daemon in a background window so the panes read its cached snapshot
+# (the window closes straight away if a daemon is already running)
+tmux new-window -d -t "$SESSION_NAME:9" -n "daemon" 'netninja-cli daemon'
+
 # Create pane layout (6 panes)
 tmux split-window -h -t "$SESSION_NAME"
 tmux select-pane -t "$SESSION_NAME:0.0"
@@ -236,17 +240,17 @@ tmux send-keys -t "$SESSION_NAME:0.0" 'clear && watch -n 2 "ip addr show | grep
 # Pane 1: iftop or network stats
 tmux send-keys -t "$SESSION_NAME:0.1" 'clear && if command -v iftop &> /dev/null; then sudo iftop -t -s 2 2>/dev/null || echo "Run with sudo for iftop"; else watch -n 2 "cat /proc/net/dev"; fi' C-m
 
-# Pane 2: Security/Auth logs
-tmux send-keys -t "$SESSION_NAME:0.2" 'clear && watch -n 5 "echo \"=== Recent Auth Logs ===\" && journalctl -u ssh -n 10 --no-pager 2>/dev/null | tail -5 || tail -10 /var/log/auth.log 2>/dev/null || echo \"No auth logs accessible\""' C-m
+# Pane 2: Security alerts and firewall
+tmux send-keys -t "$SESSION_NAME:0.2" 'clear && watch -n 5 "netninja-cli status --section security 2>/dev/null || journalctl -u ssh -n 10 --no-pager 2>/dev/null | tail -5"' C-m
 
 # Pane 3: VPN Status
-tmux send-keys -t "$SESSION_NAME:0.3" 'clear && watch -n 5 "echo \"=== VPN Status ===\" && ip addr show | grep -E \"tun|tap|wg\" -A2 || echo \"No VPN interface detected\""' C-m
+tmux send-keys -t "$SESSION_NAME:0.3" 'clear && watch -n 5 "netninja-cli status --section vpn"' C-m
 
 # Pane 4: Open Ports
-tmux send-keys -t "$SESSION_NAME:0.4" 'clear && watch -n 5 "echo \"=== Listening Ports ===\" && ss -tuln | head -20"' C-m
+tmux send-keys -t "$SESSION_NAME:0.4" 'clear && watch -n 5 "netninja-cli status --section ports"' C-m
 
 # Pane 5: Network Neighbors/Peers
-tmux send-keys -t "$SESSION_NAME:0.5" 'clear && watch -n 10 "echo \"=== Network Neighbors ===\" && ip neigh show | head -15"' C-m
+tmux send-keys -t "$SESSION_NAME:0.5" 'clear && watch -n 10 "netninja-cli status --section peers"' C-m
 
 # Attach to session
 echo "Starting NetNinja Monitoring Dashboard..."
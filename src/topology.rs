@@ -0,0 +1,209 @@
+use anyhow::Result;
+use crate::network::{self, LinkDetails};
+use crate::{containers, wireless};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceNode {
+    pub name: String,
+    pub index: u32,
+    /// Link kind, e.g. "ethernet", "bridge", "bond", "vlan", "veth", "macvlan"
+    pub kind: String,
+    pub is_up: bool,
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub addresses: Vec<String>,
+    /// Bridge or bond this device is a port of
+    pub master: Option<String>,
+    /// Lower device of a VLAN, macvlan or ipvlan
+    pub parent: Option<String>,
+    pub vlan_id: Option<u64>,
+    /// Mode of a bond, macvlan or ipvlan
+    pub mode: Option<String>,
+    pub active_slave: Option<String>,
+    /// Port state within the master, e.g. "forwarding" or "ACTIVE"/"BACKUP"
+    pub port_state: Option<String>,
+    /// The other end of a veth pair
+    pub veth_peer: Option<String>,
+    /// Container owning this device or the network it bridges
+    pub container: Option<String>,
+    pub children: Vec<InterfaceNode>,
+}
+
+impl InterfaceNode {
+    /// One-line description of the device and how it relates to others
+    pub fn describe(&self) -> String {
+        let mut parts = vec![self.kind.clone()];
+        if let Some(id) = self.vlan_id {
+            parts.push(format!("id {}", id));
+        }
+        if let Some(mode) = &self.mode {
+            parts.push(mode.clone());
+        }
+        if let Some(active) = &self.active_slave {
+            parts.push(format!("active {}", active));
+        }
+        if let (Some(parent), true) = (&self.parent, self.veth_peer.is_none()) {
+            parts.push(format!("on {}", parent));
+        }
+        if let Some(peer) = &self.veth_peer {
+            parts.push(format!("↔ {}", peer));
+        }
+        if let Some(state) = &self.port_state {
+            parts.push(state.clone());
+        }
+        parts.join(", ")
+    }
+}
+
+fn kind_of(link: &LinkDetails) -> String {
+    match (&link.kind, link.link_type.as_str()) {
+        (Some(kind), _) => kind.clone(),
+        (None, "loopback") => "loopback".to_string(),
+        (None, "ether") if wireless::is_wireless(&link.name) => "wireless".to_string(),
+        (None, "ether") => "ethernet".to_string(),
+        (None, other) => other.to_string(),
+    }
+}
+
+fn json_string(value: &serde_json::Value, key: &str) -> Option<String> {
+    value[key].as_str().map(str::to_string)
+}
+
+fn to_node(link: &LinkDetails, addresses: &HashMap<String, Vec<String>>, container_list: &[containers::Container]) -> InterfaceNode {
+    let kind = kind_of(link);
+    let is_veth = kind == "veth";
+
+    let veth_peer = match (is_veth, &link.parent, link.link_index, link.link_netnsid) {
+        (true, Some(peer), _, _) => Some(peer.clone()),
+        (true, None, Some(index), Some(nsid)) => Some(format!("ifindex {} in netns {}", index, nsid)),
+        _ => None,
+    };
+
+    InterfaceNode {
+        name: link.name.clone(),
+        index: link.index,
+        is_up: link.is_up(),
+        mac_address: link.mac_address.clone(),
+        mtu: link.mtu,
+        addresses: addresses.get(&link.name).cloned().unwrap_or_default(),
+        master: link.master.clone(),
+        parent: if is_veth { None } else { link.parent.clone() },
+        vlan_id: (kind == "vlan").then(|| link.info_data["id"].as_u64()).flatten(),
+        mode: json_string(&link.info_data, "mode"),
+        active_slave: json_string(&link.info_data, "active_slave"),
+        port_state: json_string(&link.slave_data, "state"),
+        veth_peer,
+        container: containers::describe_interface(container_list, &link.name),
+        kind,
+        children: Vec::new(),
+    }
+}
+
+/// Every interface with its relationships, in kernel index order
+pub fn get_interface_nodes() -> Result<Vec<InterfaceNode>> {
+    let links = network::get_link_details()?;
+    let addresses: HashMap<String, Vec<String>> = network::get_interfaces()?
+        .into_iter()
+        .map(|iface| (iface.name, iface.ip_addresses))
+        .collect();
+
+    // Container names are a bonus; without an engine or root the tree still works
+    let mut container_list = containers::list_containers().unwrap_or_default();
+    let _ = containers::attach_host_links(&mut container_list);
+
+    let mut nodes: Vec<InterfaceNode> = links
+        .iter()
+        .map(|link| to_node(link, &addresses, &container_list))
+        .collect();
+    nodes.sort_by_key(|n| n.index);
+    Ok(nodes)
+}
+
+/// Nest ports under their bridge or bond and upper devices under their lower device
+pub fn build_tree(nodes: Vec<InterfaceNode>) -> Vec<InterfaceNode> {
+    let names: Vec<String> = nodes.iter().map(|n| n.name.clone()).collect();
+    // A port sits under its master; a VLAN or macvlan without one sits under its parent
+    let owner_of = |node: &InterfaceNode| -> Option<String> {
+        node.master
+            .clone()
+            .or_else(|| node.parent.clone())
+            .filter(|owner| names.contains(owner) && *owner != node.name)
+    };
+
+    let mut children: HashMap<String, Vec<InterfaceNode>> = HashMap::new();
+    let mut roots = Vec::new();
+    for node in nodes {
+        match owner_of(&node) {
+            Some(owner) => children.entry(owner).or_default().push(node),
+            None => roots.push(node),
+        }
+    }
+
+    fn attach(node: &mut InterfaceNode, children: &mut HashMap<String, Vec<InterfaceNode>>) {
+        if let Some(mut kids) = children.remove(&node.name) {
+            for kid in &mut kids {
+                attach(kid, children);
+            }
+            node.children = kids;
+        }
+    }
+    for root in &mut roots {
+        attach(root, &mut children);
+    }
+    roots
+}
+
+fn print_node(node: &InterfaceNode, prefix: &str, connector: &str, child_prefix: &str) {
+    let state = if node.is_up { "🟢" } else { "🔴" };
+    let addresses = if node.addresses.is_empty() { String::new() } else { format!("  {}", node.addresses.join(", ")) };
+    println!("{}{}{} {}  [{}]{}", prefix, connector, state, node.name, node.describe(), addresses);
+    if let Some(container) = &node.container {
+        println!("{}{}   🐳 {}", prefix, child_prefix, container);
+    }
+
+    let next_prefix = format!("{}{}", prefix, child_prefix);
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        print_node(child, &next_prefix, if last { "└── " } else { "├── " }, if last { "    " } else { "│   " });
+    }
+}
+
+/// Show interfaces as a flat table or a tree, as text or JSON
+pub async fn show_interfaces(tree: bool, json: bool) -> Result<()> {
+    let nodes = tokio::task::spawn_blocking(get_interface_nodes).await??;
+    let nodes = if tree { build_tree(nodes) } else { nodes };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&nodes)?);
+        return Ok(());
+    }
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              🌳  NetNinja Interface Topology  🌳          ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    if tree {
+        for node in &nodes {
+            print_node(node, "", "", "");
+        }
+    } else {
+        println!("{:<16} {:<10} {:<6} {:<18} {:<12} {:<20}", "Interface", "Kind", "State", "MAC Address", "Master", "Addresses");
+        println!("{}", "─".repeat(90));
+        for node in &nodes {
+            println!(
+                "{:<16} {:<10} {:<6} {:<18} {:<12} {}",
+                node.name,
+                node.kind,
+                if node.is_up { "UP" } else { "DOWN" },
+                node.mac_address.as_deref().unwrap_or("-"),
+                node.master.as_deref().unwrap_or("-"),
+                node.addresses.join(", ")
+            );
+        }
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,580 @@
+use anyhow::Result;
+use crate::network::{self, LinkDetails};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Command;
+
+/// Well-known anycast addresses used to ask the kernel which way internet traffic goes
+pub const PROBE_TARGET_V4: &str = "1.1.1.1";
+pub const PROBE_TARGET_V6: &str = "2606:4700:4700::1111";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireGuardPeer {
+    pub public_key: String,
+    pub endpoint: Option<String>,
+    pub allowed_ips: Vec<String>,
+    pub latest_handshake: Option<DateTime<Utc>>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnTunnel {
+    /// Interface name, or "ipsec:<remote>" for policy-based IPsec without one
+    pub name: String,
+    pub interface: Option<String>,
+    pub vpn_type: String,
+    /// Netlink link kind, e.g. "wireguard" or "tun"
+    pub kind: Option<String>,
+    pub is_up: bool,
+    pub addresses: Vec<String>,
+    /// Remote gateway, when the tunnel type exposes one
+    pub endpoint: Option<String>,
+    pub carries_default_route: bool,
+    pub wireguard_peers: Vec<WireGuardPeer>,
+}
+
+/// Find VPN tunnels from link kinds, WireGuard state and IPsec SAs
+pub fn detect_tunnels() -> Result<Vec<VpnTunnel>> {
+    let links = network::get_link_details()?;
+    let interfaces = network::get_interfaces().unwrap_or_default();
+    let daemons = running_process_names();
+    let default_devices: BTreeSet<String> = [PROBE_TARGET_V4, PROBE_TARGET_V6]
+        .iter()
+        .filter_map(|target| route_device(target))
+        .collect();
+    let mut wireguard = wireguard_peers();
+
+    let mut tunnels = Vec::new();
+    for link in &links {
+        let Some(vpn_type) = classify_link(link, &daemons) else {
+            continue;
+        };
+        let addresses = interfaces
+            .iter()
+            .find(|i| i.name == link.name)
+            .map(|i| i.ip_addresses.clone())
+            .unwrap_or_default();
+        let peers = wireguard.remove(&link.name).unwrap_or_default();
+        let endpoint = match link.kind.as_deref() {
+            Some("wireguard") => {
+                let endpoints: Vec<&str> = peers.iter().filter_map(|p| p.endpoint.as_deref()).collect();
+                (!endpoints.is_empty()).then(|| endpoints.join(", "))
+            }
+            _ => link.info_data["remote"].as_str().map(str::to_string),
+        };
+
+        tunnels.push(VpnTunnel {
+            name: link.name.clone(),
+            interface: Some(link.name.clone()),
+            vpn_type,
+            kind: link.kind.clone(),
+            is_up: link.is_up(),
+            addresses,
+            endpoint,
+            carries_default_route: default_devices.contains(&link.name),
+            wireguard_peers: peers,
+        });
+    }
+
+    let local_addresses: BTreeSet<String> = interfaces
+        .iter()
+        .flat_map(|i| i.ip_addresses.iter().cloned())
+        .collect();
+    tunnels.extend(ipsec_tunnels(&local_addresses));
+
+    Ok(tunnels)
+}
+
+/// VPN type for a link, or None if it is not a VPN tunnel
+fn classify_link(link: &LinkDetails, daemons: &BTreeSet<String>) -> Option<String> {
+    let name = link.name.as_str();
+    let vpn_type = match link.kind.as_deref() {
+        Some("wireguard") if name.starts_with("nordlynx") => "WireGuard (NordLynx)",
+        Some("wireguard") => "WireGuard",
+        Some("vti") | Some("vti6") | Some("xfrm") => "IPsec (route-based)",
+        Some("tun") => {
+            // A tun device says nothing about who drives it; use the name and running daemons
+            if name.starts_with("tailscale") {
+                "Tailscale"
+            } else if name.starts_with("zt") {
+                "ZeroTier"
+            } else if name.starts_with("wg") && (daemons.contains("wireguard-go") || daemons.contains("boringtun")) {
+                "WireGuard (userspace)"
+            } else if daemons.contains("openvpn") {
+                "OpenVPN"
+            } else if daemons.contains("openconnect") {
+                "OpenConnect"
+            } else if daemons.contains("vpnc") {
+                "Cisco VPN (vpnc)"
+            } else if link.info_data["type"].as_str() == Some("tap") {
+                "TAP VPN"
+            } else {
+                "TUN VPN"
+            }
+        }
+        _ if link.link_type == "ppp" => "PPP (L2TP/PPTP)",
+        _ => return None,
+    };
+    Some(vpn_type.to_string())
+}
+
+fn running_process_names() -> BTreeSet<String> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return BTreeSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|e| std::fs::read_to_string(e.path().join("comm")).ok())
+        .map(|comm| comm.trim().to_string())
+        .collect()
+}
+
+/// The device the kernel would use to reach a destination
+pub fn route_device(target: &str) -> Option<String> {
+    let output = Command::new("ip")
+        .args(["-j", "route", "get", target])
+        .output()
+        .ok()?;
+    let routes: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    routes[0]["dev"].as_str().map(str::to_string)
+}
+
+/// WireGuard peers per interface from `wg show all dump` (requires root)
+fn wireguard_peers() -> BTreeMap<String, Vec<WireGuardPeer>> {
+    let mut peers: BTreeMap<String, Vec<WireGuardPeer>> = BTreeMap::new();
+    let Ok(output) = Command::new("wg").args(["show", "all", "dump"]).output() else {
+        return peers;
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        // Peer lines: iface pubkey psk endpoint allowed-ips handshake rx tx keepalive
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 9 {
+            continue;
+        }
+        let handshake: i64 = fields[5].parse().unwrap_or(0);
+        peers.entry(fields[0].to_string()).or_default().push(WireGuardPeer {
+            public_key: fields[1].to_string(),
+            endpoint: (fields[3] != "(none)").then(|| fields[3].to_string()),
+            allowed_ips: fields[4]
+                .split(',')
+                .filter(|ip| *ip != "(none)" && !ip.is_empty())
+                .map(str::to_string)
+                .collect(),
+            latest_handshake: (handshake > 0)
+                .then(|| Utc.timestamp_opt(handshake, 0).single())
+                .flatten(),
+            rx_bytes: fields[6].parse().unwrap_or(0),
+            tx_bytes: fields[7].parse().unwrap_or(0),
+        });
+    }
+
+    peers
+}
+
+/// Policy-based IPsec tunnels from `ip xfrm state`, one per remote gateway
+fn ipsec_tunnels(local_addresses: &BTreeSet<String>) -> Vec<VpnTunnel> {
+    let Ok(output) = Command::new("ip").args(["xfrm", "state"]).output() else {
+        return Vec::new();
+    };
+    let default_gateways = ipsec_default_gateways();
+
+    let mut remotes: BTreeMap<String, String> = BTreeMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        // "src 192.0.2.10 dst 198.51.100.1" starts each SA
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if let ["src", src, "dst", dst, ..] = tokens.as_slice() {
+            let (local, remote) = if local_addresses.contains(*dst) { (dst, src) } else { (src, dst) };
+            remotes.insert(remote.to_string(), local.to_string());
+        }
+    }
+
+    remotes
+        .into_iter()
+        .map(|(remote, local)| VpnTunnel {
+            name: format!("ipsec:{}", remote),
+            interface: None,
+            vpn_type: "IPsec (policy-based)".to_string(),
+            kind: None,
+            is_up: true,
+            addresses: vec![local],
+            carries_default_route: default_gateways.contains(&remote),
+            endpoint: Some(remote),
+            wireguard_peers: Vec::new(),
+        })
+        .collect()
+}
+
+/// Gateways of outbound IPsec policies that cover all destinations
+fn ipsec_default_gateways() -> BTreeSet<String> {
+    let mut gateways = BTreeSet::new();
+    let Ok(output) = Command::new("ip").args(["xfrm", "policy"]).output() else {
+        return gateways;
+    };
+
+    let mut covers_everything = false;
+    let mut outbound = false;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["src", _, "dst", dst, ..] if !line.starts_with(char::is_whitespace) => {
+                covers_everything = *dst == "0.0.0.0/0" || *dst == "::/0";
+                outbound = false;
+            }
+            ["dir", dir, ..] => outbound = *dir == "out",
+            ["tmpl", "src", _, "dst", gateway, ..] if covers_everything && outbound => {
+                gateways.insert(gateway.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    gateways
+}
+
+/// Human-readable byte count
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Public STUN servers a browser would contact to gather WebRTC candidates
+const STUN_TARGETS: &[&str] = &["74.125.250.129:19302", "[2001:4860:4864:5:8000::1]:19302"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Leak,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Pass => f.pad("✅ PASS"),
+            CheckStatus::Warn => f.pad("⚠️  WARN"),
+            CheckStatus::Leak => f.pad("❌ LEAK"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeakCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeakReport {
+    pub tunnels: Vec<VpnTunnel>,
+    pub checks: Vec<LeakCheck>,
+    /// Routes through the tunnel when the default route is not
+    pub tunnel_routes: Vec<String>,
+    /// Routes that send non-local prefixes around the tunnel
+    pub bypass_routes: Vec<String>,
+}
+
+impl LeakReport {
+    pub fn leaks(&self) -> usize {
+        self.checks.iter().filter(|c| c.status == CheckStatus::Leak).count()
+    }
+}
+
+/// What the kernel would do with traffic, relative to the active tunnels
+struct Egress<'a> {
+    tunnels: &'a [VpnTunnel],
+    /// A policy-based IPsec tunnel encrypts everything regardless of the route
+    ipsec_default: bool,
+}
+
+impl Egress<'_> {
+    fn is_tunnel_device(&self, device: &str) -> bool {
+        self.tunnels.iter().any(|t| t.is_up && t.interface.as_deref() == Some(device))
+    }
+
+    fn is_tunnel_address(&self, ip: &str) -> bool {
+        self.tunnels.iter().any(|t| t.is_up && t.addresses.iter().any(|a| a == ip))
+    }
+
+    /// Pass/leak verdict for traffic to a destination
+    fn check_route(&self, name: &str, target: &str) -> LeakCheck {
+        let (status, detail) = match route_device(target) {
+            Some(device) if self.is_tunnel_device(&device) => (CheckStatus::Pass, format!("{} egresses via {}", target, device)),
+            Some(device) if self.ipsec_default => {
+                (CheckStatus::Pass, format!("{} egresses via {}, covered by an IPsec policy", target, device))
+            }
+            Some(device) => (CheckStatus::Leak, format!("{} egresses via {}, outside the tunnel", target, device)),
+            None => (CheckStatus::Pass, format!("no route to {}", target)),
+        };
+        LeakCheck { name: name.to_string(), status, detail }
+    }
+}
+
+/// Verify that routes, DNS and UDP egress use the tunnel, without sending any traffic
+pub fn run_leak_test() -> Result<LeakReport> {
+    let status = network::get_vpn_status()?;
+    let tunnels: Vec<VpnTunnel> = status.tunnels.into_iter().filter(|t| t.is_up).collect();
+    let egress = Egress {
+        tunnels: &tunnels,
+        ipsec_default: tunnels.iter().any(|t| t.interface.is_none() && t.carries_default_route),
+    };
+    let mut checks = Vec::new();
+
+    checks.push(egress.check_route("IPv4 default route", PROBE_TARGET_V4));
+
+    // A VPN that only carries IPv4 leaves IPv6 going out the physical link
+    let mut ipv6 = egress.check_route("IPv6 default route", PROBE_TARGET_V6);
+    if ipv6.status == CheckStatus::Leak {
+        ipv6.detail = format!("IPv6 leak: {}", ipv6.detail);
+    }
+    checks.push(ipv6);
+
+    checks.extend(check_dns(&egress));
+    checks.extend(check_udp_paths(&egress));
+    checks.extend(check_host_candidates(&egress));
+
+    let (tunnel_routes, bypass_routes) = split_tunnel_routes(&egress);
+    if !bypass_routes.is_empty() {
+        checks.push(LeakCheck {
+            name: "Split tunnel".to_string(),
+            status: CheckStatus::Warn,
+            detail: format!("{} route(s) bypass the tunnel", bypass_routes.len()),
+        });
+    }
+
+    Ok(LeakReport {
+        tunnels,
+        checks,
+        tunnel_routes,
+        bypass_routes,
+    })
+}
+
+/// Nameservers in use, asking systemd-resolved for the upstreams behind its stub
+fn dns_servers() -> Vec<(String, Option<String>)> {
+    let configured: Vec<String> = std::fs::read_to_string("/etc/resolv.conf")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .map(|server| server.trim().to_string())
+        .collect();
+
+    if !configured.iter().any(|s| s == "127.0.0.53") {
+        return configured.into_iter().map(|s| (s, None)).collect();
+    }
+
+    // "Link 2 (eth0): 192.168.1.1 fe80::1"
+    let mut servers = Vec::new();
+    if let Ok(output) = Command::new("resolvectl").arg("dns").output() {
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some((scope, list)) = line.split_once(':') else {
+                continue;
+            };
+            let link = scope
+                .split_once('(')
+                .and_then(|(_, rest)| rest.strip_suffix(')'))
+                .map(str::to_string);
+            servers.extend(list.split_whitespace().map(|s| (s.to_string(), link.clone())));
+        }
+    }
+    servers
+}
+
+fn check_dns(egress: &Egress) -> Vec<LeakCheck> {
+    let servers = dns_servers();
+    if servers.is_empty() {
+        return vec![LeakCheck {
+            name: "DNS".to_string(),
+            status: CheckStatus::Warn,
+            detail: "no nameservers found".to_string(),
+        }];
+    }
+
+    servers
+        .into_iter()
+        .map(|(server, link)| {
+            let name = format!("DNS {}", server);
+            let ip: Option<std::net::IpAddr> = server.split('%').next().and_then(|s| s.parse().ok());
+            if ip.map(|ip| ip.is_loopback()).unwrap_or(false) {
+                return LeakCheck {
+                    name,
+                    status: CheckStatus::Warn,
+                    detail: "local resolver; its upstream servers cannot be checked".to_string(),
+                };
+            }
+            // systemd-resolved may query a link's servers directly over that link
+            if let Some(link) = link.filter(|l| !egress.is_tunnel_device(l)) {
+                if !egress.ipsec_default {
+                    return LeakCheck {
+                        name,
+                        status: CheckStatus::Leak,
+                        detail: format!("DNS leak: configured on {}, outside the tunnel", link),
+                    };
+                }
+            }
+            let mut check = egress.check_route(&name, &server);
+            if check.status == CheckStatus::Leak {
+                check.detail = format!("DNS leak: {}", check.detail);
+            }
+            check
+        })
+        .collect()
+}
+
+/// The source address the kernel picks for UDP to STUN servers, as WebRTC would use
+fn check_udp_paths(egress: &Egress) -> Vec<LeakCheck> {
+    STUN_TARGETS
+        .iter()
+        .map(|target| {
+            let name = format!("UDP to {}", target);
+            let bind = if target.starts_with('[') { "[::]:0" } else { "0.0.0.0:0" };
+            // connect() on UDP only selects a route and source address; nothing is sent
+            let source = std::net::UdpSocket::bind(bind)
+                .and_then(|socket| socket.connect(target).map(|_| socket))
+                .and_then(|socket| socket.local_addr());
+
+            let (status, detail) = match source {
+                Err(_) => (CheckStatus::Pass, "no path".to_string()),
+                Ok(addr) if egress.is_tunnel_address(&addr.ip().to_string()) => {
+                    (CheckStatus::Pass, format!("source {} is a tunnel address", addr.ip()))
+                }
+                Ok(addr) if egress.ipsec_default => {
+                    (CheckStatus::Pass, format!("source {}, covered by an IPsec policy", addr.ip()))
+                }
+                Ok(addr) => (CheckStatus::Leak, format!("WebRTC leak: STUN would see source {}", addr.ip())),
+            };
+            LeakCheck { name, status, detail }
+        })
+        .collect()
+}
+
+/// Public addresses outside the tunnel that WebRTC would offer as host candidates
+fn check_host_candidates(egress: &Egress) -> Vec<LeakCheck> {
+    let exposed: Vec<String> = network::get_interfaces()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|iface| iface.is_up && !egress.is_tunnel_device(&iface.name))
+        .flat_map(|iface| {
+            iface
+                .ip_addresses
+                .into_iter()
+                .filter(|ip| ip.parse().map(|ip| network::is_public_ip(&ip)).unwrap_or(false))
+                .map(move |ip| format!("{} ({})", ip, iface.name))
+        })
+        .collect();
+
+    if exposed.is_empty() {
+        return Vec::new();
+    }
+    vec![LeakCheck {
+        name: "WebRTC host candidates".to_string(),
+        status: CheckStatus::Warn,
+        detail: format!("browsers may reveal {}", exposed.join(", ")),
+    }]
+}
+
+/// Routes through the tunnel and routes that bypass it, excluding on-link, default and VPN endpoint routes
+fn split_tunnel_routes(egress: &Egress) -> (Vec<String>, Vec<String>) {
+    let endpoints: BTreeSet<String> = egress
+        .tunnels
+        .iter()
+        .filter_map(|t| t.endpoint.as_ref())
+        .flat_map(|e| e.split(", ").map(endpoint_host))
+        .collect();
+
+    let mut tunnel_routes = Vec::new();
+    let mut bypass_routes = Vec::new();
+    for family in ["-4", "-6"] {
+        let Ok(output) = Command::new("ip").args([family, "-j", "route", "show"]).output() else {
+            continue;
+        };
+        let routes: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+        for route in routes {
+            let (Some(dst), Some(dev)) = (route["dst"].as_str(), route["dev"].as_str()) else {
+                continue;
+            };
+            // Host routes to the VPN server itself are how a full tunnel reaches it
+            if dst == "default" || endpoints.contains(dst.trim_end_matches("/32").trim_end_matches("/128")) {
+                continue;
+            }
+            if egress.is_tunnel_device(dev) {
+                tunnel_routes.push(format!("{} dev {}", dst, dev));
+            } else if let Some(gateway) = route["gateway"].as_str() {
+                bypass_routes.push(format!("{} via {} dev {}", dst, gateway, dev));
+            }
+        }
+    }
+
+    (tunnel_routes, bypass_routes)
+}
+
+/// "198.51.100.1" from "198.51.100.1:51820" or "[2001:db8::1]:51820"
+fn endpoint_host(endpoint: &str) -> String {
+    match endpoint.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) && !host.ends_with(':') => {
+            host.trim_start_matches('[').trim_end_matches(']').to_string()
+        }
+        _ => endpoint.to_string(),
+    }
+}
+
+/// Run the leak test and print each check
+pub async fn show_leak_test() -> Result<()> {
+    println!("═══════════════════════════════════════════════════════════");
+    println!("               🔐  NetNinja VPN Leak Test  🔐              ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let report = tokio::task::spawn_blocking(run_leak_test).await??;
+
+    if report.tunnels.is_empty() {
+        println!("🔴 No active VPN tunnel found; all traffic uses the physical network");
+        println!();
+        return Ok(());
+    }
+    for tunnel in &report.tunnels {
+        println!("🔒 {} — {}", tunnel.name, tunnel.vpn_type);
+    }
+    println!();
+
+    for check in &report.checks {
+        println!("{:<10} {:<40} {}", check.status.to_string(), check.name, check.detail);
+    }
+
+    if !report.tunnel_routes.is_empty() {
+        println!("\n🔀 Routes through the tunnel");
+        println!("───────────────────────────────────────────────────────────");
+        for route in &report.tunnel_routes {
+            println!("   {}", route);
+        }
+    }
+    if !report.bypass_routes.is_empty() {
+        println!("\n↪️  Routes bypassing the tunnel");
+        println!("───────────────────────────────────────────────────────────");
+        for route in &report.bypass_routes {
+            println!("   {}", route);
+        }
+    }
+
+    println!();
+    match report.leaks() {
+        0 => println!("✅ No leaks detected"),
+        n => println!("❌ {} leak(s) detected", n),
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,782 @@
+use anyhow::{anyhow, Context, Result};
+use nix::errno::Errno;
+use nix::sys::socket::{self, sockopt, AddressFamily, MsgFlags, NetlinkAddr, SockFlag, SockProtocol, SockType};
+use nix::sys::time::TimeVal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Netlink and generic netlink framing
+const NLMSG_HDRLEN: usize = 16;
+const GENL_HDRLEN: usize = 4;
+const NLA_HDRLEN: usize = 4;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_DUMP: u16 = 0x300;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+
+const GENL_ID_CTRL: u16 = 16;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+const CTRL_ATTR_MCAST_GROUPS: u16 = 7;
+const CTRL_ATTR_MCAST_GRP_NAME: u16 = 1;
+const CTRL_ATTR_MCAST_GRP_ID: u16 = 2;
+
+// nl80211 commands and attributes (linux/nl80211.h)
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_STATION: u8 = 17;
+const NL80211_CMD_GET_SCAN: u8 = 32;
+const NL80211_CMD_TRIGGER_SCAN: u8 = 33;
+const NL80211_CMD_NEW_SCAN_RESULTS: u8 = 34;
+const NL80211_CMD_SCAN_ABORTED: u8 = 35;
+
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFNAME: u16 = 4;
+const NL80211_ATTR_MAC: u16 = 6;
+const NL80211_ATTR_STA_INFO: u16 = 21;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_BSS: u16 = 47;
+const NL80211_ATTR_SSID: u16 = 52;
+const NL80211_ATTR_WIPHY_TX_POWER_LEVEL: u16 = 98;
+const NL80211_ATTR_CHANNEL_WIDTH: u16 = 159;
+
+const NL80211_STA_INFO_SIGNAL: u16 = 7;
+const NL80211_STA_INFO_TX_BITRATE: u16 = 8;
+const NL80211_STA_INFO_SIGNAL_AVG: u16 = 13;
+const NL80211_STA_INFO_RX_BITRATE: u16 = 14;
+const NL80211_STA_INFO_CONNECTED_TIME: u16 = 16;
+const NL80211_RATE_INFO_BITRATE: u16 = 1;
+const NL80211_RATE_INFO_BITRATE32: u16 = 5;
+
+const NL80211_BSS_BSSID: u16 = 1;
+const NL80211_BSS_FREQUENCY: u16 = 2;
+const NL80211_BSS_CAPABILITY: u16 = 5;
+const NL80211_BSS_INFORMATION_ELEMENTS: u16 = 6;
+const NL80211_BSS_SIGNAL_MBM: u16 = 7;
+const NL80211_BSS_STATUS: u16 = 9;
+const NL80211_BSS_BEACON_IES: u16 = 11;
+const NL80211_BSS_STATUS_ASSOCIATED: u32 = 1;
+
+/// Capability bit set by networks that require encryption
+const CAPABILITY_PRIVACY: u16 = 0x0010;
+
+/// How long to wait for a reply from the kernel
+const REPLY_TIMEOUT_SECS: i64 = 5;
+
+/// How long a triggered scan may take before falling back to cached results
+const SCAN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The three non-overlapping 2.4 GHz channels
+const NON_OVERLAPPING_24GHZ: [u32; 3] = [1, 6, 11];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WirelessLink {
+    pub interface: String,
+    pub ssid: Option<String>,
+    pub bssid: Option<String>,
+    pub frequency: Option<u32>,
+    pub channel: Option<u32>,
+    pub channel_width: Option<String>,
+    pub signal_dbm: Option<i32>,
+    pub signal_avg_dbm: Option<i32>,
+    pub tx_bitrate_mbps: Option<f64>,
+    pub rx_bitrate_mbps: Option<f64>,
+    pub connected_secs: Option<u32>,
+    pub tx_power_dbm: Option<f64>,
+}
+
+impl WirelessLink {
+    pub fn is_associated(&self) -> bool {
+        self.bssid.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub bssid: String,
+    pub ssid: Option<String>,
+    pub frequency: u32,
+    pub channel: Option<u32>,
+    pub signal_dbm: Option<i32>,
+    pub security: String,
+    pub associated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelUsage {
+    pub band: String,
+    pub channel: u32,
+    pub networks: usize,
+    pub strongest_dbm: Option<i32>,
+    /// Networks on neighbouring 2.4 GHz channels that share spectrum with this one
+    pub overlapping: usize,
+}
+
+/// Link details plus a congestion hint from the kernel's cached scan results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WirelessSummary {
+    pub link: WirelessLink,
+    pub nearby_networks: usize,
+    /// Other networks on the current channel
+    pub same_channel: usize,
+    /// Networks on neighbouring 2.4 GHz channels
+    pub overlapping: usize,
+}
+
+/// Whether the kernel registers the interface as an 802.11 device
+pub fn is_wireless(interface: &str) -> bool {
+    Path::new("/sys/class/net").join(interface).join("wireless").exists()
+        || Path::new("/sys/class/net").join(interface).join("phy80211").exists()
+}
+
+/// Map a centre frequency in MHz to its 802.11 channel number
+pub fn frequency_to_channel(freq: u32) -> Option<u32> {
+    match freq {
+        2484 => Some(14),
+        2412..=2472 => Some((freq - 2407) / 5),
+        5955..=7115 => Some((freq - 5950) / 5),
+        5000..=5925 => Some((freq - 5000) / 5),
+        _ => None,
+    }
+}
+
+pub fn band(freq: u32) -> &'static str {
+    match freq {
+        2400..=2500 => "2.4 GHz",
+        5925..=7125 => "6 GHz",
+        4900..=5924 => "5 GHz",
+        _ => "other",
+    }
+}
+
+/// Rough description of a received signal level
+pub fn signal_quality(dbm: i32) -> &'static str {
+    match dbm {
+        d if d >= -50 => "excellent",
+        d if d >= -60 => "good",
+        d if d >= -70 => "fair",
+        d if d >= -80 => "weak",
+        _ => "unusable",
+    }
+}
+
+fn channel_width_name(width: u32) -> Option<&'static str> {
+    match width {
+        0 => Some("20 MHz (non-HT)"),
+        1 => Some("20 MHz"),
+        2 => Some("40 MHz"),
+        3 => Some("80 MHz"),
+        4 => Some("80+80 MHz"),
+        5 => Some("160 MHz"),
+        13 => Some("320 MHz"),
+        _ => None,
+    }
+}
+
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(buf: &[u8]) -> Option<u16> {
+    Some(u16::from_ne_bytes(buf.get(..2)?.try_into().ok()?))
+}
+
+fn read_u32(buf: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(buf.get(..4)?.try_into().ok()?))
+}
+
+/// Split a netlink attribute stream into (type, payload) pairs
+fn parse_attrs(buf: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attrs = Vec::new();
+    let mut offset = 0;
+    while offset + NLA_HDRLEN <= buf.len() {
+        let Some(len) = read_u16(&buf[offset..]).map(|l| l as usize) else { break };
+        if len < NLA_HDRLEN || offset + len > buf.len() {
+            break;
+        }
+        // Strip the nested and byte-order flag bits from the type
+        let kind = read_u16(&buf[offset + 2..]).unwrap_or(0) & 0x3fff;
+        attrs.push((kind, &buf[offset + NLA_HDRLEN..offset + len]));
+        offset += align(len);
+    }
+    attrs
+}
+
+fn attr<'a>(attrs: &[(u16, &'a [u8])], kind: u16) -> Option<&'a [u8]> {
+    attrs.iter().find(|(k, _)| *k == kind).map(|(_, v)| *v)
+}
+
+fn attr_u32(attrs: &[(u16, &[u8])], kind: u16) -> Option<u32> {
+    attr(attrs, kind).and_then(read_u32)
+}
+
+fn attr_string(attrs: &[(u16, &[u8])], kind: u16) -> Option<String> {
+    attr(attrs, kind).map(|v| String::from_utf8_lossy(v).trim_end_matches('\0').to_string())
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":")
+}
+
+/// Bitrate in Mbit/s from a nested rate info attribute (reported in 100 kbit/s units)
+fn parse_bitrate(rate: &[u8]) -> Option<f64> {
+    let attrs = parse_attrs(rate);
+    attr_u32(&attrs, NL80211_RATE_INFO_BITRATE32)
+        .or_else(|| attr(&attrs, NL80211_RATE_INFO_BITRATE).and_then(read_u16).map(u32::from))
+        .map(|r| r as f64 / 10.0)
+}
+
+/// A netlink message inside a received datagram
+struct NetlinkMessage<'a> {
+    kind: u16,
+    seq: u32,
+    payload: &'a [u8],
+}
+
+fn split_messages(buf: &[u8]) -> Vec<NetlinkMessage<'_>> {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+    while offset + NLMSG_HDRLEN <= buf.len() {
+        let len = read_u32(&buf[offset..]).unwrap_or(0) as usize;
+        if len < NLMSG_HDRLEN || offset + len > buf.len() {
+            break;
+        }
+        messages.push(NetlinkMessage {
+            kind: read_u16(&buf[offset + 4..]).unwrap_or(0),
+            seq: read_u32(&buf[offset + 8..]).unwrap_or(0),
+            payload: &buf[offset + NLMSG_HDRLEN..offset + len],
+        });
+        offset += align(len);
+    }
+    messages
+}
+
+/// Minimal generic netlink client for the nl80211 family
+struct Nl80211 {
+    fd: OwnedFd,
+    family: u16,
+    scan_group: Option<u32>,
+    seq: u32,
+}
+
+impl Nl80211 {
+    fn open() -> Result<Self> {
+        let fd = socket::socket(
+            AddressFamily::Netlink,
+            SockType::Raw,
+            SockFlag::SOCK_CLOEXEC,
+            SockProtocol::NetlinkGeneric,
+        )
+        .context("Failed to open generic netlink socket")?;
+        socket::bind(fd.as_raw_fd(), &NetlinkAddr::new(0, 0)).context("Failed to bind netlink socket")?;
+        socket::setsockopt(&fd, sockopt::ReceiveTimeout, &TimeVal::new(REPLY_TIMEOUT_SECS, 0))?;
+
+        let mut nl = Nl80211 { fd, family: GENL_ID_CTRL, scan_group: None, seq: 0 };
+        let replies = nl
+            .request(GENL_ID_CTRL, CTRL_CMD_GETFAMILY, false, &[(CTRL_ATTR_FAMILY_NAME, b"nl80211\0")])
+            .context("nl80211 is not available (no cfg80211 wireless support)")?;
+        let attrs = parse_attrs(replies.first().ok_or_else(|| anyhow!("Empty nl80211 family reply"))?);
+        nl.family = attr(&attrs, CTRL_ATTR_FAMILY_ID)
+            .and_then(read_u16)
+            .ok_or_else(|| anyhow!("nl80211 family reply has no id"))?;
+
+        for (_, group) in attr(&attrs, CTRL_ATTR_MCAST_GROUPS).map(parse_attrs).unwrap_or_default() {
+            let group = parse_attrs(group);
+            if attr_string(&group, CTRL_ATTR_MCAST_GRP_NAME).as_deref() == Some("scan") {
+                nl.scan_group = attr_u32(&group, CTRL_ATTR_MCAST_GRP_ID);
+            }
+        }
+        Ok(nl)
+    }
+
+    /// Send a request and collect the attribute payload of every reply
+    fn request(&mut self, family: u16, cmd: u8, dump: bool, attrs: &[(u16, &[u8])]) -> Result<Vec<Vec<u8>>> {
+        self.seq += 1;
+        let seq = self.seq;
+        let flags = NLM_F_REQUEST | if dump { NLM_F_DUMP } else { NLM_F_ACK };
+
+        let mut msg = vec![0u8; NLMSG_HDRLEN];
+        msg.extend_from_slice(&[cmd, 1, 0, 0]);
+        for (kind, value) in attrs {
+            msg.extend_from_slice(&((NLA_HDRLEN + value.len()) as u16).to_ne_bytes());
+            msg.extend_from_slice(&kind.to_ne_bytes());
+            msg.extend_from_slice(value);
+            msg.resize(align(msg.len()), 0);
+        }
+        let len = msg.len() as u32;
+        msg[0..4].copy_from_slice(&len.to_ne_bytes());
+        msg[4..6].copy_from_slice(&family.to_ne_bytes());
+        msg[6..8].copy_from_slice(&flags.to_ne_bytes());
+        msg[8..12].copy_from_slice(&seq.to_ne_bytes());
+        socket::send(self.fd.as_raw_fd(), &msg, MsgFlags::empty()).context("Failed to send netlink request")?;
+
+        let mut replies = Vec::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = socket::recv(self.fd.as_raw_fd(), &mut buf, MsgFlags::empty())
+                .context("No reply from nl80211")?;
+            for message in split_messages(&buf[..n]) {
+                // Skip multicast notifications and leftovers from earlier requests
+                if message.seq != seq {
+                    continue;
+                }
+                match message.kind {
+                    NLMSG_DONE => return Ok(replies),
+                    NLMSG_ERROR => {
+                        let errno = read_u32(message.payload).unwrap_or(0) as i32;
+                        if errno != 0 {
+                            return Err(Errno::from_raw(-errno).into());
+                        }
+                        return Ok(replies);
+                    }
+                    _ => replies.push(message.payload.get(GENL_HDRLEN..).unwrap_or_default().to_vec()),
+                }
+            }
+        }
+    }
+
+    fn subscribe_scan_events(&self) -> Result<()> {
+        let group = self.scan_group.ok_or_else(|| anyhow!("nl80211 has no scan multicast group"))?;
+        // nix has no wrapper for NETLINK_ADD_MEMBERSHIP
+        let ret = unsafe {
+            libc::setsockopt(
+                self.fd.as_raw_fd(),
+                libc::SOL_NETLINK,
+                libc::NETLINK_ADD_MEMBERSHIP,
+                &group as *const u32 as *const libc::c_void,
+                std::mem::size_of::<u32>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(std::io::Error::last_os_error()).context("Failed to join nl80211 scan group");
+        }
+        Ok(())
+    }
+
+    /// Wait for the scan started on `ifindex` to finish, returning false if it was aborted
+    fn wait_for_scan(&self, ifindex: u32) -> Result<bool> {
+        let deadline = Instant::now() + SCAN_TIMEOUT;
+        let mut buf = vec![0u8; 16 * 1024];
+        while Instant::now() < deadline {
+            let n = match socket::recv(self.fd.as_raw_fd(), &mut buf, MsgFlags::empty()) {
+                Ok(n) => n,
+                Err(Errno::EAGAIN) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for message in split_messages(&buf[..n]) {
+                if message.kind != self.family || message.payload.len() < GENL_HDRLEN {
+                    continue;
+                }
+                let attrs = parse_attrs(&message.payload[GENL_HDRLEN..]);
+                if attr_u32(&attrs, NL80211_ATTR_IFINDEX) != Some(ifindex) {
+                    continue;
+                }
+                match message.payload[0] {
+                    NL80211_CMD_NEW_SCAN_RESULTS => return Ok(true),
+                    NL80211_CMD_SCAN_ABORTED => return Ok(false),
+                    _ => {}
+                }
+            }
+        }
+        Err(anyhow!("Timed out waiting for scan results"))
+    }
+}
+
+/// Interface level details reported by GET_INTERFACE
+struct WirelessInterface {
+    name: String,
+    ifindex: u32,
+    ssid: Option<String>,
+    frequency: Option<u32>,
+    channel_width: Option<String>,
+    tx_power_dbm: Option<f64>,
+}
+
+fn get_wireless_interfaces(nl: &mut Nl80211) -> Result<Vec<WirelessInterface>> {
+    let family = nl.family;
+    let replies = nl.request(family, NL80211_CMD_GET_INTERFACE, true, &[])?;
+
+    Ok(replies
+        .iter()
+        .filter_map(|reply| {
+            let attrs = parse_attrs(reply);
+            Some(WirelessInterface {
+                name: attr_string(&attrs, NL80211_ATTR_IFNAME)?,
+                ifindex: attr_u32(&attrs, NL80211_ATTR_IFINDEX)?,
+                ssid: attr(&attrs, NL80211_ATTR_SSID).map(|s| String::from_utf8_lossy(s).to_string()),
+                frequency: attr_u32(&attrs, NL80211_ATTR_WIPHY_FREQ),
+                channel_width: attr_u32(&attrs, NL80211_ATTR_CHANNEL_WIDTH)
+                    .and_then(channel_width_name)
+                    .map(String::from),
+                tx_power_dbm: attr_u32(&attrs, NL80211_ATTR_WIPHY_TX_POWER_LEVEL).map(|mbm| mbm as f64 / 100.0),
+            })
+        })
+        .collect())
+}
+
+fn find_interface(nl: &mut Nl80211, interface: &str) -> Result<WirelessInterface> {
+    get_wireless_interfaces(nl)?
+        .into_iter()
+        .find(|w| w.name == interface)
+        .ok_or_else(|| anyhow!("{} is not a wireless interface", interface))
+}
+
+/// Names of every nl80211 interface
+pub fn list_interfaces() -> Result<Vec<String>> {
+    let mut nl = Nl80211::open()?;
+    Ok(get_wireless_interfaces(&mut nl)?.into_iter().map(|w| w.name).collect())
+}
+
+/// Current association of a wireless interface
+pub fn get_link(interface: &str) -> Result<WirelessLink> {
+    let mut nl = Nl80211::open()?;
+    let wiface = find_interface(&mut nl, interface)?;
+
+    let mut link = WirelessLink {
+        interface: wiface.name.clone(),
+        ssid: wiface.ssid.clone(),
+        bssid: None,
+        frequency: wiface.frequency,
+        channel: wiface.frequency.and_then(frequency_to_channel),
+        channel_width: wiface.channel_width.clone(),
+        signal_dbm: None,
+        signal_avg_dbm: None,
+        tx_bitrate_mbps: None,
+        rx_bitrate_mbps: None,
+        connected_secs: None,
+        tx_power_dbm: wiface.tx_power_dbm,
+    };
+
+    // In station mode the only station entry is the access point we are associated with
+    let family = nl.family;
+    let ifindex = wiface.ifindex.to_ne_bytes();
+    let stations = nl.request(family, NL80211_CMD_GET_STATION, true, &[(NL80211_ATTR_IFINDEX, &ifindex)])?;
+    if let Some(station) = stations.first() {
+        let attrs = parse_attrs(station);
+        link.bssid = attr(&attrs, NL80211_ATTR_MAC).map(format_mac);
+        let info = attr(&attrs, NL80211_ATTR_STA_INFO).map(parse_attrs).unwrap_or_default();
+        link.signal_dbm = attr(&info, NL80211_STA_INFO_SIGNAL).and_then(|v| v.first()).map(|&s| s as i8 as i32);
+        link.signal_avg_dbm = attr(&info, NL80211_STA_INFO_SIGNAL_AVG).and_then(|v| v.first()).map(|&s| s as i8 as i32);
+        link.tx_bitrate_mbps = attr(&info, NL80211_STA_INFO_TX_BITRATE).and_then(parse_bitrate);
+        link.rx_bitrate_mbps = attr(&info, NL80211_STA_INFO_RX_BITRATE).and_then(parse_bitrate);
+        link.connected_secs = attr_u32(&info, NL80211_STA_INFO_CONNECTED_TIME);
+    }
+
+    // Older kernels omit the SSID from GET_INTERFACE, the scan cache still has it
+    if link.ssid.is_none() && link.bssid.is_some() {
+        if let Ok(results) = dump_scan(&mut nl, wiface.ifindex) {
+            link.ssid = results.into_iter().find(|r| r.associated).and_then(|r| r.ssid);
+        }
+    }
+
+    Ok(link)
+}
+
+/// Describe the security of a network from its beacon capability and information elements
+fn describe_security(capability: u16, ies: &[u8]) -> String {
+    let mut rsn = None;
+    let mut wpa = false;
+
+    let mut i = 0;
+    while i + 2 <= ies.len() {
+        let (id, len) = (ies[i], ies[i + 1] as usize);
+        let Some(body) = ies.get(i + 2..i + 2 + len) else { break };
+        match id {
+            48 => rsn = Some(body),
+            221 if body.starts_with(&[0x00, 0x50, 0xf2, 0x01]) => wpa = true,
+            _ => {}
+        }
+        i += 2 + len;
+    }
+
+    if let Some(body) = rsn {
+        // version(2) group cipher(4) pairwise count(2) + suites, then AKM count(2) + suites
+        let pairwise = body.get(6..8).map(|c| u16::from_le_bytes([c[0], c[1]]) as usize).unwrap_or(0);
+        let akm_start = 8 + pairwise * 4;
+        let akm_count = body
+            .get(akm_start..akm_start + 2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]) as usize)
+            .unwrap_or(0);
+        let akms: Vec<u8> = (0..akm_count)
+            .filter_map(|n| body.get(akm_start + 2 + n * 4 + 3).copied())
+            .collect();
+
+        let has = |suite: u8| akms.contains(&suite);
+        return match () {
+            _ if has(8) && has(2) => "WPA2/WPA3".to_string(),
+            _ if has(8) => "WPA3".to_string(),
+            _ if has(18) => "OWE".to_string(),
+            _ if has(1) || has(5) || has(12) => "WPA2-Enterprise".to_string(),
+            _ if wpa => "WPA/WPA2".to_string(),
+            _ => "WPA2".to_string(),
+        };
+    }
+    if wpa {
+        "WPA".to_string()
+    } else if capability & CAPABILITY_PRIVACY != 0 {
+        "WEP".to_string()
+    } else {
+        "Open".to_string()
+    }
+}
+
+/// SSID from the first information element, None for hidden networks
+fn ssid_from_ies(ies: &[u8]) -> Option<String> {
+    if ies.first() != Some(&0) {
+        return None;
+    }
+    let len = *ies.get(1)? as usize;
+    let ssid = ies.get(2..2 + len)?;
+    if ssid.is_empty() || ssid.iter().all(|&b| b == 0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(ssid).to_string())
+}
+
+fn dump_scan(nl: &mut Nl80211, ifindex: u32) -> Result<Vec<ScanResult>> {
+    let family = nl.family;
+    let replies = nl.request(family, NL80211_CMD_GET_SCAN, true, &[(NL80211_ATTR_IFINDEX, &ifindex.to_ne_bytes())])?;
+
+    Ok(replies
+        .iter()
+        .filter_map(|reply| {
+            let attrs = parse_attrs(reply);
+            let bss = parse_attrs(attr(&attrs, NL80211_ATTR_BSS)?);
+            let frequency = attr_u32(&bss, NL80211_BSS_FREQUENCY)?;
+            let ies = attr(&bss, NL80211_BSS_INFORMATION_ELEMENTS)
+                .or_else(|| attr(&bss, NL80211_BSS_BEACON_IES))
+                .unwrap_or_default();
+            let capability = attr(&bss, NL80211_BSS_CAPABILITY).and_then(read_u16).unwrap_or(0);
+
+            Some(ScanResult {
+                bssid: format_mac(attr(&bss, NL80211_BSS_BSSID)?),
+                ssid: ssid_from_ies(ies),
+                frequency,
+                channel: frequency_to_channel(frequency),
+                signal_dbm: attr_u32(&bss, NL80211_BSS_SIGNAL_MBM).map(|mbm| mbm as i32 / 100),
+                security: describe_security(capability, ies),
+                associated: attr_u32(&bss, NL80211_BSS_STATUS) == Some(NL80211_BSS_STATUS_ASSOCIATED),
+            })
+        })
+        .collect())
+}
+
+/// Nearby networks, optionally triggering a fresh scan first (needs CAP_NET_ADMIN).
+/// Returns the results and whether they came from a fresh scan.
+pub fn scan(interface: &str, trigger: bool) -> Result<(Vec<ScanResult>, bool)> {
+    let mut nl = Nl80211::open()?;
+    let wiface = find_interface(&mut nl, interface)?;
+
+    let mut fresh = false;
+    if trigger {
+        // Subscribe before triggering so the completion event cannot be missed
+        nl.subscribe_scan_events()?;
+        let family = nl.family;
+        match nl.request(family, NL80211_CMD_TRIGGER_SCAN, false, &[(NL80211_ATTR_IFINDEX, &wiface.ifindex.to_ne_bytes())]) {
+            Ok(_) => fresh = nl.wait_for_scan(wiface.ifindex)?,
+            // Another scan is already running, its results will do
+            Err(e) if e.downcast_ref::<Errno>() == Some(&Errno::EBUSY) => fresh = nl.wait_for_scan(wiface.ifindex)?,
+            // Without privileges only the kernel's cached results are available
+            Err(e) if e.downcast_ref::<Errno>() == Some(&Errno::EPERM) => {}
+            Err(e) => return Err(e.context("Failed to trigger scan")),
+        }
+    }
+
+    let mut results = dump_scan(&mut nl, wiface.ifindex)?;
+    results.sort_by_key(|r| std::cmp::Reverse(r.signal_dbm.unwrap_or(i32::MIN)));
+    Ok((results, fresh))
+}
+
+/// Number of networks per channel, strongest first within each band
+pub fn channel_usage(results: &[ScanResult]) -> Vec<ChannelUsage> {
+    let mut channels: BTreeMap<(u32, u32), ChannelUsage> = BTreeMap::new();
+    for result in results {
+        let Some(channel) = result.channel else { continue };
+        let usage = channels.entry((result.frequency, channel)).or_insert_with(|| ChannelUsage {
+            band: band(result.frequency).to_string(),
+            channel,
+            networks: 0,
+            strongest_dbm: None,
+            overlapping: 0,
+        });
+        usage.networks += 1;
+        usage.strongest_dbm = usage.strongest_dbm.max(result.signal_dbm);
+    }
+
+    // 2.4 GHz channels are 22 MHz wide on a 5 MHz grid, so anything within four channels overlaps
+    let mut usage: Vec<ChannelUsage> = channels.into_values().collect();
+    let narrowband: Vec<(u32, usize)> = usage
+        .iter()
+        .filter(|u| u.band == "2.4 GHz")
+        .map(|u| (u.channel, u.networks))
+        .collect();
+    for entry in usage.iter_mut().filter(|u| u.band == "2.4 GHz") {
+        entry.overlapping = narrowband
+            .iter()
+            .filter(|(c, _)| *c != entry.channel && c.abs_diff(entry.channel) <= 4)
+            .map(|(_, n)| n)
+            .sum();
+    }
+    usage
+}
+
+/// The least crowded of channels 1, 6 and 11
+pub fn recommend_24ghz_channel(usage: &[ChannelUsage]) -> u32 {
+    let load = |channel: u32| -> usize {
+        usage
+            .iter()
+            .filter(|u| u.band == "2.4 GHz" && u.channel.abs_diff(channel) <= 4)
+            .map(|u| u.networks)
+            .sum()
+    };
+    NON_OVERLAPPING_24GHZ.into_iter().min_by_key(|&c| load(c)).unwrap_or(1)
+}
+
+/// Link details with a congestion hint, without triggering a scan
+pub fn summarize(interface: &str) -> Result<WirelessSummary> {
+    let link = get_link(interface)?;
+    let results = scan(interface, false).map(|(results, _)| results).unwrap_or_default();
+
+    let current_band = link.frequency.map(band);
+    let usage = channel_usage(&results);
+    let current = usage
+        .iter()
+        .find(|u| Some(u.channel) == link.channel && Some(u.band.as_str()) == current_band);
+
+    Ok(WirelessSummary {
+        nearby_networks: results.len(),
+        same_channel: current.map(|u| u.networks.saturating_sub(1)).unwrap_or(0),
+        overlapping: current.map(|u| u.overlapping).unwrap_or(0),
+        link,
+    })
+}
+
+/// Print the association details of a link, shared with the status report
+pub fn print_link(link: &WirelessLink) {
+    if !link.is_associated() {
+        println!("Wi-Fi: 🔴 not associated");
+        return;
+    }
+    println!("SSID: {}", link.ssid.as_deref().unwrap_or("(hidden)"));
+    println!("BSSID: {}", link.bssid.as_deref().unwrap_or("unknown"));
+    if let Some(freq) = link.frequency {
+        let channel = link.channel.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string());
+        let width = link.channel_width.as_deref().map(|w| format!(", {}", w)).unwrap_or_default();
+        println!("Frequency: {} MHz ({}, channel {}{})", freq, band(freq), channel, width);
+    }
+    if let Some(signal) = link.signal_dbm {
+        let average = link.signal_avg_dbm.map(|a| format!(", avg {} dBm", a)).unwrap_or_default();
+        let icon = match signal_quality(signal) {
+            "excellent" | "good" => "🟢",
+            "fair" => "🟡",
+            _ => "🔴",
+        };
+        println!("Signal: {} {} dBm ({}{})", icon, signal, signal_quality(signal), average);
+    }
+    match (link.tx_bitrate_mbps, link.rx_bitrate_mbps) {
+        (None, None) => {}
+        (tx, rx) => println!(
+            "Bitrate: tx {} / rx {}",
+            tx.map(|r| format!("{:.1} Mbit/s", r)).unwrap_or_else(|| "-".to_string()),
+            rx.map(|r| format!("{:.1} Mbit/s", r)).unwrap_or_else(|| "-".to_string())
+        ),
+    }
+    if let Some(secs) = link.connected_secs {
+        println!("Connected: {}h {}m", secs / 3600, (secs % 3600) / 60);
+    }
+    if let Some(power) = link.tx_power_dbm {
+        println!("TX Power: {:.0} dBm", power);
+    }
+}
+
+/// Show the wireless link and, optionally, nearby networks and channel congestion
+pub async fn show_wifi(interface: Option<String>, do_scan: bool) -> Result<()> {
+    let interface = match interface {
+        Some(name) => name,
+        None => {
+            let primary = crate::network::get_primary_interface().ok().map(|i| i.name);
+            match primary.filter(|name| is_wireless(name)) {
+                Some(name) => name,
+                None => list_interfaces()?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow!("No wireless interfaces found"))?,
+            }
+        }
+    };
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              📶  NetNinja Wireless Report  📶             ");
+    println!("═══════════════════════════════════════════════════════════\n");
+
+    let link_interface = interface.clone();
+    let link = tokio::task::spawn_blocking(move || get_link(&link_interface)).await??;
+    println!("Interface: {}", link.interface);
+    print_link(&link);
+    println!();
+
+    if !do_scan {
+        return Ok(());
+    }
+
+    println!("Scanning for nearby networks on {}...\n", interface);
+    let (results, fresh) = tokio::task::spawn_blocking(move || scan(&interface, true)).await??;
+    if !fresh {
+        println!("⚠️  Could not start a scan (needs root), showing the kernel's cached results\n");
+    }
+    if results.is_empty() {
+        println!("No networks found");
+        println!();
+        return Ok(());
+    }
+
+    println!("{:<2} {:<32} {:<18} {:<8} {:<8} {:<8} {:<12}", "", "SSID", "BSSID", "Band", "Channel", "Signal", "Security");
+    println!("{}", "─".repeat(94));
+    for result in &results {
+        let ssid: String = result.ssid.as_deref().unwrap_or("(hidden)").chars().take(31).collect();
+        println!(
+            "{:<2} {:<32} {:<18} {:<8} {:<8} {:<8} {:<12}",
+            if result.associated { "*" } else { "" },
+            ssid,
+            result.bssid,
+            band(result.frequency),
+            result.channel.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+            result.signal_dbm.map(|s| format!("{} dBm", s)).unwrap_or_else(|| "-".to_string()),
+            result.security
+        );
+    }
+    println!("\n{} network(s)\n", results.len());
+
+    println!("📊 CHANNEL CONGESTION");
+    println!("───────────────────────────────────────────────────────────");
+    let usage = channel_usage(&results);
+    println!("{:<8} {:<8} {:<10} {:<12} {:<12}", "Band", "Channel", "Networks", "Overlapping", "Strongest");
+    println!("{}", "─".repeat(54));
+    for entry in &usage {
+        println!(
+            "{:<8} {:<8} {:<10} {:<12} {:<12}",
+            entry.band,
+            entry.channel,
+            entry.networks,
+            if entry.band == "2.4 GHz" { entry.overlapping.to_string() } else { "-".to_string() },
+            entry.strongest_dbm.map(|s| format!("{} dBm", s)).unwrap_or_else(|| "-".to_string())
+        );
+    }
+    if usage.iter().any(|u| u.band == "2.4 GHz") {
+        println!("\n💡 Least congested 2.4 GHz channel: {}", recommend_24ghz_channel(&usage));
+    }
+    if let Some(current) = link.channel {
+        let current_band = link.frequency.map(band);
+        if let Some(entry) = usage.iter().find(|u| u.channel == current && Some(u.band.as_str()) == current_band) {
+            let sharing = entry.networks.saturating_sub(1) + entry.overlapping;
+            println!("Current channel {} is shared with {} other network(s)", current, sharing);
+        }
+    }
+    println!();
+
+    Ok(())
+}